@@ -0,0 +1,26 @@
+//! A chat bot's side of the solver, with the chat left out: each line on
+//! stdin is a channel and a slash command, e.g. "general /guess crane
+//! gybbg", and the reply is printed. A Discord or Slack bot would call
+//! `handle` the same way with each slash command's channel, sharing one
+//! solver between all of them.
+//!
+//! ```text
+//! cargo run --release --example bot
+//! ```
+
+use std::io::{self, BufRead};
+use wordlesolve::{read_words, Minimax, SessionManager, Solver, ALLOWED, GOALS};
+
+fn main() -> io::Result<()> {
+    let goals = read_words(GOALS.as_bytes(), "goals", 5, None)?;
+    let mut dict = read_words(ALLOWED.as_bytes(), "guesses", 5, None)?;
+    dict.extend(&goals);
+    let strategy = Minimax { progress_every: usize::MAX, ..Minimax::default() };
+    let mut sessions = SessionManager::new(Solver::new(goals, dict).with_matrix(), Box::new(strategy));
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let Some((channel, command)) = line.trim().split_once(' ') else { continue };
+        println!("{}: {}", channel, sessions.handle(channel, command));
+    }
+    Ok(())
+}
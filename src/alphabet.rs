@@ -0,0 +1,165 @@
+use std::str;
+use std::sync::OnceLock;
+
+// A set of symbols, a bit for each.
+pub(crate) type LetterMask = u128;
+
+/// Most symbols an alphabet can have, one per bit of a letter mask: enough
+/// for syllabaries like kana as well as alphabets.
+pub const MAX_SYMBOLS: usize = LetterMask::BITS as usize;
+
+static INSTALLED: OnceLock<Alphabet> = OnceLock::new();
+
+/// The symbols words are spelled with, in order, each mapped to a bit
+/// position in letter masks. English a–z unless another is installed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: Vec<char>,
+    fold_accents: bool,
+}
+
+impl Alphabet {
+    /// The 26 lowercase ASCII letters.
+    pub fn english() -> Self {
+        Self { symbols: ('a'..='z').collect(), fold_accents: false }
+    }
+
+    /// This alphabet, reading letters with accents it doesn't have as the
+    /// letters without them, e.g. é as e but ñ as itself if it's a symbol,
+    /// so words match whatever their accents. A word is still shown as
+    /// it was first spelled; see [`Word`](crate::Word).
+    pub fn folding_accents(self) -> Self {
+        Self { fold_accents: true, ..self }
+    }
+
+    /// Whether accents are folded; see [`folding_accents`](Alphabet::folding_accents).
+    pub fn folds_accents(&self) -> bool {
+        self.fold_accents
+    }
+
+    /// How many symbols there are.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Whether there are no symbols, which can't be parsed or installed.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// The bit position for `c`, if it's in the alphabet.
+    pub fn index(&self, c: char) -> Option<u8> {
+        self.symbols.iter().position(|&s| s == c).map(|i| i as u8)
+    }
+
+    /// The bit position `c` is read as: its own, or with accents folded,
+    /// its unaccented letter's.
+    pub fn lookup(&self, c: char) -> Option<u8> {
+        self.index(c).or_else(|| unaccented(c).filter(|_| self.fold_accents).and_then(|c| self.index(c)))
+    }
+
+    /// The symbol at bit position `i`. Panics if there's no such symbol.
+    pub fn symbol(&self, i: u8) -> char {
+        self.symbols[i as usize]
+    }
+
+    /// The symbols, in bit order.
+    pub fn symbols(&self) -> &[char] {
+        &self.symbols
+    }
+
+    /// Whether words are written right to left, as Hebrew's and Arabic's
+    /// are: whether most symbols come from those scripts. Words are still
+    /// kept first letter first; only drawing them goes the other way.
+    pub fn is_rtl(&self) -> bool {
+        2 * self.symbols.iter().filter(|&&c| is_rtl_letter(c)).count() > self.symbols.len()
+    }
+
+    /// Makes this the alphabet every [`Word`](crate::Word) and
+    /// [`Pattern`](crate::Pattern) is read and written with. It can only be
+    /// installed once, before anything uses [`alphabet`]; otherwise it's
+    /// handed back.
+    pub fn install(self) -> Result<(), Alphabet> {
+        INSTALLED.set(self)
+    }
+}
+
+/// Reads the symbols in order, e.g. "abcdefghijklmnñopqrstuvwxyz". They must
+/// be distinct, lowercase and at most [`MAX_SYMBOLS`].
+impl str::FromStr for Alphabet {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        let symbols: Vec<char> = text.chars().collect();
+        if symbols.is_empty() || symbols.len() > MAX_SYMBOLS {
+            return Err(format!("an alphabet needs 1 to {} symbols", MAX_SYMBOLS));
+        }
+        for (i, &c) in symbols.iter().enumerate() {
+            if symbols[..i].contains(&c) { return Err(format!("{:?} is in the alphabet twice", c)) }
+            if c.is_uppercase() || !c.is_alphabetic() { return Err(format!("{:?} isn't a lowercase letter", c)) }
+        }
+        Ok(Self { symbols, fold_accents: false })
+    }
+}
+
+// Latin letters and their accented forms.
+const ACCENTED: [(char, &str); 19] = [
+    ('a', "áàâäãåāăą"), ('c', "çćĉċč"), ('d', "ďđ"), ('e', "éèêëēĕėęě"), ('g', "ĝğġģ"), ('h', "ĥħ"), ('i', "íìîïĩīĭįı"),
+    ('j', "ĵ"), ('k', "ķ"), ('l', "ĺļľŀł"), ('n', "ñńņň"), ('o', "óòôöõøōŏő"), ('r', "ŕŗř"), ('s', "śŝşšș"),
+    ('t', "ţťŧț"), ('u', "úùûüũūŭůűų"), ('w', "ŵ"), ('y', "ýÿŷ"), ('z', "źżž"),
+];
+
+// The letter `c` is an accented form of, if it is one.
+fn unaccented(c: char) -> Option<char> {
+    ACCENTED.iter().find(|(_, forms)| forms.contains(c)).map(|&(base, _)| base)
+}
+
+// Letters of the Hebrew, Arabic, Syriac and Thaana blocks and their
+// presentation forms.
+fn is_rtl_letter(c: char) -> bool {
+    matches!(c, '\u{590}'..='\u{7bf}' | '\u{860}'..='\u{8ff}' | '\u{fb1d}'..='\u{fdff}' | '\u{fe70}'..='\u{feff}')
+}
+
+/// Whether `c` is an invisible mark setting the direction of the text
+/// around it, as pasting right-to-left words often brings along. Words are
+/// parsed without them.
+pub fn is_direction_mark(c: char) -> bool {
+    matches!(c, '\u{200e}' | '\u{200f}' | '\u{61c}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
+
+/// The installed alphabet; see [`Alphabet::install`].
+pub fn alphabet() -> &'static Alphabet {
+    INSTALLED.get_or_init(Alphabet::english)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tells_which_way_words_go() {
+        assert!(!Alphabet::english().is_rtl());
+        assert!("אבגדהוזחטיכךלמםנןסעפףצץקרשת".parse::<Alphabet>().unwrap().is_rtl());
+        assert!("ابتثجحخدذرزسشصضطظعغفقكلمنهوي".parse::<Alphabet>().unwrap().is_rtl());
+        assert!(is_direction_mark('\u{200f}') && !is_direction_mark('a'));
+    }
+
+    #[test]
+    fn folds_accents_it_lacks() {
+        let spanish: Alphabet = "abcdefghijklmnñopqrstuvwxyz".parse().unwrap();
+        assert_eq!(spanish.lookup('é'), None);
+        let spanish = spanish.folding_accents();
+        assert_eq!(spanish.lookup('é'), spanish.index('e'));
+        assert_eq!(spanish.lookup('ñ'), Some(14));
+        assert_eq!(spanish.lookup('ń'), spanish.index('n'));
+        assert_eq!(spanish.lookup('ß'), None);
+    }
+
+    #[test]
+    fn holds_syllabaries() {
+        let hiragana: String = ('\u{3041}'..='\u{3096}').collect();
+        assert_eq!(hiragana.parse::<Alphabet>().unwrap().len(), 86);
+        let too_many: String = ('\u{4e00}'..).take(MAX_SYMBOLS + 1).collect();
+        assert!(too_many.parse::<Alphabet>().is_err());
+    }
+}
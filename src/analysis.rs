@@ -0,0 +1,151 @@
+use crate::alphabet::MAX_SYMBOLS;
+use crate::outcome::{LetterOutcome, Outcome};
+use crate::pattern::Pattern;
+use crate::priors::Priors;
+use crate::word::{to_letter_mask, Word};
+use std::collections::HashMap;
+
+/// Letter grade from the relative regret of a guess's confidence against the
+/// recommended guess's confidence, as returned by
+/// [`guess_confidence`](crate::guess_confidence).
+pub fn grade(confidence: i32, recommended_confidence: i32) -> char {
+    let regret = (recommended_confidence - confidence) as f64 / -recommended_confidence as f64;
+    match regret {
+        r if r <= 0.0 => 'A',
+        r if r <= 0.5 => 'B',
+        r if r <= 1.0 => 'C',
+        r if r <= 2.0 => 'D',
+        _ => 'F',
+    }
+}
+
+/// Size of the largest set of goals sharing one outcome for this guess.
+pub fn worst_bucket(guess: Word, goals: &[Word]) -> usize {
+    let mut buckets: HashMap<Outcome, usize> = HashMap::new();
+    for &goal in goals { *buckets.entry(Outcome::compare(goal, guess)).or_insert(0) += 1; }
+    buckets.into_values().max().unwrap_or(0)
+}
+
+/// How many goals give each outcome for this guess, indexed by [`Outcome::code`].
+pub fn outcome_counts(guess: Word, goals: &[Word]) -> Vec<u32> {
+    let mut counts = vec![0; Outcome::states(guess.len())];
+    for &goal in goals { counts[Outcome::compare(goal, guess).code() as usize] += 1; }
+    counts
+}
+
+/// The outcome an adversarial host like Absurdle answers `guess` with: the
+/// one shared by the most goals, ties going to the lowest
+/// [`code`](Outcome::code), which has the fewest hints up front.
+pub fn adversarial_outcome(guess: Word, goals: &[Word]) -> Outcome {
+    let counts = outcome_counts(guess, goals);
+    let code = (0..counts.len()).max_by_key(|&c| (counts[c], std::cmp::Reverse(c))).unwrap();
+    Outcome::from_code(code as u16, guess.len())
+}
+
+/// Shannon entropy in bits of the outcome of this guess, when the answer is
+/// drawn uniformly from the goals.
+pub fn outcome_entropy(guess: Word, goals: &[Word]) -> f64 {
+    let n = goals.len() as f64;
+    outcome_counts(guess, goals).iter().filter(|&&c| c > 0).map(|&c| {
+        let p = c as f64 / n;
+        -p * p.log2()
+    }).sum()
+}
+
+/// Expected number of goals left after this guess, when the answer is drawn
+/// uniformly from the goals.
+pub fn expected_remaining(guess: Word, goals: &[Word]) -> f64 {
+    let sum_of_squares: u64 = outcome_counts(guess, goals).iter().map(|&c| c as u64 * c as u64).sum();
+    sum_of_squares as f64 / goals.len().max(1) as f64
+}
+
+// Probability mass of the goals giving each outcome for this guess, indexed
+// by code.
+fn outcome_masses(guess: Word, goals: &[Word], priors: &Priors) -> Vec<f64> {
+    let mut masses = vec![0.0; Outcome::states(guess.len())];
+    for (&goal, p) in goals.iter().zip(priors.probabilities(goals)) { masses[Outcome::compare(goal, guess).code() as usize] += p; }
+    masses
+}
+
+/// [`outcome_entropy`] when the answer is drawn from the goals by `priors`.
+pub fn weighted_entropy(guess: Word, goals: &[Word], priors: &Priors) -> f64 {
+    outcome_masses(guess, goals, priors).iter().filter(|&&p| p > 0.0).map(|&p| -p * p.log2()).sum()
+}
+
+/// [`expected_remaining`] when the answer is drawn from the goals by
+/// `priors`: each bucket's size weighted by its probability.
+pub fn weighted_remaining(guess: Word, goals: &[Word], priors: &Priors) -> f64 {
+    outcome_masses(guess, goals, priors).iter().zip(outcome_counts(guess, goals)).map(|(&p, c)| p * c as f64).sum()
+}
+
+/// A quick opener: the goal word with the smallest [`worst_bucket`].
+pub fn best_opener(goals: &[Word]) -> (Word, usize) {
+    goals.iter().map(|&g| (g, worst_bucket(g, goals))).min_by_key(|&(g, n)| (n, g)).unwrap()
+}
+
+/// How many goals have each letter at each position, up to the longest goal.
+/// Indexed by position, then by the letter's index in the
+/// [`alphabet`](crate::alphabet).
+pub fn positional_counts(goals: &[Word]) -> Vec<[u32; MAX_SYMBOLS]> {
+    let mut counts = vec![[0; MAX_SYMBOLS]; goals.iter().map(Word::len).max().unwrap_or(0)];
+    for g in goals {
+        for (i, &s) in g.symbols().iter().enumerate() { counts[i][s as usize] += 1; }
+    }
+    counts
+}
+
+/// P(letter at position i) when the answer is drawn uniformly from the goals.
+/// Indexed like [`positional_counts`].
+pub fn letter_probabilities(goals: &[Word]) -> Vec<[f64; MAX_SYMBOLS]> {
+    let mut probabilities = vec![[0.0; MAX_SYMBOLS]; goals.iter().map(Word::len).max().unwrap_or(0)];
+    for (p, c) in probabilities.iter_mut().zip(positional_counts(goals)) {
+        for (p, c) in p.iter_mut().zip(c) { *p = c as f64 / goals.len() as f64; }
+    }
+    probabilities
+}
+
+/// P(letter appears anywhere in the answer) when the answer is drawn uniformly
+/// from the goals, indexed by the letter's index in the
+/// [`alphabet`](crate::alphabet).
+pub fn letter_presence(goals: &[Word]) -> [f64; MAX_SYMBOLS] {
+    let mut presence = [0.0; MAX_SYMBOLS];
+    for g in goals {
+        for (l, p) in presence.iter_mut().enumerate() {
+            if g.letters & to_letter_mask(l as u8) != 0 { *p += 1.0; }
+        }
+    }
+    for p in &mut presence { *p /= goals.len().max(1) as f64; }
+    presence
+}
+
+/// A burner guess: the one in `dict` with the most letters `pattern` says
+/// nothing about yet, whether or not it could be the answer. With
+/// `weighted`, each letter counts [`letter_presence`] among the goals, so
+/// letters none of them has count for nothing; either way, ties go to the
+/// other measure, then alphabetically.
+pub fn burner_guess(pattern: &Pattern, goals: &[Word], dict: &[Word], weighted: bool) -> Option<Word> {
+    let presence = letter_presence(goals);
+    let known = pattern.positive_letters | pattern.negative_letters;
+    let score = |w: Word| {
+        let new = w.letters & !known;
+        let weight: f64 = (0..MAX_SYMBOLS).filter(|&l| new & to_letter_mask(l as u8) != 0).map(|l| presence[l]).sum();
+        (new.count_ones() as f64, weight)
+    };
+    dict.iter().copied().map(|w| (w, score(w))).max_by(|(a, (a_new, a_weight)), (b, (b_new, b_weight))| {
+        let (a_key, b_key) = if weighted { ((a_weight, a_new), (b_weight, b_new)) } else { ((a_new, a_weight), (b_new, b_weight)) };
+        a_key.0.total_cmp(b_key.0).then(a_key.1.total_cmp(b_key.1)).then(b.cmp(a))
+    }).map(|(w, _)| w)
+}
+
+/// Average greens and yellows the guess would score against the goals.
+pub fn expected_tiles(guess: Word, goals: &[Word]) -> (f64, f64) {
+    let (mut greens, mut yellows) = (0, 0);
+    for &goal in goals {
+        let outcome = Outcome::compare(goal, guess);
+        let outcome = outcome.tiles();
+        greens += outcome.iter().filter(|&&o| o == LetterOutcome::Here).count();
+        yellows += outcome.iter().filter(|&&o| o == LetterOutcome::Elsewhere).count();
+    }
+    let n = goals.len().max(1) as f64;
+    (greens as f64 / n, yellows as f64 / n)
+}
@@ -0,0 +1,129 @@
+use crate::cache::fnv;
+use crate::solver::{score_all, Solver};
+use crate::strategy::Strategy;
+use crate::word::Word;
+use std::time::{Duration, Instant};
+
+/// How a strategy did autoplaying a list of goals.
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    /// Each goal with the guesses it took, or `None` if it wasn't solved.
+    pub results: Vec<(Word, Option<usize>)>,
+
+    /// The words guessed for each goal and how long its game took, in the
+    /// same order.
+    pub games: Vec<(Vec<Word>, Duration)>,
+}
+
+impl BenchReport {
+    /// How many goals took each number of guesses, indexed by guess count.
+    pub fn histogram(&self) -> Vec<usize> {
+        let mut histogram = vec![0; self.worst().unwrap_or(0) + 1];
+        for &(_, n) in &self.results {
+            if let Some(n) = n { histogram[n] += 1; }
+        }
+        histogram
+    }
+
+    /// Mean guesses over the goals that were solved.
+    pub fn mean(&self) -> f64 {
+        let solved: Vec<usize> = self.results.iter().filter_map(|r| r.1).collect();
+        solved.iter().sum::<usize>() as f64 / solved.len().max(1) as f64
+    }
+
+    /// Most guesses any solved goal took.
+    pub fn worst(&self) -> Option<usize> {
+        self.results.iter().filter_map(|r| r.1).max()
+    }
+
+    /// The `n` goals that took the most guesses, unsolved ones first, ties in
+    /// word order.
+    pub fn hardest(&self, n: usize) -> Vec<(Word, Option<usize>)> {
+        let mut results = self.results.clone();
+        results.sort_by_key(|&(goal, guesses)| (guesses.map(std::cmp::Reverse), goal));
+        results.truncate(n);
+        results
+    }
+
+    /// Goals that weren't solved within `limit` guesses.
+    pub fn failures(&self, limit: usize) -> Vec<Word> {
+        self.results.iter().filter(|r| r.1.is_none_or(|n| n > limit)).map(|r| r.0).collect()
+    }
+
+    /// The share of goals in [`failures`](BenchReport::failures), lost games
+    /// that [`mean`](BenchReport::mean) still counts if they were solved
+    /// later; 0 with no goals.
+    pub fn failure_rate(&self, limit: usize) -> f64 {
+        self.failures(limit).len() as f64 / self.results.len().max(1) as f64
+    }
+}
+
+/// How a strategy whose guesses are random did over several trials of every
+/// goal.
+#[derive(Debug, Clone)]
+pub struct SimulationReport {
+    /// Each trial, in order.
+    pub trials: Vec<BenchReport>,
+}
+
+impl SimulationReport {
+    /// Every game of every trial as one report, the trials one after another.
+    pub fn pooled(&self) -> BenchReport {
+        BenchReport {
+            results: self.trials.iter().flat_map(|t| t.results.iter().copied()).collect(),
+            games: self.trials.iter().flat_map(|t| t.games.iter().cloned()).collect(),
+        }
+    }
+
+    /// Mean guesses over the solved games of every trial.
+    pub fn mean(&self) -> f64 {
+        self.pooled().mean()
+    }
+
+    /// A 95% confidence interval on [`mean`](SimulationReport::mean), from
+    /// how much the trials' means vary, by the normal approximation, so it
+    /// may be too narrow for a few trials. Just the mean for one.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let means: Vec<f64> = self.trials.iter().map(BenchReport::mean).collect();
+        let mean = self.mean();
+        if means.len() < 2 { return (mean, mean) }
+        let n = means.len() as f64;
+        let average = means.iter().sum::<f64>() / n;
+        let variance = means.iter().map(|m| (m - average) * (m - average)).sum::<f64>() / (n - 1.0);
+        let margin = 1.96 * (variance / n).sqrt();
+        (mean - margin, mean + margin)
+    }
+}
+
+/// Autoplays every candidate of `solver` as the goal `trials` times, like
+/// [`bench`], with a fresh strategy from `make` for each game. Each is
+/// seeded from `seed`, the trial and the goal, so the games are the same
+/// however they're spread over cores.
+pub fn simulate(solver: &Solver, make: impl Fn(u64) -> Box<dyn Strategy> + Sync, trials: usize, limit: usize, seed: u64) -> SimulationReport {
+    let goals: Vec<(usize, Word)> = solver.candidates().iter().copied().enumerate().collect();
+    let trials = (0..trials as u64).map(|trial| {
+        let played = score_all(&goals, usize::MAX, |(i, goal)| {
+            let start = Instant::now();
+            let strategy = make(fnv([seed, trial, i as u64].iter().flat_map(|n| n.to_le_bytes())));
+            let mut game = solver.clone();
+            let solved = game.play(goal, strategy.as_ref(), limit);
+            (solved, game.history().iter().map(|&(guess, _)| guess).collect(), start.elapsed())
+        });
+        let (results, games) = played.into_iter().map(|((_, goal), (solved, guesses, time))| ((goal, solved), (guesses, time))).unzip();
+        BenchReport { results, games }
+    }).collect();
+    SimulationReport { trials }
+}
+
+/// Autoplays every candidate of `solver` as the goal with
+/// [`Solver::play`], giving up after `limit` guesses, on all available cores.
+pub fn bench(solver: &Solver, strategy: &dyn Strategy, limit: usize) -> BenchReport {
+    let played = score_all(solver.candidates(), usize::MAX, |goal| {
+        let start = Instant::now();
+        let mut game = solver.clone();
+        let solved = game.play(goal, strategy, limit);
+        (solved, game.history().iter().map(|&(guess, _)| guess).collect(), start.elapsed())
+    });
+    let (results, games) = played.into_iter().map(|(goal, (solved, guesses, time))| ((goal, solved), (guesses, time))).unzip();
+    BenchReport { results, games }
+}
@@ -0,0 +1,61 @@
+use crate::alphabet::alphabet;
+use crate::outcome::{LetterOutcome, Outcome};
+use crate::word::Word;
+
+/// Reconstructs guesses and outcomes from text copied off Wordle-style pages.
+///
+/// Each row is `length` letters (as a word or separate tiles) and as many
+/// tile emoji, in either order and on the same or adjacent lines. Anything
+/// else, like the "Wordle 123 4/6" header, resets the row in progress.
+/// Letters are those of the [`alphabet`], in either case.
+pub fn parse_board(text: &str, length: usize) -> Vec<(Word, Outcome)> {
+    let letter = |c: char| c.to_lowercase().next().filter(|&c| alphabet().lookup(c).is_some());
+    let mut board = Vec::new();
+    let mut letters = Vec::new();
+    let mut outcomes = Vec::new();
+    let mut run = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(c) = letter(c) {
+            run.push(c);
+            if chars.peek().is_some_and(|&c| letter(c).is_some()) { continue }
+            if run.len() == 1 || run.len() == length && letters.is_empty() {
+                letters.append(&mut run);
+            } else {
+                letters.clear();
+                outcomes.clear();
+            }
+            run.clear();
+        } else if let Some(o) = LetterOutcome::from_emoji(c) {
+            outcomes.push(o);
+        } else {
+            continue;
+        }
+        if letters.len() > length || outcomes.len() > length {
+            letters.clear();
+            outcomes.clear();
+        } else if letters.len() == length && outcomes.len() == length {
+            board.push((Word::new(&letters.iter().collect::<String>()), Outcome::new(&outcomes)));
+            letters.clear();
+            outcomes.clear();
+        }
+    }
+    board
+}
+
+/// Pairs the rows of a Wordle share grid, lines of tile emoji with the words
+/// left out, with the `guesses` that were played, in order. Other lines, like
+/// the "Wordle 123 4/6" header, are skipped. Fails unless there's one row per
+/// guess, each as long as its guess.
+pub fn parse_share(text: &str, guesses: &[Word]) -> Result<Vec<(Word, Outcome)>, String> {
+    let rows: Vec<Vec<LetterOutcome>> = text.lines()
+        .filter_map(|line| line.trim().chars().map(LetterOutcome::from_emoji).collect::<Option<Vec<_>>>())
+        .filter(|row| !row.is_empty()).collect();
+    if rows.len() != guesses.len() {
+        return Err(format!("the grid has {} rows but {} guesses were given", rows.len(), guesses.len()));
+    }
+    guesses.iter().zip(rows).map(|(&guess, row)| {
+        if row.len() != guess.len() { return Err(format!("{} has {} letters but its row has {} tiles", guess, guess.len(), row.len())) }
+        Ok((guess, Outcome::new(&row)))
+    }).collect()
+}
@@ -0,0 +1,238 @@
+use crate::matrix::OutcomeMatrix;
+use crate::metrics::{count, Counter};
+use crate::pattern::Pattern;
+use crate::strategy::{Quick, Strategy};
+use crate::word::Word;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+
+/// FNV-1a, which unlike the standard library's hasher is the same on every
+/// run and every build, as keys kept in a file must be.
+pub fn fnv(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf29ce484222325, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+// Counts a lookup that found `guess`.
+fn hit(guess: Word) -> Word {
+    count(Counter::CacheLookups, 1);
+    count(Counter::CacheHits, 1);
+    guess
+}
+
+// The guess `choose` makes with Quick, counted.
+fn fall_back(choose: impl FnOnce(&dyn Strategy) -> Option<Word>) -> Option<Word> {
+    let guess = choose(&Quick)?;
+    count(Counter::QuickFallbacks, 1);
+    Some(guess)
+}
+
+// A hash of a list of words that doesn't depend on their order.
+pub(crate) fn list_hash<'a>(words: impl Iterator<Item = &'a Word>) -> u64 {
+    words.map(|w| fnv(w.symbols().iter().copied())).fold(0, u64::wrapping_add)
+}
+
+// A key for the guess `settings` pick from `dict` among `candidates`, the
+// same whatever order the lists are in.
+fn key<'a>(settings: &str, candidates: impl Iterator<Item = &'a Word>, dict: impl Iterator<Item = &'a Word>) -> u64 {
+    fnv(settings.bytes().chain(list_hash(candidates).to_le_bytes()).chain(list_hash(dict).to_le_bytes()))
+}
+
+// The keys for Strategy::choose and Strategy::choose_indexed. Strategies may
+// read more into the pattern than the candidates say, so unlike the matrix
+// path, the first keys on it too.
+fn pattern_key(settings: &str, candidates: &[Word], dict: &[Word], pattern: &Pattern) -> u64 {
+    key(&format!("{} {}", settings, pattern), candidates.iter(), dict.iter())
+}
+
+fn indexed_key(settings: &str, matrix: &OutcomeMatrix, candidates: &[u32], rows: &[u32]) -> u64 {
+    key(settings, candidates.iter().map(|&id| &matrix.goals()[id as usize]), rows.iter().map(|&row| &matrix.guesses()[row as usize]))
+}
+
+// Guesses saved a line each, as a hex key and the guess.
+fn read_guesses(text: &str) -> HashMap<u64, Word> {
+    text.lines().filter_map(|line| {
+        let (key, guess) = line.split_once(' ')?;
+        Some((u64::from_str_radix(key, 16).ok()?, Word::parse(guess)?))
+    }).collect()
+}
+
+/// A [`Strategy`] remembering another's choices in a file, so the slow opening
+/// recommendations, which depend only on the word lists and the settings, are
+/// instant on later runs.
+///
+/// Only positions with more than [`min_candidates`](Cached::min_candidates)
+/// left are cached: the first guess and the replies to it. Positions with
+/// more than [`max_candidates`](Cached::max_candidates) are looked up, but
+/// otherwise get [`Quick`]'s guess, uncached, as they would without the
+/// cache. Errors reading or writing the file are ignored, leaving the cache
+/// empty or unsaved.
+pub struct Cached {
+    strategy: Box<dyn Strategy>,
+    settings: String,
+    path: String,
+    guesses: Mutex<(HashMap<u64, Word>, bool)>,
+
+    /// Positions with at most this many candidates are cheap enough not to
+    /// cache; 20 by default.
+    pub min_candidates: usize,
+
+    /// Positions with more candidates than this are only looked up; the
+    /// strategy's [`max_candidates`](Strategy::max_candidates) by default.
+    pub max_candidates: usize,
+}
+
+impl Cached {
+    /// Caches `strategy`'s choices in the file at `path`. `settings` should
+    /// describe everything besides the word lists that the strategy's choices
+    /// depend on, e.g. its options.
+    pub fn new(strategy: Box<dyn Strategy>, settings: &str, path: &str) -> Self {
+        let guesses = fs::read_to_string(path).map(|text| read_guesses(&text)).unwrap_or_default();
+        Self {
+            settings: format!("{} {}", strategy.name(), settings),
+            max_candidates: strategy.max_candidates(),
+            strategy,
+            path: path.to_string(),
+            guesses: Mutex::new((guesses, false)),
+            min_candidates: 20,
+        }
+    }
+
+    // The cached guess under `key`, or the one `choose` makes with the
+    // strategy, remembered, or with Quick if there are too many `candidates`.
+    fn cached(&self, key: u64, candidates: usize, choose: impl FnOnce(&dyn Strategy) -> Option<Word>) -> Option<Word> {
+        if let Some(&guess) = self.guesses.lock().unwrap().0.get(&key) { return Some(hit(guess)) }
+        count(Counter::CacheLookups, 1);
+        if candidates > self.max_candidates { return fall_back(choose) }
+        let guess = choose(self.strategy.as_ref())?;
+        let mut guesses = self.guesses.lock().unwrap();
+        guesses.0.insert(key, guess);
+        guesses.1 = true;
+        Some(guess)
+    }
+}
+
+impl Strategy for Cached {
+    fn name(&self) -> &'static str {
+        self.strategy.name()
+    }
+
+    fn choose(&self, candidates: &[Word], dict: &[Word], pattern: &Pattern) -> Word {
+        if candidates.len() <= self.min_candidates { return self.strategy.choose(candidates, dict, pattern) }
+        let key = pattern_key(&self.settings, candidates, dict, pattern);
+        self.cached(key, candidates.len(), |strategy| Some(strategy.choose(candidates, dict, pattern))).unwrap()
+    }
+
+    fn choose_indexed(&self, matrix: &OutcomeMatrix, candidates: &[u32], rows: &[u32]) -> Option<Word> {
+        if candidates.len() <= self.min_candidates { return self.strategy.choose_indexed(matrix, candidates, rows) }
+        let key = indexed_key(&self.settings, matrix, candidates, rows);
+        self.cached(key, candidates.len(), |strategy| strategy.choose_indexed(matrix, candidates, rows))
+    }
+
+    // Big positions fall back to Quick here, after looking them up.
+    fn max_candidates(&self) -> usize {
+        usize::MAX
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.strategy.is_deterministic()
+    }
+}
+
+/// The opening book built into the binary: the default strategy's replies to
+/// each outcome of its first guess over Wordle's lists, and of the popular
+/// first guesses in openers.txt, written by the `book` command.
+pub const BOOK: &str = include_str!("../book.txt");
+
+/// A [`Strategy`] playing another's choices from an opening book, so the
+/// first turns need no search. A book is a file [`Cached`] wrote, with the same
+/// strategy and settings; positions it doesn't have, such as after another
+/// first guess, are left to the strategy, or to [`Quick`] if they have too
+/// many candidates for it.
+pub struct Book {
+    strategy: Box<dyn Strategy>,
+    settings: String,
+    guesses: HashMap<u64, Word>,
+}
+
+impl Book {
+    /// Plays `strategy` from the book in `text`, looking up positions as a
+    /// [`Cached`] with the same `settings` would.
+    pub fn new(strategy: Box<dyn Strategy>, settings: &str, text: &str) -> Self {
+        Self { settings: format!("{} {}", strategy.name(), settings), strategy, guesses: read_guesses(text) }
+    }
+
+    /// How many positions the book has.
+    pub fn len(&self) -> usize {
+        self.guesses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guesses.is_empty()
+    }
+
+    // The guess `choose` makes for a position not in the book, with the
+    // strategy or with Quick if there are too many `candidates` for it.
+    fn missed(&self, candidates: usize, choose: impl FnOnce(&dyn Strategy) -> Option<Word>) -> Option<Word> {
+        count(Counter::CacheLookups, 1);
+        if candidates > self.strategy.max_candidates() { return fall_back(choose) }
+        choose(self.strategy.as_ref())
+    }
+}
+
+impl Strategy for Book {
+    fn name(&self) -> &'static str {
+        self.strategy.name()
+    }
+
+    fn choose(&self, candidates: &[Word], dict: &[Word], pattern: &Pattern) -> Word {
+        let key = pattern_key(&self.settings, candidates, dict, pattern);
+        let choose = |strategy: &dyn Strategy| Some(strategy.choose(candidates, dict, pattern));
+        self.guesses.get(&key).copied().map(hit).or_else(|| self.missed(candidates.len(), choose)).unwrap()
+    }
+
+    fn choose_indexed(&self, matrix: &OutcomeMatrix, candidates: &[u32], rows: &[u32]) -> Option<Word> {
+        let key = indexed_key(&self.settings, matrix, candidates, rows);
+        let choose = |strategy: &dyn Strategy| strategy.choose_indexed(matrix, candidates, rows);
+        self.guesses.get(&key).copied().map(hit).or_else(|| self.missed(candidates.len(), choose))
+    }
+
+    // Big positions fall back to Quick here, after looking them up.
+    fn max_candidates(&self) -> usize {
+        usize::MAX
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.strategy.is_deterministic()
+    }
+}
+
+/// The lines of a slow report on the word lists, such as the best openers,
+/// kept in the file at `path`: the ones saved there for the same `settings`
+/// and lists, or else the ones `report` returns, saved for next time. Errors
+/// reading or writing the file are ignored, as for [`Cached`].
+pub fn cached_report(path: &str, settings: &str, goals: &[Word], dict: &[Word], report: impl FnOnce() -> Vec<String>) -> Vec<String> {
+    let key = format!("{:016x}", key(settings, goals.iter(), dict.iter()));
+    if let Ok(text) = fs::read_to_string(path) {
+        let mut lines = text.lines();
+        if lines.next() == Some(&key) { return lines.map(str::to_string).collect() }
+    }
+    let lines = report();
+    if let Some(dir) = std::path::Path::new(path).parent() { let _ = fs::create_dir_all(dir); }
+    let _ = fs::write(path, lines.iter().fold(key + "\n", |text, line| text + line + "\n"));
+    lines
+}
+
+impl Drop for Cached {
+    // Saves the cache if anything was added to it, in key order, so a book
+    // written again from the same positions is the same file.
+    fn drop(&mut self) {
+        let (guesses, changed) = &*self.guesses.lock().unwrap();
+        if !changed { return }
+        let mut guesses: Vec<_> = guesses.iter().collect();
+        guesses.sort();
+        let text: String = guesses.into_iter().map(|(key, guess)| format!("{:016x} {}\n", key, guess)).collect();
+        if let Some(dir) = std::path::Path::new(&self.path).parent() { let _ = fs::create_dir_all(dir); }
+        let _ = fs::write(&self.path, text);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::alphabet::alphabet;
+use crate::word::{Word, MAX_LEN};
+use std::fs::File;
+use std::io::{self, BufRead, IsTerminal};
+use std::str;
+
+const PROGRESS_WORDS: usize = 100_000;
+
+/// The standard Wordle answer list, built into the binary.
+pub const GOALS: &str = include_str!("../goals.txt");
+
+/// The standard Wordle list of allowed guesses that are never answers.
+pub const ALLOWED: &str = include_str!("../extra.txt");
+
+/// A game whose word lists are built into the binary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Variant {
+    pub name: &'static str,
+
+    /// The language's ISO 639-1 code, e.g. "en".
+    pub lang: &'static str,
+
+    /// The symbols words are spelled with, for
+    /// [`Alphabet`](crate::Alphabet), and how many letters they have.
+    pub alphabet: &'static str,
+    pub length: usize,
+
+    /// The goals, and the other allowed guesses, one per line.
+    pub goals: &'static str,
+    pub allowed: &'static str,
+}
+
+/// The games with built-in lists. Wordle's are always built in; any others
+/// come with the cargo feature named after them, keeping the default binary
+/// small.
+pub const VARIANTS: &[Variant] = &[
+    Variant { name: "wordle", lang: "en", alphabet: "abcdefghijklmnopqrstuvwxyz", length: 5, goals: GOALS, allowed: ALLOWED },
+];
+
+impl Default for Variant {
+    /// Wordle.
+    fn default() -> Self {
+        VARIANTS[0]
+    }
+}
+
+/// The built-in variant called `name`, or the first for the language `name`
+/// is the code of.
+pub fn variant(name: &str) -> Option<&'static Variant> {
+    VARIANTS.iter().find(|v| v.name == name).or_else(|| VARIANTS.iter().find(|v| v.lang == name))
+}
+
+/// Reads the words of `length` letters from a word list with one word per
+/// line, spelled in the [`alphabet`], skipping words of other lengths.
+///
+/// Streams the list one line at a time into a buffer reused across lines,
+/// stopping early once `cap` words have been read, and reports progress on
+/// stderr for very large lists, if stderr is a terminal.
+///
+/// A filename of "-" reads standard input. With the `fetch` feature, http
+/// and https URLs are downloaded with curl.
+pub fn read_dict(filename: &str, length: usize, cap: Option<usize>) -> io::Result<Vec<Word>> {
+    if filename == "-" { return read_words(io::stdin().lock(), "stdin", length, cap) }
+    if filename.starts_with("http://") || filename.starts_with("https://") { return fetch(filename, length, cap) }
+    let file = File::open(filename)?;
+    // One byte per letter plus the newline, if every word is this length.
+    let expected = file.metadata()?.len() as usize / (length + 1);
+    read_lines(io::BufReader::new(file), filename, length, cap, expected)
+}
+
+/// Like [`read_dict`], but from an already open list; `name` labels progress
+/// reports.
+pub fn read_words(reader: impl BufRead, name: &str, length: usize, cap: Option<usize>) -> io::Result<Vec<Word>> {
+    read_lines(reader, name, length, cap, 0)
+}
+
+#[cfg(feature = "fetch")]
+fn fetch(url: &str, length: usize, cap: Option<usize>) -> io::Result<Vec<Word>> {
+    let output = std::process::Command::new("curl").args(["--silent", "--show-error", "--fail", "--location", url]).output()?;
+    if !output.status.success() {
+        let message = format!("fetching {} failed: {}", url, String::from_utf8_lossy(&output.stderr).trim());
+        return Err(io::Error::other(message));
+    }
+    read_words(&output.stdout[..], url, length, cap)
+}
+
+#[cfg(not(feature = "fetch"))]
+fn fetch(url: &str, _length: usize, _cap: Option<usize>) -> io::Result<Vec<Word>> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, format!("can't fetch {}: built without the fetch feature", url)))
+}
+
+fn read_lines(mut reader: impl BufRead, filename: &str, length: usize, cap: Option<usize>, expected: usize) -> io::Result<Vec<Word>> {
+    let mut dict = Vec::with_capacity(cap.map_or(expected, |cap| cap.min(expected)));
+    let mut line = String::new();
+    let progress = io::stderr().is_terminal();
+    while cap.is_none_or(|cap| dict.len() < cap) {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 { break }
+        let word = line.trim_end_matches(&['\r', '\n'][..]);
+        if word.is_empty() || word.chars().any(|c| alphabet().lookup(c).is_none()) {
+            Err(io::Error::new(io::ErrorKind::InvalidData, format!("malformed word {:?}", word)))?;
+        }
+        if word.chars().count() != length { continue }
+
+        dict.push(Word::new(word));
+        if progress && dict.len() % PROGRESS_WORDS == 0 { eprintln!("{}: {} words read", filename, dict.len()); }
+    }
+    if progress && dict.len() >= PROGRESS_WORDS { eprintln!("{}: done, {} words", filename, dict.len()); }
+    Ok(dict)
+}
+
+/// A word list's lines sorted out by [`check_list`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListCheck {
+    /// Each word once, in the order first seen, trimmed and lowercased.
+    pub words: Vec<String>,
+
+    /// Line numbers, from 1, and text of lines that aren't a word spelled in
+    /// the [`alphabet`]. Blank lines are skipped.
+    pub malformed: Vec<(usize, String)>,
+
+    /// Line numbers and words of lines repeating an earlier line's word.
+    pub duplicates: Vec<(usize, String)>,
+
+    /// How many words were only accepted once trimmed or lowercased, which
+    /// the lists [`read_dict`] reads must already be.
+    pub unnormalized: usize,
+}
+
+/// Reads every line of a word list, whatever the length of its words,
+/// noting those [`read_dict`] would stop at or read twice.
+pub fn check_list(reader: impl BufRead) -> io::Result<ListCheck> {
+    let mut check = ListCheck::default();
+    let mut seen = std::collections::HashSet::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let word = line.trim().to_lowercase();
+        if word.is_empty() { continue }
+        if word.chars().any(|c| alphabet().lookup(c).is_none()) {
+            check.malformed.push((i + 1, line));
+            continue;
+        }
+        if word != line { check.unnormalized += 1; }
+        if seen.insert(word.clone()) { check.words.push(word) } else { check.duplicates.push((i + 1, word)) }
+    }
+    Ok(check)
+}
+
+/// Rules out goal words by their ending.
+///
+/// Written as "suffix!exception!exception", e.g. "s!ss" drops goals ending in
+/// "s" unless they end in "ss".
+#[derive(Debug, Clone)]
+pub struct SuffixFilter {
+    suffix: String,
+    exceptions: Vec<String>,
+}
+
+/// Plurals, which NYT answers avoid.
+pub const PLURALS: &str = "s!ss!us!is";
+
+/// Past tenses, which NYT answers avoid.
+pub const PAST_TENSE: &str = "ed";
+
+impl SuffixFilter {
+    pub fn excludes(&self, word: Word) -> bool {
+        let text = word.to_string();
+        text.ends_with(&self.suffix) && !self.exceptions.iter().any(|e| text.ends_with(e))
+    }
+}
+
+impl str::FromStr for SuffixFilter {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, ()> {
+        let mut parts = text.split('!').map(str::to_string);
+        let suffix = parts.next().filter(|s| !s.is_empty() && s.chars().count() <= MAX_LEN).ok_or(())?;
+        Ok(Self { suffix, exceptions: parts.collect() })
+    }
+}
@@ -0,0 +1,109 @@
+//! A C interface to [`Solver`], built with the `ffi` feature:
+//!
+//! ```c
+//! typedef struct Solver Solver;
+//! Solver *solver_new(const char *goals, const char *dict, size_t length);
+//! int solver_refine(Solver *solver, const char *guess, const char *outcome);
+//! size_t solver_remaining(const Solver *solver);
+//! int solver_recommend(const Solver *solver, char *guess, size_t size);
+//! void solver_free(Solver *solver);
+//! ```
+
+use crate::dict::{read_words, ALLOWED, GOALS};
+use crate::outcome::Outcome;
+use crate::solver::Solver;
+use crate::strategy::{Minimax, Quick, Strategy};
+use crate::word::Word;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+// A C string, or `None` if it's null or not UTF-8.
+unsafe fn text<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() { return None }
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Starts a game, returning null if either list can't be read or there are
+/// no goals of `length` letters.
+///
+/// `goals` and `dict` are word lists, one word per line, or null for
+/// Wordle's. Free the solver with [`solver_free`].
+///
+/// # Safety
+///
+/// `goals` and `dict` must each be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn solver_new(goals: *const c_char, dict: *const c_char, length: usize) -> *mut Solver {
+    let list = |list: *const c_char, builtin: &str, name| {
+        let text = if list.is_null() { builtin } else { text(list)? };
+        read_words(text.as_bytes(), name, length, None).ok()
+    };
+    let (Some(goals), Some(mut dict)) = (list(goals, GOALS, "goals"), list(dict, ALLOWED, "guesses")) else { return ptr::null_mut() };
+    if goals.is_empty() { return ptr::null_mut() }
+    dict.extend(&goals);
+    Box::into_raw(Box::new(Solver::new(goals, dict).with_matrix()))
+}
+
+/// Records `guess` getting `outcome`, tiles typed as letters like "gybbg".
+/// Returns 0, or -1 if either isn't the right length or can't be read.
+///
+/// # Safety
+///
+/// `solver` must come from [`solver_new`], and `guess` and `outcome` must be
+/// NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn solver_refine(solver: *mut Solver, guess: *const c_char, outcome: *const c_char) -> c_int {
+    let solver = &mut *solver;
+    let length = solver.history().first().map_or_else(|| solver.dict()[0].len(), |(guess, _)| guess.len());
+    let guess = text(guess).and_then(Word::parse).filter(|w| w.len() == length);
+    let outcome = text(outcome).and_then(|o| o.parse().ok()).filter(|o: &Outcome| o.tiles().len() == length);
+    match (guess, outcome) {
+        (Some(guess), Some(outcome)) => { solver.refine(guess, outcome); 0 },
+        _ => -1,
+    }
+}
+
+/// How many goals are still possible.
+///
+/// # Safety
+///
+/// `solver` must come from [`solver_new`].
+#[no_mangle]
+pub unsafe extern "C" fn solver_remaining(solver: *const Solver) -> usize {
+    (*solver).candidates().len()
+}
+
+/// Writes the recommended guess to `guess` as a NUL-terminated string of at
+/// most `size` bytes. Returns 0, or -1 if no goals are left or it doesn't
+/// fit.
+///
+/// Guesses are picked by [`Minimax`], or [`Quick`] while there are too many
+/// goals for it.
+///
+/// # Safety
+///
+/// `solver` must come from [`solver_new`], and `guess` must point to at
+/// least `size` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn solver_recommend(solver: *const Solver, guess: *mut c_char, size: usize) -> c_int {
+    let solver = &*solver;
+    let minimax = Minimax { progress_every: usize::MAX, ..Minimax::default() };
+    let strategy: &dyn Strategy = if solver.candidates().len() > minimax.max_candidates() { &Quick } else { &minimax };
+    let Some(recommended) = solver.choose(strategy) else { return -1 };
+    let recommended = recommended.to_string();
+    if recommended.len() >= size { return -1 }
+    ptr::copy_nonoverlapping(recommended.as_ptr(), guess as *mut u8, recommended.len());
+    *guess.add(recommended.len()) = 0;
+    0
+}
+
+/// Frees a solver from [`solver_new`]. Null is ignored.
+///
+/// # Safety
+///
+/// `solver` must be null or come from [`solver_new`], and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn solver_free(solver: *mut Solver) {
+    if !solver.is_null() { drop(Box::from_raw(solver)); }
+}
@@ -0,0 +1,233 @@
+use crate::alphabet::{alphabet, LetterMask, MAX_SYMBOLS};
+use crate::metrics::{count, Counter};
+use crate::outcome::{LetterOutcome, Outcome};
+use crate::pattern::Pattern;
+use crate::word::{Word, MAX_LEN};
+use std::collections::HashMap;
+
+/// A set of goals, as a bitset of indices into a fixed goal list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoalSet {
+    bits: Vec<u64>,
+    len: usize,
+}
+
+impl GoalSet {
+    /// No goals out of `len`.
+    pub fn empty(len: usize) -> Self {
+        Self { bits: vec![0; len.div_ceil(64)], len }
+    }
+
+    /// All `len` goals.
+    pub fn full(len: usize) -> Self {
+        let mut set = Self { bits: vec![!0; len.div_ceil(64)], len };
+        if !len.is_multiple_of(64) { *set.bits.last_mut().unwrap() = (1 << (len % 64)) - 1; }
+        set
+    }
+
+    /// Adds goal `i`.
+    pub fn insert(&mut self, i: usize) {
+        self.bits[i / 64] |= 1 << (i % 64);
+    }
+
+    /// Whether goal `i` is in the set.
+    pub fn contains(&self, i: usize) -> bool {
+        self.bits[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// Keeps only goals also in `other`.
+    pub fn intersect(&mut self, other: &GoalSet) {
+        for (a, b) in self.bits.iter_mut().zip(&other.bits) { *a &= b; }
+    }
+
+    /// Drops goals in `other`.
+    pub fn subtract(&mut self, other: &GoalSet) {
+        for (a, b) in self.bits.iter_mut().zip(&other.bits) { *a &= !b; }
+    }
+
+    /// How many goals are in the set.
+    pub fn count(&self) -> usize {
+        self.bits.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    /// How many goals are in both sets, without building the intersection.
+    pub fn count_common(&self, other: &GoalSet) -> usize {
+        self.bits.iter().zip(&other.bits).map(|(a, b)| (a & b).count_ones() as usize).sum()
+    }
+
+    /// The indices in the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits.iter().enumerate().flat_map(|(i, &b)| {
+            let mut b = b;
+            std::iter::from_fn(move || {
+                if b == 0 { return None }
+                let bit = b.trailing_zeros() as usize;
+                b &= b - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    /// Size of the goal list the indices refer to.
+    pub fn capacity(&self) -> usize {
+        self.len
+    }
+}
+
+/// Precomputed [`GoalSet`]s for a goal list, one per elementary constraint,
+/// so the goals matching a [`Pattern`] are a run of bitwise ANDs.
+#[derive(Debug, Clone)]
+pub struct GoalIndex {
+    goals: Vec<Word>,
+    // Goals with each letter at each position, indexed [position][letter].
+    at: Vec<Vec<GoalSet>>,
+    // Goals with at least n copies of each letter, indexed [letter][n - 1].
+    at_least: Vec<[GoalSet; MAX_LEN]>,
+}
+
+impl GoalIndex {
+    /// Indexes `goals`, which later [`GoalSet`]s refer to by position.
+    pub fn new(goals: &[Word]) -> Self {
+        let n = goals.len();
+        let symbols = alphabet().len();
+        let mut at = vec![vec![GoalSet::empty(n); symbols]; goals.iter().map(Word::len).max().unwrap_or(0)];
+        let mut at_least: Vec<[GoalSet; MAX_LEN]> = (0..symbols).map(|_| std::array::from_fn(|_| GoalSet::empty(n))).collect();
+        for (i, goal) in goals.iter().enumerate() {
+            let mut copies = [0; MAX_SYMBOLS];
+            for (p, &s) in goal.symbols().iter().enumerate() {
+                let l = s as usize;
+                at[p][l].insert(i);
+                at_least[l][copies[l]].insert(i);
+                copies[l] += 1;
+            }
+        }
+        Self { goals: goals.to_vec(), at, at_least }
+    }
+
+    /// The indexed goals.
+    pub fn goals(&self) -> &[Word] {
+        &self.goals
+    }
+
+    /// The goals `pattern` matches, the same ones as [`Pattern::matches`].
+    pub fn matching(&self, pattern: &Pattern) -> GoalSet {
+        let mut set = GoalSet::full(self.goals.len());
+        for l in 0..self.at_least.len() {
+            let (min, max) = (pattern.min_counts[l] as usize, pattern.max_counts[l] as usize);
+            let min = if pattern.positive_letters & (1 << l) != 0 { min.max(1) } else { min };
+            let max = if pattern.negative_letters & (1 << l) != 0 { 0 } else { max };
+            if min > 0 { set.intersect(&self.at_least[l][min - 1]); }
+            if max < MAX_LEN { set.subtract(&self.at_least[l][max]); }
+        }
+        for (&allowed, at) in pattern.per_char.iter().zip(&self.at) {
+            for (l, goals) in at.iter().enumerate() {
+                if allowed & (1 << l) == 0 { set.subtract(goals); }
+            }
+        }
+        set
+    }
+
+    /// The goals that would give `outcome` for `guess`, from the sets alone:
+    /// the same ones as matching a new pattern refined by them.
+    pub fn with_outcome(&self, guess: Word, outcome: Outcome) -> GoalSet {
+        let mut set = GoalSet::full(self.goals.len());
+        let mut scored = [0; MAX_SYMBOLS];
+        let mut grayed: LetterMask = 0;
+        for (p, (&s, &o)) in guess.symbols().iter().zip(outcome.tiles()).enumerate() {
+            let l = s as usize;
+            match o {
+                LetterOutcome::Here => set.intersect(&self.at[p][l]),
+                _ => set.subtract(&self.at[p][l]),
+            }
+            if o == LetterOutcome::Nowhere { grayed |= 1 << l; } else { scored[l] += 1; }
+        }
+        for &s in guess.symbols() {
+            let l = s as usize;
+            if scored[l] > 0 { set.intersect(&self.at_least[l][scored[l] - 1]); }
+            if grayed & (1 << l) != 0 && scored[l] < MAX_LEN { set.subtract(&self.at_least[l][scored[l]]); }
+        }
+        set
+    }
+
+    /// [`guess_confidence`](crate::guess_confidence) over the goals in
+    /// `candidates`, counting survivors by popcount.
+    pub fn confidence(&self, pattern: &Pattern, candidates: &GoalSet, guess: Word) -> i32 {
+        self.confidence_within(pattern, candidates, guess, usize::MAX)
+    }
+
+    /// Like [`confidence`](GoalIndex::confidence), but gives up once some
+    /// outcome leaves more than `bound` goals, returning that many negated:
+    /// a guess known to be worse than one leaving at most `bound`. Goals are
+    /// tried in index order.
+    pub fn confidence_within(&self, pattern: &Pattern, candidates: &GoalSet, guess: Word, bound: usize) -> i32 {
+        let mut matching = candidates.clone();
+        matching.intersect(&self.matching(pattern));
+        // Goals giving the same outcome leave the same goals.
+        let mut left: HashMap<u16, usize> = HashMap::new();
+        let mut worst = 0;
+        for i in candidates.iter() {
+            let outcome = Outcome::compare(self.goals[i], guess);
+            let n = *left.entry(outcome.code()).or_insert_with(|| self.with_outcome(guess, outcome).count_common(&matching));
+            worst = worst.max(n);
+            if worst > bound {
+                count(Counter::GuessesCutOff, 1);
+                break;
+            }
+        }
+        -(worst as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::guess_confidence;
+
+    fn goals() -> Vec<Word> {
+        include_str!("../goals.txt").lines().step_by(3).map(Word::new).collect()
+    }
+
+    fn patterns() -> Vec<Pattern> {
+        let texts = ["", "?a,-esoir", "+s@1,?r@3@4,-eio", "e=2,?t", "e>=2", "a<=1,?l@1@2", "-aeiou"];
+        let refined = [("eerie", "ybybg"), ("abbey", "yyggb"), ("sassy", "gbggg"), ("crane", "bbbbb")].iter()
+            .map(|&(w, o)| Pattern::from_history(&[(Word::new(w), o.parse().unwrap())]));
+        texts.iter().map(|t| t.parse().unwrap()).chain(refined).collect()
+    }
+
+    #[test]
+    fn matching_agrees_with_patterns() {
+        let goals = goals();
+        let index = GoalIndex::new(&goals);
+        for pattern in patterns() {
+            let expected: Vec<usize> = (0..goals.len()).filter(|&i| pattern.matches(goals[i])).collect();
+            assert_eq!(index.matching(&pattern).iter().collect::<Vec<_>>(), expected, "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn outcomes_agree_with_refined_patterns() {
+        let goals = goals();
+        let index = GoalIndex::new(&goals);
+        for guess in ["eerie", "abbey", "sassy", "crane", "mamma"].iter().map(|&w| Word::new(w)) {
+            for &goal in goals.iter().step_by(11) {
+                let outcome = Outcome::compare(goal, guess);
+                let expected = index.matching(&Pattern::from_history(&[(guess, outcome)]));
+                assert_eq!(index.with_outcome(guess, outcome), expected, "{} against {}", guess, goal);
+            }
+        }
+    }
+
+    #[test]
+    fn confidence_agrees_with_guess_confidence() {
+        let goals = goals();
+        for pattern in patterns() {
+            let left: Vec<Word> = goals.iter().copied().filter(|&w| pattern.matches(w)).collect();
+            if left.is_empty() { continue }
+            let index = GoalIndex::new(&left);
+            let all = GoalSet::full(left.len());
+            for guess in ["soare", "eerie", "lying", "natal"].iter().map(|&w| Word::new(w)) {
+                assert_eq!(index.confidence(&pattern, &all, guess), guess_confidence(&pattern, &left, guess), "{} after {}", guess, pattern);
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::analysis::worst_bucket;
+use crate::outcome::Outcome;
+use crate::word::Word;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// The most guesses left at which [`safe_guess`] searches: any more, and the
+/// search would be too slow, while the goals left are hardly ever too many.
+pub const GUARANTEE_DEPTH: usize = 3;
+
+/// Whether every one of `goals` can be found within `guesses` more guesses
+/// from `dict`, whatever the answer. Exact, but exponential in `guesses`.
+pub fn solvable_within(goals: &[Word], dict: &[Word], guesses: usize) -> bool {
+    Search { dict, memo: HashMap::new() }.solvable(goals, guesses)
+}
+
+/// Whether playing `guess` still lets every one of `goals` be found within
+/// `guesses` guesses, counting it.
+pub fn is_safe(guess: Word, goals: &[Word], dict: &[Word], guesses: usize) -> bool {
+    Search { dict, memo: HashMap::new() }.safe(guess, goals, guesses)
+}
+
+/// `preferred` if it's safe to play with `guesses` left, by [`is_safe`], or
+/// else the safe guess leaving the fewest goals at worst, goals first among
+/// equals. `None` if no guess is safe. With more than [`GUARANTEE_DEPTH`]
+/// guesses left, `preferred` is taken as it is.
+pub fn safe_guess(preferred: Word, goals: &[Word], dict: &[Word], guesses: usize) -> Option<Word> {
+    if guesses > GUARANTEE_DEPTH { return Some(preferred) }
+    let mut search = Search { dict, memo: HashMap::new() };
+    if search.safe(preferred, goals, guesses) { return Some(preferred) }
+    ranked(goals, dict).into_iter().find(|&guess| search.safe(guess, goals, guesses))
+}
+
+// Every guess that tells some of `goals` apart, leaving the fewest at worst
+// first, then goals, as they might win.
+fn ranked(goals: &[Word], dict: &[Word]) -> Vec<Word> {
+    let mut ranked: Vec<(usize, bool, Word)> = goals.iter().map(|&g| (worst_bucket(g, goals), false, g))
+        .chain(dict.iter().map(|&g| (worst_bucket(g, goals), !goals.contains(&g), g)))
+        .filter(|&(worst, other, _)| worst < goals.len() || !other).collect();
+    ranked.sort();
+    ranked.dedup_by_key(|r| r.2);
+    ranked.into_iter().map(|r| r.2).collect()
+}
+
+// A search memoized on (sorted goals, guesses left).
+struct Search<'a> {
+    dict: &'a [Word],
+    memo: HashMap<(Vec<Word>, usize), bool>,
+}
+
+impl Search<'_> {
+    fn solvable(&mut self, goals: &[Word], guesses: usize) -> bool {
+        if goals.is_empty() { return true }
+        // Guessing one of two goals, then the other, always works.
+        if goals.len() <= 2 { return guesses >= goals.len() }
+        if guesses <= 1 { return false }
+        let mut key = goals.to_vec();
+        key.sort();
+        if let Some(&solvable) = self.memo.get(&(key.clone(), guesses)) { return solvable }
+        let solvable = ranked(goals, self.dict).into_iter().any(|guess| self.safe(guess, goals, guesses));
+        self.memo.insert((key, guesses), solvable);
+        solvable
+    }
+
+    fn safe(&mut self, guess: Word, goals: &[Word], guesses: usize) -> bool {
+        if guesses == 0 { return false }
+        let mut buckets: HashMap<Outcome, Vec<Word>> = HashMap::new();
+        for &goal in goals {
+            if goal != guess { buckets.entry(Outcome::compare(goal, guess)).or_default().push(goal); }
+        }
+        let mut buckets: Vec<Vec<Word>> = buckets.into_values().collect();
+        // The biggest are likeliest to fail, ending the check soonest.
+        buckets.sort_by_key(|b| Reverse(b.len()));
+        buckets.iter().all(|bucket| self.solvable(bucket, guesses - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<Word> {
+        text.split(' ').map(Word::new).collect()
+    }
+
+    #[test]
+    fn checks_every_answer_is_found_in_time() {
+        // Only their first letters tell these apart, which blmpz tries at once.
+        let goals = words("batch latch match patch");
+        let mut dict = goals.clone();
+        assert!(solvable_within(&goals, &dict, 4));
+        assert!(!solvable_within(&goals, &dict, 3));
+        assert_eq!(safe_guess(goals[0], &goals, &dict, 3), None);
+        dict.push(Word::new("blmpz"));
+        assert!(solvable_within(&goals, &dict, 2));
+        assert!(!solvable_within(&goals, &dict, 1));
+        assert!(is_safe(Word::new("blmpz"), &goals, &dict, 2));
+        assert!(!is_safe(goals[0], &goals, &dict, 2));
+        assert!(is_safe(goals[0], &goals, &dict, 3));
+        assert_eq!(safe_guess(goals[0], &goals, &dict, 2), Some(Word::new("blmpz")));
+        assert_eq!(safe_guess(goals[0], &goals, &dict, 3), Some(goals[0]));
+        // Too many guesses left to bother checking.
+        assert_eq!(safe_guess(goals[0], &goals, &goals, GUARANTEE_DEPTH + 1), Some(goals[0]));
+    }
+
+    #[test]
+    fn small_sets() {
+        let goals = words("cigar rebut");
+        assert!(solvable_within(&[], &goals, 0));
+        assert!(solvable_within(&goals, &goals, 2));
+        assert!(!solvable_within(&goals, &goals, 1));
+        assert!(solvable_within(&goals[..1], &goals, 1));
+    }
+}
@@ -0,0 +1,61 @@
+//! A Wordle solver.
+//!
+//! [`Word`]s are guessed and scored into [`Outcome`]s, which accumulate into a
+//! [`Pattern`] describing what is known about the answer. A [`Solver`] ties
+//! these together for a game in progress: it tracks the remaining candidate
+//! goals and recommends the next guess.
+
+mod alphabet;
+mod analysis;
+mod bench;
+mod board;
+mod cache;
+mod dict;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod goalset;
+mod guarantee;
+mod matrix;
+mod metrics;
+mod multi;
+mod openers;
+mod optimal;
+mod outcome;
+mod pattern;
+mod priors;
+mod review;
+mod session;
+mod solver;
+mod strategy;
+mod transposition;
+mod tree;
+mod verify;
+mod word;
+
+pub use alphabet::{alphabet, is_direction_mark, Alphabet, MAX_SYMBOLS};
+pub use analysis::{
+    adversarial_outcome, best_opener, burner_guess, expected_remaining, expected_tiles, grade, letter_presence, letter_probabilities, outcome_counts, outcome_entropy, positional_counts,
+    weighted_entropy, weighted_remaining, worst_bucket,
+};
+pub use bench::{bench, simulate, BenchReport, SimulationReport};
+pub use board::{parse_board, parse_share};
+pub use cache::{cached_report, fnv, Book, Cached, BOOK};
+pub use dict::{check_list, read_dict, read_words, variant, ListCheck, SuffixFilter, Variant, ALLOWED, GOALS, PAST_TENSE, PLURALS, VARIANTS};
+pub use goalset::{GoalIndex, GoalSet};
+pub use guarantee::{is_safe, safe_guess, solvable_within, GUARANTEE_DEPTH};
+pub use matrix::OutcomeMatrix;
+pub use metrics::Metrics;
+pub use multi::MultiSolver;
+pub use openers::{best_openers, best_pairs, OpeningSplit};
+pub use optimal::{Objective, OptimalSearch};
+pub use outcome::{LetterOutcome, Outcome};
+pub use pattern::{KeyState, Pattern};
+pub use priors::{read_priors, Priors};
+pub use review::{compare_lines, expected_guesses, review, skill_and_luck, LineComparison, TurnReview};
+pub use session::SessionManager;
+pub use solver::{guess_confidence, limited_guess, lookahead_confidence, lookahead_guess, quick_guess, recommended_guess, Candidates, SearchLimits, Solver, Turn};
+pub use strategy::{Entropy, Expected, Minimax, Quick, Sampled, Strategy};
+pub use transposition::TranspositionTable;
+pub use tree::{DecisionTree, Planned};
+pub use verify::{consistent_goals, verify_game, verify_goals, Divergence};
+pub use word::{Word, MAX_LEN};
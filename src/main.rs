@@ -1,192 +1,3396 @@
-use rand::{thread_rng, seq::SliceRandom};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::collections::HashSet;
 use std::env;
 use std::fmt;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::ops::Range;
+use std::process::ExitCode;
 use std::str;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use terminal::{cursor_row, key_char, parse_key, read_key, Key, Picked, RawMode, TilePicker};
+use messages::{Catalog, Message};
+use theme::Theme;
 
-fn to_letter_mask(c: u8) -> u32 {
-    1 << (c - ('a' as u8))
+mod messages;
+mod terminal;
+mod theme;
+use wordlesolve::{
+    adversarial_outcome, alphabet, is_direction_mark, BOOK, check_list, review, variant, bench, best_opener, burner_guess, simulate, best_openers, best_pairs, cached_report, expected_remaining, fnv, expected_tiles, grade, letter_presence, skill_and_luck, compare_lines, letter_probabilities, outcome_entropy, parse_board, parse_share, read_dict, read_priors, read_words, verify_game, worst_bucket, solvable_within,
+    Alphabet, BenchReport, Book, Cached, ListCheck, DecisionTree, Entropy, Expected, Minimax, Metrics, MultiSolver, OpeningSplit, OutcomeMatrix, LetterOutcome, KeyState, LineComparison, Objective, OptimalSearch, Outcome, Pattern, Planned, Priors, Quick, Sampled, SimulationReport, Solver, Strategy, SuffixFilter, TranspositionTable, Variant, Word, GUARANTEE_DEPTH, MAX_LEN, PAST_TENSE, PLURALS, VARIANTS,
+};
+
+fn wordlist_diff(old: &[Word], new: &[Word]) {
+    let old_set: HashSet<Word> = old.iter().copied().collect();
+    let new_set: HashSet<Word> = new.iter().copied().collect();
+    let mut added: Vec<Word> = new_set.difference(&old_set).copied().collect();
+    let mut removed: Vec<Word> = old_set.difference(&new_set).copied().collect();
+    added.sort();
+    removed.sort();
+    println!("{} added", added.len());
+    for w in &added { println!("  + {}", w); }
+    println!("{} removed", removed.len());
+    for w in &removed { println!("  - {}", w); }
+
+    if old.is_empty() || new.is_empty() { return }
+    let (old_opener, old_worst) = best_opener(old);
+    let (new_opener, new_worst) = best_opener(new);
+    println!("best opener was {} (at most {} left), now {} (at most {} left)",
+             old_opener, old_worst, new_opener, new_worst);
+    if old_opener != new_opener {
+        println!("{} now leaves at most {}", old_opener, worst_bucket(old_opener, new));
+    }
+}
+
+// Drops repeated words, keeping the first of each.
+fn dedup(words: &mut Vec<Word>) {
+    let mut seen = HashSet::new();
+    words.retain(|&w| seen.insert(w));
+}
+
+// Reads a list for the dict commands, "-" meaning standard input.
+fn check_file(filename: &str) -> Result<ListCheck, Error> {
+    let result = if filename == "-" {
+        check_list(io::stdin().lock())
+    } else {
+        std::fs::File::open(filename).and_then(|file| check_list(io::BufReader::new(file)))
+    };
+    result.map_err(|e| list_error(filename, e))
+}
+
+// The variant's list called `name` that update-wordlists installed, if any.
+fn installed_list(options: &Options, name: &str) -> Option<String> {
+    let path = format!("{}/{}/{}", options.lists_dir.as_ref()?, options.variant.name, name);
+    std::path::Path::new(&path).exists().then_some(path)
+}
+
+// Downloads the variant's goals and other allowed guesses from `sources`, or
+// --lists-source's goals.txt and extra.txt, and installs them once both are
+// read, with a word of the right length and none repeated. The lists they
+// replace are kept beside them as .bak.
+fn update_wordlists(sources: &[String], options: &Options) -> Result<(), Error> {
+    const NAMES: [&str; 2] = ["goals.txt", "extra.txt"];
+    let sources: Vec<String> = match (sources, &options.lists_source) {
+        ([goals, extra], _) => vec![goals.clone(), extra.clone()],
+        ([], Some(base)) => NAMES.iter().map(|name| format!("{}/{}", base.trim_end_matches('/'), name)).collect(),
+        _ => return Err(usage_error("update-wordlists needs the goals' and extra guesses' lists, or --lists-source")),
+    };
+    let dir = options.lists_dir.as_ref().ok_or_else(|| usage_error("there's no home directory to install lists in; try --lists-dir"))?;
+    let dir = format!("{}/{}", dir, options.variant.name);
+    let mut lists = Vec::new();
+    for (source, name) in sources.iter().zip(NAMES) {
+        let words = read_dict(source, options.length, None).map_err(|e| list_error(source, e))?;
+        if words.is_empty() { return Err(Error::Input(format!("{} has no {}-letter words", source, options.length))) }
+        let mut seen = HashSet::new();
+        if let Some(word) = words.iter().find(|&&w| !seen.insert(w)) { return Err(Error::Input(format!("{} lists {} twice", source, word))) }
+        lists.push((source, name, words));
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| file_error(&dir, e))?;
+    for (source, name, words) in lists {
+        let path = format!("{}/{}", dir, name);
+        let builtin = if name == NAMES[0] { options.variant.goals } else { options.variant.allowed };
+        let old = match installed_list(options, name) {
+            Some(path) => read_dict(&path, options.length, None).map_err(|e| list_error(&path, e))?,
+            None => read_words(builtin.as_bytes(), name, options.length, None).map_err(|e| list_error(name, e))?,
+        };
+        let (old, new): (HashSet<Word>, HashSet<Word>) = (old.into_iter().collect(), words.iter().copied().collect());
+        let text: String = words.iter().map(|w| format!("{}\n", w)).collect();
+        let staged = format!("{}.new", path);
+        std::fs::write(&staged, text).map_err(|e| file_error(&staged, e))?;
+        if std::path::Path::new(&path).exists() {
+            std::fs::rename(&path, format!("{}.bak", path)).map_err(|e| file_error(&path, e))?;
+        }
+        std::fs::rename(&staged, &path).map_err(|e| file_error(&path, e))?;
+        println!("{}: {} words from {}, {} added and {} removed", path, words.len(), source, new.difference(&old).count(), old.difference(&new).count());
+    }
+    Ok(())
+}
+
+// The dict commands, for maintaining word lists.
+fn dict_command(args: &[String], options: &Options) -> Result<(), Error> {
+    match (args[0].as_str(), &args[1..]) {
+        ("validate", lists) => {
+            let mut bad = 0;
+            for filename in lists {
+                let check = check_file(filename)?;
+                let mut lengths: Vec<(usize, usize)> = Vec::new();
+                for word in &check.words {
+                    let n = word.chars().count();
+                    match lengths.iter_mut().find(|(length, _)| *length == n) {
+                        Some((_, count)) => *count += 1,
+                        None => lengths.push((n, 1)),
+                    }
+                }
+                lengths.sort();
+                let lengths: Vec<String> = lengths.iter().map(|(length, count)| format!("{} of {} letters", count, length)).collect();
+                println!("{}: {} words, {}", filename, check.words.len(), lengths.join(", "));
+                for (line, text) in &check.malformed { println!("  line {}: {:?} isn't a word", line, text); }
+                for (line, word) in &check.duplicates { println!("  line {}: {} is repeated", line, word); }
+                if check.unnormalized > 0 { println!("  {} of the words need trimming or lowercasing; dict normalize fixes that", check.unnormalized); }
+                if !check.malformed.is_empty() || !check.duplicates.is_empty() || check.unnormalized > 0 { bad += 1; }
+            }
+            if bad > 0 { return Err(Error::Input(format!("{} of {} lists need fixing", bad, lists.len()))) }
+        },
+        ("merge", lists) | ("normalize", lists @ [_]) => {
+            let mut words = Vec::new();
+            for filename in lists {
+                let check = check_file(filename)?;
+                for (line, text) in &check.malformed { eprintln!("{}:{}: skipping {:?}, which isn't a word", filename, line, text); }
+                words.extend(check.words);
+            }
+            words.sort();
+            words.dedup();
+            for word in words { println!("{}", word); }
+        },
+        ("diff", [old, new]) => {
+            let list = |filename: &str| read_dict(filename, options.length, options.max_words).map_err(|e| list_error(filename, e));
+            wordlist_diff(&list(old)?, &list(new)?);
+        },
+        ("normalize" | "diff", _) => return Err(usage_error(&format!("wrong number of lists for dict {}", args[0]))),
+        (command, _) => return Err(usage_error(&format!("unknown dict command {}", command))),
+    }
+    Ok(())
+}
+
+// The rules of the Wordle implementations whose differences change the
+// best play. All of them check guesses against the allowed words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Profile {
+    // The New York Times' game, which never repeats an answer.
+    Nyt,
+    // Josh Wardle's original, whose answers come from the goal list in
+    // order, one a day from 2021-06-19.
+    Original,
+    // hello wordl, which picks a new answer each game from every word it
+    // allows, repeats and all.
+    HelloWordl,
+}
+
+// The original game's first day, 2021-06-19, as days since 1970-01-01.
+const ORIGINAL_EPOCH: i64 = 18797;
+
+#[derive(Debug, Default)]
+struct Options {
+    // Keep every line of output within 40 columns.
+    compact: bool,
+
+    // The keys that play the suggested guess, take back the last, print a
+    // hint and suggest the next best guess instead, in assist.
+    keys: Vec<(Action, Key)>,
+
+    // Print only recommendations and outcomes, without the board, the
+    // pattern or a progress bar, e.g. for scripts; or add how long each
+    // recommendation took and what it searched.
+    quiet: bool,
+    verbose: bool,
+
+    // Redraw the whole screen each turn, with the board and keyboard beside
+    // the goals left and the best guesses.
+    tui: bool,
+
+    // Report each turn as a line of JSON instead of text.
+    json: bool,
+
+    // Read each line of input as a JSON command too, answering each with a
+    // line of JSON.
+    protocol: bool,
+
+    // Also list this many of the best guesses, with what each could leave.
+    top: Option<usize>,
+
+    // Draw the board and heatmap with ANSI colors, unless --no-color, the
+    // NO_COLOR environment variable or output to a pipe says otherwise.
+    color: bool,
+
+    // The language turns are described in, from --ui-lang, or else the
+    // variant's if there's a catalog for it.
+    messages: Catalog,
+
+    // The colors drawn in, from --theme or the config file.
+    theme: Theme,
+
+    // Leave the guessed words out of the share grid printed on a win.
+    spoiler_free: bool,
+
+    // Hide the words in the share grid and the Markdown summary behind
+    // Discord's ||spoiler|| markup, leaving the tiles showing.
+    spoiler_markup: bool,
+
+    // A word frequency list, making common goals likelier, and the priors
+    // read from it once the lists are loaded.
+    frequencies: Option<String>,
+    priors: Option<Arc<Priors>>,
+
+    // Guesses allowed before the game is lost.
+    guesses: usize,
+
+    // The word lists are shuffled, randomly or from a fixed seed for
+    // reproducible runs, unless --no-shuffle keeps them in file order.
+    shuffle: bool,
+    seed: Option<u64>,
+
+    // The game to play, whose built-in lists, alphabet and length are the
+    // defaults.
+    variant: Variant,
+
+    // Word lists of goals and of other allowed guesses, if not the built-in
+    // ones. Alternatively, --allowed lists every allowed guess, to
+    // which the goals are only added with --goals-are-allowed.
+    goals: Option<String>,
+    dict: Option<String>,
+    allowed: Option<String>,
+    goals_are_allowed: bool,
+
+    // Where update-wordlists installs each variant's lists, which are read
+    // instead of the built-in ones once there, and where it downloads them
+    // from: a URL or directory with a goals.txt and an extra.txt.
+    lists_dir: Option<String>,
+    lists_source: Option<String>,
+
+    // Read at most this many words from each word list.
+    max_words: Option<usize>,
+
+    // Letters per word; lists are filtered to words of this length.
+    length: usize,
+
+    // Wordle's hard mode: greens stay put and yellows are reused.
+    hard: bool,
+
+    // Strict hard mode: greens stay put, yellows move and grays are never
+    // replayed, which is exactly the set of words matching the Pattern.
+    ultra_hard: bool,
+
+    // The words guesses are checked against once the lists are loaded,
+    // unless --allow-any takes any word of the right length, e.g. for a
+    // variant whose lists differ.
+    allow_any: bool,
+
+    // Check after each guess that the goals left are exactly the ones every
+    // guess would have scored that way against; see `Solver::verifying`.
+    verify: bool,
+
+    // Score outcomes as searches need them instead of precomputing them in
+    // an `OutcomeMatrix`, and keep no transposition table, for small machines
+    // and big lists.
+    low_memory: bool,
+
+    // The implementation whose rules are played by, from --profile, and
+    // answers it has used already, which it won't use again.
+    profile: Option<Profile>,
+    past_answers: Option<String>,
+
+    // Take any allowed word as a possible answer from the start, instead of
+    // only once no goal fits.
+    answers_anywhere: bool,
+
+    // Refuse guesses that could leave the goal unfound within the allowed
+    // guesses; see `Solver::guaranteeing`.
+    guarantee: bool,
+    words: Arc<HashSet<Word>>,
+
+    // Goal words ruled out by their endings; guesses are unaffected.
+    goal_filters: Vec<SuffixFilter>,
+
+    // Name of the strategy recommending guesses; see `strategy`.
+    strategy: String,
+
+    // How many guesses ahead minimax looks, 1 or 2.
+    depth: usize,
+
+    // How long minimax may search before settling for its best guess so far,
+    // and how many of the most promising non-goal guesses it scores.
+    budget: Option<Duration>,
+    prune: Option<usize>,
+
+    // Boards played at once in assist mode, e.g. 4 for Quordle.
+    boards: Option<usize>,
+
+    // Strategies for `bench` to play against each other, e.g. "minimax,entropy".
+    compare: Vec<String>,
+
+    // Starting constraints for `best`, e.g. "+a@1,-e,?r@3".
+    constraints: Option<Pattern>,
+
+    // Guesses already played, e.g. "crane,sloth", scored against the goal.
+    history: Vec<Word>,
+
+    // A pasted board of guesses already played, with their tiles.
+    board: Option<String>,
+
+    // What `optimal` minimizes, and how many guesses it tries per node if
+    // it doesn't need to be exact.
+    objective: Option<Objective>,
+
+    // How many threads `optimal` searches with, and where it saves its
+    // progress to resume from.
+    threads: Option<usize>,
+    checkpoint: Option<String>,
+    breadth: Option<usize>,
+
+    // Review each guess once a game is over, as the review command does.
+    review: bool,
+
+    // Print each game bench or simulate lost, turn by turn.
+    show_failures: bool,
+
+    // Write a finished game up in Markdown, from --summary markdown.
+    markdown_summary: bool,
+
+    // Whether `openers` also ranks fixed pairs of first and second guesses.
+    pairs: bool,
+
+    // Where recommendations for the opening are remembered between runs, if
+    // anywhere; see `Cached`.
+    cache: Option<String>,
+
+    // An opening book to play the first turns from instead of the built-in
+    // one, or none at all; see `Book`.
+    book: Option<String>,
+    no_book: bool,
+
+    // Positions searched so far this run, shared by every strategy and
+    // search so none is searched twice.
+    table: Arc<TranspositionTable>,
+
+    // Where finished games are recorded for `stats`, and whether they are:
+    // by default only hosted ones, which are the player's own.
+    stats: Option<String>,
+    record: Option<bool>,
+
+    // With daily, the day whose puzzle is being played, which the game is
+    // recorded under instead of the day it's played.
+    daily: Option<i64>,
+
+    // Where `bench` and `simulate` write a row per goal, as JSON if it ends
+    // in .json, a page to share if it ends in .html and CSV otherwise.
+    report: Option<String>,
+
+    // Where `tree` writes the decision tree, as JSON, with DOT beside it.
+    out: Option<String>,
+
+    // A decision tree written by `tree` to follow instead of searching.
+    tree: Option<String>,
+
+    // Where a JSON array of what each search took is written, an entry per
+    // turn, and the entries so far.
+    metrics_out: Option<String>,
+    metrics_log: Mutex<Vec<String>>,
+
+    // Print the usage and exit.
+    help: bool,
+}
+
+const USAGE: &str = "\
+usage: wordlesolve [flags] <command>
+
+commands:
+  <goal>                    play against a known goal, typing guesses
+  assist                    recommend guesses for a game played elsewhere
+  play <goal>               watch the strategy solve a goal
+  bench                     play every goal and report guess counts
+  simulate [trials]         play every goal in several trials, 10 by default,
+                            for a strategy with random guesses like sampled
+  hardest                   the goals the strategy needs the most guesses
+                            for, and the word Absurdle's host would hold out
+  optimal                   search for an optimal decision tree
+  openers                   rank fixed first guesses over every goal
+  tree                      expand the strategy into a decision tree
+  verify [games]            check the goal filters against scoring each goal
+                            directly over random games, 1000 by default
+  book <file> [opener]...   add the strategy's replies to each outcome of
+                            the openers, or of its own, to an opening book
+  best                      recommend one guess for --constraints
+  match <template> [required] [excluded]
+                            list the words with the letters of a template
+                            like _r_ne in place, every required letter
+                            and none of the excluded ones
+  burner [weighted]         the guess probing the most letters --constraints
+                            says nothing about, weighted by the goals left
+  absurdle [auto]           play against an adversarial host
+  host [date]               host a game with a secret goal, the same one
+                            for everyone on a date like 2026-01-31 or today
+  daily [assist] [date]     host the puzzle of a date, today by default, as
+                            host does, or assist with one played elsewhere,
+                            recording it once under that date
+  stats                     streaks and guess counts of the recorded games
+  analyze [board]           recommend a guess for a pasted board
+  review [board]            judge each guess of a pasted board's game
+  import [grid]             recommend a guess for a share grid of --history
+  crowd <goal>              play by majority vote of lines on stdin
+  wordlist diff <old> <new> compare two word lists
+  dict validate <list>...   report malformed, repeated and unnormalized lines
+  dict merge <list>...      print the lists' words together, sorted, once each
+  dict normalize <list>     the same for one list
+  dict diff <old> <new>     the same as wordlist diff
+  update-wordlists [goals extra]
+                            download the variant's goals and other allowed
+                            guesses, from --lists-source unless given, check
+                            them and install them, backing up the old ones
+
+flags:
+  --variant NAME            play a game with built-in lists, e.g. wordle
+  --lang CODE               the same, by language, e.g. en
+  --goals FILE              goal words (default Wordle's answers); - for stdin
+  --dict FILE               allowed guesses besides the goals (default Wordle's)
+  --allowed FILE            every allowed guess, instead of --dict
+  --goals-are-allowed       add the goals to --allowed's guesses
+  --ui-lang CODE            the language games describe turns in, en or es
+                            (default the variant's, if it's one of those)
+  --lists-dir DIR           where update-wordlists installs lists, read
+                            instead of the built-in ones once there
+                            (default ~/.local/share/wordlesolve/lists)
+  --lists-source URL        where update-wordlists downloads goals.txt and
+                            extra.txt from, a URL or a directory
+  --length N                letters per word (default 5)
+  --alphabet SYMBOLS        letters words are spelled with (default a-z)
+  --fold-accents            match letters with accents the alphabet lacks as
+                            the letters without them, e.g. é as e, showing
+                            words as the lists spell them
+  --max-words N             read at most N words from each list
+  --strategy NAME           minimax, entropy, expected, quick or sampled, a
+                            random goal left (default minimax)
+  --quick, --fast           same as --strategy quick, the letter frequency
+                            heuristic used anyway when there are too many
+                            goals left for the chosen strategy
+  --depth N                 moves minimax looks ahead, 1 or 2
+  --budget T                let minimax search for at most T, e.g. 2s,
+                            and settle for the best guess so far
+  --prune K                 let minimax score only the K most promising
+                            guesses besides the goals
+  --hard                    only recommend and accept Wordle hard mode guesses
+  --ultra-hard              only recommend and accept guesses that could win
+  --allow-any               accept guesses missing from the word lists
+  --answers-anywhere        treat every allowed word as a possible answer, as
+                            assist does anyway once no goal word fits
+  --profile NAME            play by a Wordle implementation's rules, which
+                            all check guesses against the allowed words:
+                            nyt never repeats an answer, ruling out
+                            --past-answers; original takes each day's answer
+                            from the goal list in order from 2021-06-19,
+                            ruling out the days' before; hello-wordl takes
+                            any allowed word as the answer, repeats and all
+  --past-answers FILE       answers used already, which aren't goals unless
+                            --profile hello-wordl repeats them
+  --guarantee               never recommend a guess that could lose within
+                            --guesses, even at the cost of the average
+  --metrics-out FILE        write what each search took, the guesses it scored
+                            and the lookups it made, as JSON; -v prints it
+  --verify                  check the goals left after every guess against
+                            scoring each goal directly, panicking if they differ
+  --low-memory              score outcomes as they're needed instead of
+                            precomputing them, keeping no search tables; slower
+  --boards N                assist N boards at once, as in Quordle
+  --compare A,B             strategies for bench to compare
+  --constraints C           constraints for best, e.g. +a@1,-e,?r@3
+  --history W,W             guesses already played, against the goal or for import
+  --board FILE              a pasted board of guesses already played
+  --resume FILE             same as --board, e.g. for a game saved with save
+  --exclude-suffix S        rule out goals ending in S, e.g. s!ss
+  --no-plurals              rule out plural goals
+  --no-past-tense           rule out past tense goals
+  --objective total|worst   what optimal minimizes
+  --threads N               threads optimal searches with (default one per core)
+  --checkpoint FILE         save optimal's search to FILE every minute, and
+                            resume from it if it's there
+  --breadth N               guesses optimal tries at each node, or openers
+                            tries pairs from (default 10)
+  --pairs                   also rank fixed pairs of opening guesses
+  --show-failures           print each game bench or simulate lost, turn by
+                            turn, and when too many goals were left
+  --report FILE             write bench's or simulate's results per goal to
+                            FILE, as CSV or, if it ends in .json or .html,
+                            JSON or a self-contained web page
+  --out FILE                write tree's JSON to FILE and DOT beside it
+  --tree FILE               follow a tree's guesses, searching only off it
+  --compact                 keep output within 40 columns
+  -q, --quiet               print only recommendations and outcomes
+  -v, --verbose             also print how long each recommendation took
+  --tui                     redraw the screen each turn, in panes; in assist at a terminal,
+                            click a word to play it, or a tile to change its color
+  --key-accept KEY          in assist, the key playing the suggested guess, tab by
+                            default; a key is tab, ctrl- and a letter, or a
+                            character, which acts only on an empty line
+  --key-undo KEY            the key taking back the last guess (default ctrl-b)
+  --key-hint KEY            the key printing a hint (default ctrl-t)
+  --key-cycle KEY           the key suggesting the next best guess instead
+                            (default ctrl-n)
+  --no-color                print the board and heatmap without ANSI colors
+  --theme THEME             the colors of the tiles, keyboard and --tui's
+                            headings: default, high-contrast, solarized, or
+                            256-color numbers for green, yellow and gray
+                            tiles, then optionally their letters and the
+                            headings, e.g. 28,178,240,15,15
+  --frequencies FILE        word,weight lines making common goals likelier
+  --seed N                  shuffle the word lists reproducibly
+  --no-shuffle              keep the word lists in file order
+  --cache FILE              remember opening recommendations in FILE
+                            (default ~/.cache/wordlesolve/openers)
+  --no-cache                recompute opening recommendations every run
+  --stats FILE              record games in FILE for stats
+                            (default ~/.local/share/wordlesolve/stats)
+  --record                  record assisted and played games too, not only
+                            hosted ones
+  --no-record               record no games
+  --book FILE               play the first turns from the opening book in
+                            FILE, written by book, not the built-in one
+  --no-book                 search from the first turn
+  --json                    report each turn as a line of JSON
+  --protocol                assist with JSON commands, one per line, e.g.
+                            {\"guess\":\"crane\",\"outcome\":\"gybgg\"}
+  --top N                   also rank the N best guesses by what they leave
+  --review                  judge each guess when a game is over
+  --summary markdown        write a game up to paste once it's over, or
+                            review's board instead of judging it
+  --spoiler-free            leave the words out of the share grid on a win
+  --spoiler-markup          hide the share grid's and --summary's words as
+                            ||spoilers||, for chats like Discord
+  --guesses N               guesses allowed before the game is lost, and
+                            bench and simulate count it failed (default 6)
+  --config FILE             read default flags from FILE
+                            (default ~/.config/wordlesolve/config.toml)
+  --no-config               ignore the config file
+  --help                    print this
+
+the config file is TOML with a key per flag, without its dashes, e.g.
+hard = true, color = false, goals = \"words.txt\" or length = 6; a table's
+keys are flags starting with its name, so undo = \"ctrl-z\" under [key] is
+--key-undo ctrl-z, and an array gives its flag once for each value
+
+exit status: 0 on success, 1 on an I/O error, 2 on a command line mistake,
+3 if a file can't be read or written, 4 if its contents can't be used
+";
+
+// Why a run failed. Each kind exits with its own status.
+#[derive(Debug)]
+enum Error {
+    // A command line mistake: status 2.
+    Usage(String),
+    // A word list that couldn't be read, or another file that couldn't be
+    // read or written: status 3.
+    List(String, io::Error),
+    File(String, io::Error),
+    // Input that was read but can't be used, such as a malformed word list:
+    // status 4.
+    Input(String),
+    // Anything else, such as a closed stdout: status 1.
+    Io(io::Error),
+}
+
+impl Error {
+    fn status(&self) -> u8 {
+        match self {
+            Error::Usage(_) => 2,
+            Error::List(..) | Error::File(..) => 3,
+            Error::Input(_) => 4,
+            Error::Io(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Usage(message) => write!(formatter, "{}; see --help", message),
+            Error::List(path, e) if e.kind() == io::ErrorKind::NotFound => {
+                write!(formatter, "can't find word list {}; Wordle's goals.txt and extra.txt are built in, \
+                                   so --goals and --dict are only needed for other lists", path)
+            },
+            Error::List(path, e) => write!(formatter, "can't read word list {}: {}", path, e),
+            Error::File(path, e) => write!(formatter, "{}: {}", path, e),
+            Error::Input(message) => formatter.write_str(message),
+            Error::Io(e) => e.fmt(formatter),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+// A command line mistake, pointing at --help.
+fn usage_error(message: &str) -> Error {
+    Error::Usage(message.to_string())
+}
+
+// An error from reading or writing `path`, telling unusable contents apart
+// from a file that couldn't be opened.
+fn file_error(path: &str, e: io::Error) -> Error {
+    match e.kind() {
+        io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput => Error::Input(format!("{}: {}", path, e)),
+        _ => Error::File(path.to_string(), e),
+    }
+}
+
+// Like file_error for a word list.
+fn list_error(path: &str, e: io::Error) -> Error {
+    match file_error(path, e) {
+        Error::File(path, e) => Error::List(path, e),
+        e => e,
+    }
+}
+
+fn read_file(path: &str) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|e| file_error(path, e))
+}
+
+impl Options {
+    // Splits flags out of the command line, returning the positional arguments.
+    //
+    // --alphabet, e.g. "abcdefghijklmnñopqrstuvwxyz", is installed first
+    // wherever it appears, since the words in other flags are spelled with
+    // it, folding accents if the last of --fold-accents and
+    // --no-fold-accents says to.
+    fn parse(args: impl Iterator<Item = String>) -> Result<(Self, Vec<String>), Error> {
+        let args: Vec<String> = args.collect();
+        let variant = match args.iter().rposition(|a| a == "--variant" || a == "--lang") {
+            Some(i) => {
+                let name = args.get(i + 1).ok_or_else(|| usage_error(&format!("{} needs a valid value", args[i])))?;
+                variant(name).ok_or_else(|| {
+                    let names: Vec<&str> = VARIANTS.iter().map(|v| v.name).collect();
+                    usage_error(&format!("no built-in variant {:?}; this build has {}", name, names.join(", ")))
+                })?
+            },
+            None => &VARIANTS[0],
+        };
+        let variant = *variant;
+        let symbols: Alphabet = match args.iter().rposition(|a| a == "--alphabet") {
+            Some(i) => flag_value(&args[i], args.get(i + 1).cloned())?,
+            None => variant.alphabet.parse().expect("built-in alphabets are valid"),
+        };
+        let fold = |flag: &str| args.iter().rposition(|a| a == flag);
+        let symbols = if fold("--fold-accents") > fold("--no-fold-accents") { symbols.folding_accents() } else { symbols };
+        symbols.install().expect("nothing reads words before the options");
+        let mut options = Self {
+            variant,
+            strategy: "minimax".to_string(),
+            depth: 1,
+            length: variant.length,
+            guesses: 6,
+            keys: vec![(Action::Accept, Key::Tab), (Action::Undo, Key::Ctrl('b')), (Action::Hint, Key::Ctrl('t')), (Action::Cycle, Key::Ctrl('n'))],
+            shuffle: true,
+            cache: default_cache(),
+            stats: default_stats(),
+            lists_dir: default_lists(),
+            messages: Catalog::find(variant.lang).unwrap_or_default(),
+            ..Self::default()
+        };
+        let mut positional = Vec::new();
+        let mut no_color = false;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--alphabet" | "--config" | "--variant" | "--lang" => { args.next(); },
+                "--no-config" | "--fold-accents" | "--no-fold-accents" => {},
+                "--compact" => options.compact = true,
+                "-q" | "--quiet" => (options.quiet, options.verbose) = (true, false),
+                "-v" | "--verbose" => (options.quiet, options.verbose) = (false, true),
+                "--tui" => options.tui = true,
+                "--key-accept" | "--key-undo" | "--key-hint" | "--key-cycle" => {
+                    let action = match arg.as_str() {
+                        "--key-accept" => Action::Accept,
+                        "--key-undo" => Action::Undo,
+                        "--key-hint" => Action::Hint,
+                        _ => Action::Cycle,
+                    };
+                    let key = args.next().as_deref().and_then(parse_key)
+                        .ok_or_else(|| usage_error(&format!("{} needs a key: tab, ctrl- and a letter, or a character", arg)))?;
+                    options.keys.retain(|&(a, k)| a != action && k != key);
+                    options.keys.push((action, key));
+                },
+                "--json" => options.json = true,
+                "--top" => options.top = Some(flag_value(&arg, args.next())?),
+                "--spoiler-free" => options.spoiler_free = true,
+                "--spoiler-markup" => options.spoiler_markup = true,
+                "--no-color" => no_color = true,
+                "--theme" => options.theme = args.next().and_then(|t| t.parse().ok())
+                    .ok_or_else(|| usage_error("--theme is default, high-contrast, solarized or colors like 28,178,240"))?,
+                "--frequencies" => options.frequencies = Some(flag_value(&arg, args.next())?),
+                "--seed" => options.seed = Some(flag_value(&arg, args.next())?),
+                "--no-shuffle" => options.shuffle = false,
+                "--cache" => options.cache = Some(flag_value(&arg, args.next())?),
+                "--no-cache" => options.cache = None,
+                "--stats" => options.stats = Some(flag_value(&arg, args.next())?),
+                "--ui-lang" => {
+                    let lang: String = flag_value(&arg, args.next())?;
+                    let langs: Vec<&str> = messages::CATALOGS.iter().map(|c| c.lang).collect();
+                    options.messages = Catalog::find(&lang).ok_or_else(|| usage_error(&format!("no messages in {:?}; there are {}", lang, langs.join(", "))))?;
+                },
+                "--lists-dir" => options.lists_dir = Some(flag_value(&arg, args.next())?),
+                "--lists-source" => options.lists_source = Some(flag_value(&arg, args.next())?),
+                "--record" => options.record = Some(true),
+                "--no-record" => options.record = Some(false),
+                "--book" => (options.book, options.no_book) = (Some(flag_value(&arg, args.next())?), false),
+                "--no-book" => options.no_book = true,
+                "--out" => options.out = Some(flag_value(&arg, args.next())?),
+                "--report" => options.report = Some(flag_value(&arg, args.next())?),
+                "--tree" => options.tree = Some(flag_value(&arg, args.next())?),
+                "--budget" => {
+                    let value = args.next();
+                    options.budget = Some(value.as_deref().and_then(parse_duration).ok_or_else(|| usage_error("--budget needs a time, e.g. 2s or 500ms"))?);
+                },
+                "--prune" => {
+                    options.prune = Some(flag_value(&arg, args.next())?);
+                    if options.prune == Some(0) { return Err(usage_error("--prune must be at least 1")) }
+                },
+                "--protocol" => {
+                    options.protocol = true;
+                    options.json = true;
+                },
+                "--guesses" => {
+                    options.guesses = flag_value(&arg, args.next())?;
+                    if options.guesses == 0 { return Err(usage_error("--guesses must be at least 1")) }
+                },
+                "--hard" => options.hard = true,
+                "--ultra-hard" => options.ultra_hard = true,
+                "--allow-any" => options.allow_any = true,
+                "--verify" => options.verify = true,
+                "--low-memory" => options.low_memory = true,
+                "--guarantee" => options.guarantee = true,
+                "--answers-anywhere" => options.answers_anywhere = true,
+                "--profile" => {
+                    let profile = match args.next().as_deref() {
+                        Some("nyt") => Profile::Nyt,
+                        Some("original") => Profile::Original,
+                        Some("hello-wordl") => Profile::HelloWordl,
+                        _ => return Err(usage_error("--profile is nyt, original or hello-wordl")),
+                    };
+                    options.profile = Some(profile);
+                    options.allow_any = false;
+                    options.answers_anywhere = profile == Profile::HelloWordl;
+                },
+                "--past-answers" => options.past_answers = Some(flag_value(&arg, args.next())?),
+                "--metrics-out" => options.metrics_out = Some(flag_value(&arg, args.next())?),
+                "--strategy" => options.strategy = flag_value(&arg, args.next())?,
+                "--depth" => options.depth = flag_value(&arg, args.next())?,
+                "--boards" => options.boards = Some(flag_value(&arg, args.next())?),
+                "--compare" => {
+                    let names: String = flag_value(&arg, args.next())?;
+                    options.compare = names.split(',').map(|n| n.trim().to_string()).collect();
+                },
+                "--quick" | "--fast" => options.strategy = "quick".to_string(),
+                "--constraints" => options.constraints = Some(flag_value(&arg, args.next())?),
+                "--history" => {
+                    let guesses: String = flag_value(&arg, args.next())?;
+                    for guess in guesses.split(',') {
+                        options.history.push(flag_value(&arg, Some(guess.trim().to_string()))?);
+                    }
+                },
+                "--board" | "--resume" => options.board = Some(flag_value(&arg, args.next())?),
+                "--exclude-suffix" => options.goal_filters.push(flag_value(&arg, args.next())?),
+                "--no-plurals" => options.goal_filters.push(PLURALS.parse().unwrap()),
+                "--no-past-tense" => options.goal_filters.push(PAST_TENSE.parse().unwrap()),
+                "--objective" => options.objective = Some(flag_value(&arg, args.next())?),
+                "--breadth" => options.breadth = Some(flag_value(&arg, args.next())?),
+                "--threads" => options.threads = Some(flag_value(&arg, args.next())?),
+                "--checkpoint" => options.checkpoint = Some(flag_value(&arg, args.next())?),
+                "--pairs" => options.pairs = true,
+                "--review" => options.review = true,
+                "--show-failures" => options.show_failures = true,
+                "--summary" => {
+                    let format: String = flag_value(&arg, args.next())?;
+                    if format != "markdown" { return Err(usage_error(&format!("unknown summary format {:?}, expected markdown", format))) }
+                    options.markdown_summary = true;
+                },
+                "--length" => {
+                    options.length = flag_value(&arg, args.next())?;
+                    if !(1..=MAX_LEN).contains(&options.length) {
+                        return Err(usage_error(&format!("--length must be 1 to {}", MAX_LEN)));
+                    }
+                },
+                "--goals" => options.goals = Some(flag_value(&arg, args.next())?),
+                "--dict" => options.dict = Some(flag_value(&arg, args.next())?),
+                "--allowed" => options.allowed = Some(flag_value(&arg, args.next())?),
+                "--goals-are-allowed" => options.goals_are_allowed = true,
+                "--max-words" => options.max_words = Some(flag_value(&arg, args.next())?),
+                "--help" | "-h" => options.help = true,
+                _ if arg.starts_with("--") => return Err(usage_error(&format!("unknown flag {}", arg))),
+                _ => positional.push(arg),
+            }
+        }
+        options.color = !no_color && env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal();
+        Ok((options, positional))
+    }
+}
+
+impl Options {
+    // The theme, if drawing in color.
+    fn colors(&self) -> Option<&Theme> {
+        if self.color { Some(&self.theme) } else { None }
+    }
+}
+
+// The opener cache under $XDG_CACHE_HOME, or ~/.cache without it.
+fn default_cache() -> Option<String> {
+    let dir = env::var("XDG_CACHE_HOME").ok().filter(|d| !d.is_empty())
+        .or_else(|| env::var("HOME").ok().map(|home| format!("{}/.cache", home)))?;
+    Some(format!("{}/wordlesolve/openers", dir))
+}
+
+// The stats file under $XDG_DATA_HOME, or ~/.local/share without it.
+fn default_stats() -> Option<String> {
+    let dir = env::var("XDG_DATA_HOME").ok().filter(|d| !d.is_empty())
+        .or_else(|| env::var("HOME").ok().map(|home| format!("{}/.local/share", home)))?;
+    Some(format!("{}/wordlesolve/stats", dir))
+}
+
+// The directory of installed word lists under $XDG_DATA_HOME, or
+// ~/.local/share without it.
+fn default_lists() -> Option<String> {
+    let dir = env::var("XDG_DATA_HOME").ok().filter(|d| !d.is_empty())
+        .or_else(|| env::var("HOME").ok().map(|home| format!("{}/.local/share", home)))?;
+    Some(format!("{}/wordlesolve/lists", dir))
+}
+
+// Where defaults for the flags are read from, if there's a home directory.
+fn default_config() -> Option<String> {
+    let dir = env::var("XDG_CONFIG_HOME").ok().filter(|d| !d.is_empty())
+        .or_else(|| env::var("HOME").ok().map(|home| format!("{}/.config", home)))?;
+    Some(format!("{}/wordlesolve/config.toml", dir))
+}
+
+// The flags a config file stands for. It's TOML with a key per flag, named
+// without its dashes: `key = true` and `key = false` become --key and
+// --no-key, e.g. `hard = true` or `color = false`, a string or number is the
+// flag's value, e.g. `goals = "words.txt"` or `length = 6`, and an array
+// gives the flag once for each of its values. Tables and dotted keys join
+// their names with dashes, so `undo = "ctrl-z"` under `[key]` is --key-undo,
+// and underscores in keys are read as dashes.
+fn config_args(path: &str, text: &str) -> Result<Vec<String>, Error> {
+    let mut args = Vec::new();
+    let mut table = String::new();
+    let mut seen = HashSet::new();
+    for (i, line) in text.lines().enumerate() {
+        let error = |message: &str| Error::Input(format!("{}:{}: {}", path, i + 1, message));
+        let mut rest = line.trim_start();
+        if rest.is_empty() || rest.starts_with('#') { continue }
+        if let Some(header) = rest.strip_prefix('[') {
+            if header.starts_with('[') { return Err(error("arrays of tables aren't flags")) }
+            let (name, after) = toml_key(header).map_err(&error)?;
+            let after = after.trim_start().strip_prefix(']').ok_or_else(|| error("expected ] after the table name"))?;
+            toml_end(after).map_err(&error)?;
+            table = format!("{}-", name);
+            continue;
+        }
+        let (key, after) = toml_key(rest).map_err(&error)?;
+        let key = format!("{}{}", table, key);
+        rest = after.trim_start().strip_prefix('=').ok_or_else(|| error("expected a line like key = value"))?.trim_start();
+        if !seen.insert(key.clone()) { return Err(error(&format!("{} is set twice", key))) }
+        let values = match rest.strip_prefix('[') {
+            Some(mut items) => {
+                let mut values = Vec::new();
+                loop {
+                    items = items.trim_start();
+                    if let Some(after) = items.strip_prefix(']') { rest = after; break }
+                    let (value, after) = toml_value(items).map_err(&error)?;
+                    values.push(value);
+                    items = after.trim_start();
+                    items = items.strip_prefix(',').unwrap_or_else(|| if items.starts_with(']') { items } else { "" });
+                    if items.is_empty() { return Err(error("expected , or ] in the array, all on one line")) }
+                }
+                values
+            },
+            None => {
+                let (value, after) = toml_value(rest).map_err(&error)?;
+                rest = after;
+                vec![value]
+            },
+        };
+        toml_end(rest).map_err(&error)?;
+        for value in values {
+            match value.as_str() {
+                "true" => args.push(format!("--{}", key)),
+                "false" => args.push(format!("--no-{}", key)),
+                _ => args.extend([format!("--{}", key), value]),
+            }
+        }
+    }
+    Ok(args)
+}
+
+// A TOML key at the start of `text`, bare, quoted or dotted, with its parts
+// joined by dashes, and the text after it.
+fn toml_key(text: &str) -> Result<(String, &str), &'static str> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    loop {
+        rest = rest.trim_start();
+        let (part, after) = if rest.starts_with('"') || rest.starts_with('\'') {
+            toml_string(rest)?
+        } else {
+            let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-')).unwrap_or(rest.len());
+            (rest[..end].to_string(), &rest[end..])
+        };
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') { return Err("expected a flag name") }
+        parts.push(part.replace('_', "-"));
+        match after.trim_start().strip_prefix('.') {
+            Some(after) => rest = after,
+            None => return Ok((parts.join("-"), after)),
+        }
+    }
+}
+
+// A TOML string, boolean or number at the start of `text` as a flag would
+// take it, and the text after it.
+fn toml_value(text: &str) -> Result<(String, &str), &'static str> {
+    if text.starts_with('"') || text.starts_with('\'') { return toml_string(text) }
+    let end = text.find(|c: char| c.is_whitespace() || c == ',' || c == ']' || c == '#').unwrap_or(text.len());
+    let value = &text[..end];
+    let number = value.replace('_', "");
+    if value == "true" || value == "false" || number.parse::<i64>().is_ok() || number.parse::<f64>().is_ok_and(f64::is_finite) {
+        Ok((number, &text[end..]))
+    } else if value.is_empty() || value.starts_with('{') {
+        Err("expected a string, boolean, number or array")
+    } else {
+        Err("expected quotes around the string")
+    }
+}
+
+// A basic "string", with its escapes, or a literal 'string' at the start of
+// `text`, and the text after it. Multi-line strings aren't flags' values.
+fn toml_string(text: &str) -> Result<(String, &str), &'static str> {
+    if text.starts_with("\"\"\"") || text.starts_with("'''") { return Err("multi-line strings aren't supported") }
+    if let Some(literal) = text.strip_prefix('\'') {
+        let end = literal.find('\'').ok_or("expected a closing quote")?;
+        return Ok((literal[..end].to_string(), &literal[end + 1..]));
+    }
+    let mut value = String::new();
+    let mut chars = text[1..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &text[i + 2..])),
+            '\\' => value.push(match chars.next().map(|(_, c)| c) {
+                Some('"') => '"',
+                Some('\\') => '\\',
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('r') => '\r',
+                Some(u @ ('u' | 'U')) => {
+                    let digits: String = chars.by_ref().take(if u == 'u' { 4 } else { 8 }).map(|(_, c)| c).collect();
+                    u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32).ok_or("expected a unicode escape")?
+                },
+                _ => return Err("unknown escape in the string"),
+            }),
+            c => value.push(c),
+        }
+    }
+    Err("expected a closing quote")
+}
+
+// Checks nothing but a comment follows a value or table name.
+fn toml_end(text: &str) -> Result<(), &'static str> {
+    let text = text.trim_start();
+    if text.is_empty() || text.starts_with('#') { Ok(()) } else { Err("expected the line to end after the value") }
+}
+
+// A length of time like "2s", "500ms" or "1.5", in seconds.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let (number, scale) = match text.strip_suffix("ms") {
+        Some(ms) => (ms, 0.001),
+        None => (text.strip_suffix('s').unwrap_or(text), 1.0),
+    };
+    number.parse::<f64>().ok().filter(|n| n.is_finite() && *n >= 0.0).map(|n| Duration::from_secs_f64(n * scale))
+}
+
+fn flag_value<T: str::FromStr>(flag: &str, value: Option<String>) -> Result<T, Error> {
+    value.and_then(|v| v.parse().ok()).ok_or_else(|| usage_error(&format!("{} needs a valid value", flag)))
+}
+
+// Letters that can still appear, with their probability at each position.
+fn print_probabilities(goals: &[Word]) {
+    let probabilities = letter_probabilities(goals);
+    println!("  {}", (1..=probabilities.len()).map(|i| format!("{:>7}", i)).collect::<String>());
+    for (l, &c) in alphabet().symbols().iter().enumerate() {
+        if probabilities.iter().all(|p| p[l] == 0.0) { continue }
+        let row: String = probabilities.iter().map(|p| format!("{:>6.1}%", 100.0 * p[l])).collect();
+        println!("{} {}", c, row);
+    }
+}
+
+// What a search took by the counts, for --verbose.
+fn print_metrics(metrics: &Metrics) {
+    let percent = |rate: f64| format!("{:.1}%", 100.0 * rate);
+    if metrics.guesses_scored > 0 {
+        println!("  scored {} guesses, cutting {} short ({})", metrics.guesses_scored, metrics.guesses_cut_off, percent(metrics.cut_off_rate()));
+    }
+    if metrics.table_lookups > 0 {
+        println!("  {} of {} table lookups hit ({})", metrics.table_hits, metrics.table_lookups, percent(metrics.table_hit_rate()));
+    }
+    if metrics.cache_lookups > 0 {
+        println!("  {} of {} cache and book lookups hit ({})", metrics.cache_hits, metrics.cache_lookups, percent(metrics.cache_hit_rate()));
+    }
+}
+
+// Solver::choose, logging what the search took for --metrics-out and
+// returning it for --verbose.
+fn choose_measured(solver: &Solver, strategy: &dyn Strategy, options: &Options) -> (Option<Word>, Metrics, Duration) {
+    let (before, start) = (Metrics::now(), Instant::now());
+    let recommended = solver.choose(strategy);
+    let (metrics, elapsed) = (Metrics::now().since(&before), start.elapsed());
+    log_metrics(options, vec![
+        ("turn", (solver.history().len() + 1).to_string()),
+        ("goals", solver.candidates().len().to_string()),
+        ("strategy", json_str(chooser(strategy, &metrics))),
+    ], &metrics, elapsed);
+    (recommended, metrics, elapsed)
+}
+
+// Adds an entry with `fields` and the counts to --metrics-out's array,
+// rewriting the file, so it's whole however the program ends.
+fn log_metrics(options: &Options, mut fields: Vec<(&str, String)>, metrics: &Metrics, elapsed: Duration) {
+    let Some(path) = &options.metrics_out else { return };
+    fields.extend([
+        ("seconds", format!("{:.6}", elapsed.as_secs_f64())),
+        ("guesses_scored", metrics.guesses_scored.to_string()),
+        ("guesses_cut_off", metrics.guesses_cut_off.to_string()),
+        ("table_lookups", metrics.table_lookups.to_string()),
+        ("table_hits", metrics.table_hits.to_string()),
+        ("cache_lookups", metrics.cache_lookups.to_string()),
+        ("cache_hits", metrics.cache_hits.to_string()),
+        ("quick_fallbacks", metrics.quick_fallbacks.to_string()),
+    ]);
+    let entry: Vec<String> = fields.iter().map(|(name, value)| format!("{}:{}", json_str(name), value)).collect();
+    let mut log = options.metrics_log.lock().unwrap();
+    log.push(format!("{{{}}}", entry.join(",")));
+    if let Err(e) = std::fs::write(path, format!("[\n{}\n]\n", log.join(",\n"))) { eprintln!("couldn't write metrics to {}: {}", path, e); }
+}
+
+// How much the transposition table has saved, for --verbose.
+fn print_table(options: &Options) {
+    println!("  {} positions remembered, {} looked up", options.table.len(), options.table.hits());
+}
+
+// Every letter still in some goal, commonest first: how likely the answer is
+// to have it anywhere, then at each position.
+fn print_letters(goals: &[Word]) {
+    let presence = letter_presence(goals);
+    let probabilities = letter_probabilities(goals);
+    let mut letters: Vec<usize> = (0..alphabet().symbols().len()).filter(|&l| presence[l] > 0.0).collect();
+    letters.sort_by(|&a, &b| presence[b].total_cmp(&presence[a]).then(a.cmp(&b)));
+    println!("      any{}", (1..=probabilities.len()).map(|i| format!("{:>7}", i)).collect::<String>());
+    for l in letters {
+        let row: String = probabilities.iter().map(|p| format!("{:>6.1}%", 100.0 * p[l])).collect();
+        println!("{} {:>6.1}%{}", alphabet().symbol(l as u8), 100.0 * presence[l], row);
+    }
+}
+
+fn print_preview(guess: Word, goals: &[Word]) {
+    let (greens, yellows) = expected_tiles(guess, goals);
+    println!("{}: {:.2} greens, {:.2} yellows expected", guess, greens, yellows);
+}
+
+// How the guess splits the goals: every outcome it could get, largest first,
+// with the goals left after each, listed when there are few.
+fn print_explanation(guess: Word, goals: &[Word]) {
+    let mut buckets: Vec<(Outcome, Vec<Word>)> = Vec::new();
+    for &goal in goals {
+        let outcome = Outcome::compare(goal, guess);
+        match buckets.iter_mut().find(|(o, _)| *o == outcome) {
+            Some((_, bucket)) => bucket.push(goal),
+            None => buckets.push((outcome, vec![goal])),
+        }
+    }
+    buckets.sort_by_key(|(o, b)| (std::cmp::Reverse(b.len()), std::cmp::Reverse(o.code())));
+    for (outcome, bucket) in &buckets {
+        let words = if bucket.len() <= 6 { bucket.iter().map(|w| w.to_string()).collect::<Vec<_>>().join(" ") } else { String::new() };
+        println!("{} {:>4}  {}", outcome, bucket.len(), words);
+    }
+    println!("{} splits {} goals into {} outcomes, at most {} left, {:.2} bits",
+             guess, goals.len(), buckets.len(), buckets.first().map_or(0, |(_, b)| b.len()), outcome_entropy(guess, goals));
+}
+
+// Background shades from dark to bright green.
+const HEAT: [u8; 5] = [22, 28, 34, 40, 46];
+
+// One row per position: letters the pattern rules out there are dotted out,
+// the rest are shaded by how many remaining goals have them in that spot.
+// Without color, the letters that could still be there are just listed.
+fn print_heatmap(pattern: &Pattern, goals: &[Word], color: bool) {
+    for (i, row) in letter_probabilities(goals).iter().enumerate() {
+        let max = row.iter().cloned().fold(0.0, f64::max);
+        let mut line = format!("{} ", i + 1);
+        for (&c, &n) in alphabet().symbols().iter().zip(row) {
+            if !pattern.allows(i, c) {
+                line.push('·');
+            } else if n == 0.0 || !color {
+                line.push(c);
+            } else {
+                let shade = HEAT[((n / max * HEAT.len() as f64).ceil() as usize).clamp(1, HEAT.len()) - 1];
+                line.push_str(&format!("\x1b[30;48;5;{}m{}\x1b[0m", shade, c));
+            }
+        }
+        println!("{}", line);
+    }
+}
+
+// The guesses so far stacked like the game board: with color, as capital
+// letters on green, yellow and gray tiles, otherwise each followed by its
+// tile emoji.
+fn print_board(history: &[(Word, Outcome)], colors: Option<&Theme>) {
+    for line in board_lines(history, colors) { println!("{}", line); }
+}
+
+fn board_lines(history: &[(Word, Outcome)], colors: Option<&Theme>) -> Vec<String> {
+    history.iter().map(|(guess, outcome)| {
+        let Some(theme) = colors else { return format!("{} {}", guess, outcome) };
+        in_reading_order(guess.to_string().chars().zip(outcome.tiles()).map(|(c, &o)| theme.tile(o, &format!(" {} ", c.to_uppercase()))).collect())
+    }).collect()
+}
+
+// Cells drawn in reading order: right to left for such alphabets, held there
+// by a left-to-right override so that terminals reordering text themselves
+// don't turn them back round. Text that's only printed is left in logical
+// order, for the terminal, or whatever reads it, to lay out.
+fn in_reading_order(mut cells: Vec<String>) -> String {
+    if !alphabet().is_rtl() { return cells.concat() }
+    cells.reverse();
+    format!("\u{202d}{}\u{202c}", cells.concat())
+}
+
+const KEYBOARD: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+// Like the game's keyboard: with color, keys are green once placed, yellow
+// once known present and gray once known absent; otherwise known-present
+// letters are capitalized and known-absent ones blanked out. Letters of the
+// alphabet missing from the QWERTY layout go on a fourth row, and more.
+fn print_keyboard(pattern: &Pattern, colors: Option<&Theme>) {
+    for line in keyboard_lines(pattern, colors) { println!("{}", line); }
+}
+
+fn keyboard_lines(pattern: &Pattern, colors: Option<&Theme>) -> Vec<String> {
+    let extra: Vec<char> = alphabet().symbols().iter().copied().filter(|&c| !KEYBOARD.iter().any(|r| r.contains(c))).collect();
+    let rows = KEYBOARD.iter().map(|r| r.chars().filter(|&c| alphabet().index(c).is_some()).collect::<String>());
+    // Big alphabets, like kana, take as many more rows as they need.
+    let extra = extra.chunks(KEYBOARD[0].len()).map(|r| r.iter().collect::<String>());
+    rows.chain(extra).enumerate().filter(|(_, r)| !r.is_empty()).map(|(indent, row)| {
+        let indent = indent.min(KEYBOARD.len());
+        if let Some(theme) = colors {
+            let keys: String = row.chars().map(|c| {
+                let tile = match pattern.key_state(c) {
+                    KeyState::Placed => LetterOutcome::Here,
+                    KeyState::Present => LetterOutcome::Elsewhere,
+                    KeyState::Absent => LetterOutcome::Nowhere,
+                    KeyState::Unused => return format!(" {} ", c.to_uppercase()),
+                };
+                theme.tile(tile, &format!(" {} ", c.to_uppercase()))
+            }).collect();
+            return format!("{}{}", " ".repeat(2 * indent), keys);
+        }
+        let keys: Vec<String> = row.chars().map(|c| match pattern.key_state(c) {
+            KeyState::Placed | KeyState::Present => c.to_uppercase().to_string(),
+            KeyState::Absent => ".".to_string(),
+            KeyState::Unused => c.to_string(),
+        }).collect();
+        format!("{}{}", " ".repeat(indent), keys.join(" "))
+    }).collect()
+}
+
+fn analyze(board: &[(Word, Outcome)], goals: Vec<Word>, dict: Vec<Word>, strategy: &dyn Strategy, options: &Options) {
+    for (guess, outcome) in board { println!("{} {}", guess, outcome); }
+    recommend_once(&indexed(Solver::with_pattern(goals, dict, Pattern::from_history(board)), options), strategy, options);
+}
+
+// How each guess of a game went: the goals it left, what the strategy would
+// have played, a grade for its worst case like the one given while playing,
+// how close it came to the strategy's guess and how lucky its tiles were,
+// then the skill and luck of the whole game. Then, if the goal is known or
+// the game was won, the game beside the strategy's line against the goal.
+fn print_review(solver: &Solver, history: &[(Word, Outcome)], goal: Option<Word>, strategy: &dyn Strategy, options: &Options) {
+    let turns = review(solver, history, strategy);
+    for (i, turn) in turns.iter().enumerate() {
+        let grade = grade(-(turn.worst as i32), -(turn.recommended_worst as i32));
+        let recommended = turn.recommended.map_or_else(String::new, |r| r.to_string());
+        if options.json {
+            print_json(&[("turn", (i + 1).to_string()), ("guess", json_str(&turn.guess.to_string())),
+                         ("outcome", json_str(&turn.outcome.letters())), ("before", turn.before.to_string()),
+                         ("after", turn.after.to_string()), ("recommended", json_str(&recommended)),
+                         ("grade", json_str(&grade.to_string())), ("worst_case", turn.worst.to_string()),
+                         ("expected", format!("{:.2}", turn.expected)), ("skill", format!("{:.2}", turn.skill)),
+                         ("luck", format!("{:.2}", turn.luck))]);
+            continue;
+        }
+        let after = if turn.outcome.is_win() { "solved".to_string() } else { format!("{} goals", turn.after) };
+        println!("{}. {} {}  {} goals -> {}", i + 1, turn.guess, turn.outcome, turn.before, after);
+        println!("   grade {} (at most {} left; {} leaves at most {})", grade, turn.worst, recommended, turn.recommended_worst);
+        println!("   skill {:.0}% ({:.1} left expected; {} leaves {:.1})", 100.0 * turn.skill, turn.expected, recommended,
+                 turn.recommended_expected);
+        println!("   luck {:.0}% ({} left, {:.1} expected)", 100.0 * turn.luck, turn.after, turn.expected);
+    }
+    let (skill, luck) = skill_and_luck(&turns);
+    if options.json {
+        print_json(&[("skill", format!("{:.2}", skill)), ("luck", format!("{:.2}", luck))]);
+    } else {
+        println!("skill {:.0}%, luck {:.0}%", 100.0 * skill, 100.0 * luck);
+    }
+    if let Some(goal) = answer(history, goal) { print_line_comparison(&compare_lines(solver, history, goal, strategy, PLAY_LIMIT), history, strategy.name(), options); }
+}
+
+// The goal of a game, if it's known or the game was won.
+fn answer(history: &[(Word, Outcome)], goal: Option<Word>) -> Option<Word> {
+    goal.or_else(|| history.last().filter(|(_, outcome)| outcome.is_win()).map(|&(guess, _)| guess))
+}
+
+// A game written up in Markdown to paste in a forum or a chat: its board as
+// a table with each turn's goals left, skill and luck, the whole game's,
+// and where the strategy would have played otherwise, if the goal's known.
+fn print_markdown_summary(solver: &Solver, history: &[(Word, Outcome)], goal: Option<Word>, strategy: &dyn Strategy, options: &Options) {
+    let turns = review(solver, history, strategy);
+    let solved = history.last().is_some_and(|(_, o)| o.is_win()) && history.len() <= options.guesses;
+    println!();
+    println!("**Wordle {}/{}**", if solved { history.len().to_string() } else { "X".to_string() }, options.guesses);
+    println!();
+    // Chats don't draw tables, and the spoiler markup's bars would split
+    // their cells, so with it each turn is a line of a list.
+    if options.spoiler_markup {
+        for (i, turn) in turns.iter().enumerate() {
+            println!("{}. {} {}: {} → {} goals, skill {:.0}%, luck {:.0}%", i + 1, turn.outcome, spoiler(turn.guess, options), turn.before,
+                     turn.after, 100.0 * turn.skill, 100.0 * turn.luck);
+        }
+    } else {
+        println!("| | guess | tiles | goals left | skill | luck |");
+        println!("|--:|---|---|--:|--:|--:|");
+        for (i, turn) in turns.iter().enumerate() {
+            println!("| {} | {} | {} | {} → {} | {:.0}% | {:.0}% |", i + 1, turn.guess, turn.outcome, turn.before, turn.after,
+                     100.0 * turn.skill, 100.0 * turn.luck);
+        }
+    }
+    let (skill, luck) = skill_and_luck(&turns);
+    println!();
+    println!("Skill {:.0}%, luck {:.0}%.", 100.0 * skill, 100.0 * luck);
+    let Some(goal) = answer(history, goal) else { return };
+    let compared = compare_lines(solver, history, goal, strategy, PLAY_LIMIT);
+    println!();
+    match compared.divergence {
+        Some(turn) => {
+            let line: Vec<String> = compared.line.iter().map(|&(guess, _)| spoiler(guess, options)).collect();
+            println!("The {} strategy would have played **{}** instead of {} on turn {}, expecting {:.2} {} guesses: {}.", strategy.name(),
+                     spoiler(compared.line[turn].0, options), spoiler(history[turn].0, options), turn + 1, compared.cost.abs(), if compared.cost < 0.0 { "more" } else { "fewer" },
+                     line.join(", "));
+        },
+        None => println!("Every guess was the {} strategy's.", strategy.name()),
+    }
+}
+
+// A game's guesses and the strategy's line side by side, marking the first
+// turn they differ with what it cost.
+fn print_line_comparison(compared: &LineComparison, history: &[(Word, Outcome)], strategy: &str, options: &Options) {
+    let line = |turns: &[(Word, Outcome)]| -> Vec<String> { turns.iter().map(|(guess, _)| json_str(&guess.to_string())).collect() };
+    if options.json {
+        let mut fields = vec![("strategy_line", format!("[{}]", line(&compared.line).join(",")))];
+        if let Some(turn) = compared.divergence {
+            fields.push(("divergence", (turn + 1).to_string()));
+            fields.push(("cost", format!("{:.2}", compared.cost)));
+        }
+        return print_json(&fields);
+    }
+    let cell = |turns: &[(Word, Outcome)], i: usize| turns.get(i).map_or_else(String::new, |(guess, outcome)| format!("{} {}", guess, outcome));
+    let width = history.iter().chain(&compared.line).map(|(guess, _)| 2 * guess.len() + 1).max().unwrap_or(0);
+    println!("    {:<w$}  {}", "played", strategy, w = width);
+    for i in 0..history.len().max(compared.line.len()) {
+        let row = format!("{:<3} {:<w$}  {:<w$}", format!("{}.", i + 1), cell(history, i), cell(&compared.line, i), w = width);
+        if compared.divergence == Some(i) {
+            println!("{}  <- first difference, {:.2} {} guesses expected", row, compared.cost.abs(),
+                     if compared.cost < 0.0 { "fewer" } else { "more" });
+        } else {
+            println!("{}", row.trim_end());
+        }
+    }
+    if compared.divergence.is_none() { println!("the same line as {}", strategy); }
+}
+
+// One-shot advice for a fixed pattern, falling back to the quick heuristic
+// when there are too many goals left for the chosen strategy.
+fn recommend_once(solver: &Solver, strategy: &dyn Strategy, options: &Options) {
+    let goals = solver.candidates();
+    println!("  {} matching goal words", goals.len());
+    if goals.len() <= 20 {
+        print_odds(&listed(goals, options));
+    }
+    let strategy = practical(strategy, goals.len());
+    if let (Some(recommended), metrics, _) = choose_measured(solver, strategy, options) {
+        println!("recommended guess is {} ({})", recommended, describe(solver, recommended, chooser(strategy, &metrics)));
+    }
+    if let Some(n) = options.top { print_top(solver, n, false); }
+}
+
+// The name of the strategy whose choice a search measured as `metrics`:
+// the quick heuristic's if a cache or book fell back to it.
+fn chooser(strategy: &dyn Strategy, metrics: &Metrics) -> &'static str {
+    if metrics.quick_fallbacks > 0 { Quick.name() } else { strategy.name() }
+}
+
+// The strategy, or the quick heuristic if there are too many goals left for
+// it, so there's always a recommendation.
+fn practical(strategy: &dyn Strategy, goals: usize) -> &dyn Strategy {
+    if goals > strategy.max_candidates() { &Quick } else { strategy }
+}
+
+// The goals in the order they're listed, likeliest first with --frequencies,
+// each with its chance of being the answer.
+fn listed(goals: &[Word], options: &Options) -> Vec<(Word, f64)> {
+    let mut goals = goals.to_vec();
+    match &options.priors {
+        Some(priors) => {
+            priors.sort_by_likelihood(&mut goals);
+            let odds = priors.probabilities(&goals);
+            goals.into_iter().zip(odds).collect()
+        },
+        None => goals.iter().map(|&g| (g, 1.0 / goals.len() as f64)).collect(),
+    }
+}
+
+// One goal per line with its chance of being the answer, then how much of
+// the chance the likeliest few cover.
+fn print_odds(goals: &[(Word, f64)]) {
+    for (g, p) in goals { println!("  {}  {:>5.1}%", g, 100.0 * p); }
+    if goals.len() > 3 { println!("  top 3 cover {:.1}%", 100.0 * goals[..3].iter().map(|g| g.1).sum::<f64>()); }
+}
+
+// The best guesses by worst case, with the expected goals each leaves.
+fn print_top(solver: &Solver, n: usize, compact: bool) {
+    let ranked = solver.ranked_guesses(n, usize::MAX);
+    if compact {
+        println!("{:>3} {:<8} {:>5} {:>8}", "#", "guess", "worst", "expected");
+    } else {
+        println!("{:>4}  {:<8}  {:>10}  {:>14}", "rank", "guess", "worst case", "expected left");
+    }
+    for (i, (guess, worst, expected)) in ranked.iter().enumerate() {
+        if compact {
+            println!("{:>3} {:<8} {:>5} {:>8.2}", i + 1, guess.to_string(), worst, expected);
+        } else {
+            println!("{:>4}  {:<8}  {:>10}  {:>14.2}", i + 1, guess.to_string(), worst, expected);
+        }
+    }
+}
+
+// Where scoring the guess is affordable, how many words it could leave.
+fn describe(solver: &Solver, guess: Word, strategy: &str) -> String {
+    if solver.candidates().len() < SCORING_LIMIT {
+        format!("at most {} possible words", -solver.confidence(guess))
+    } else {
+        format!("{} strategy", strategy)
+    }
+}
+
+const SCORING_LIMIT: usize = 1000;
+
+// Guesses benchmarks and the automatic adversary play before giving up: well
+// past --guesses, so slow solves still count toward the mean, though they're
+// reported as losses.
+const PLAY_LIMIT: usize = 20;
+
+// Strategies selectable with --strategy.
+const STRATEGIES: [&str; 5] = ["minimax", "entropy", "expected", "quick", "sampled"];
+
+fn strategy(name: &str, options: &Options, progress_every: usize) -> Result<Box<dyn Strategy>, Error> {
+    match name {
+        "minimax" => {
+            if !(1..=2).contains(&options.depth) { return Err(usage_error("--depth must be 1 or 2")) }
+            Ok(Box::new(Minimax { progress_every, depth: options.depth, budget: options.budget, prune: options.prune,
+                                  table: (!options.low_memory).then(|| options.table.clone()) }))
+        },
+        "entropy" => Ok(Box::new(Entropy { priors: options.priors.clone() })),
+        "expected" => Ok(Box::new(Expected { priors: options.priors.clone() })),
+        "quick" => Ok(Box::new(Quick)),
+        "sampled" => Ok(Box::new(Sampled::new(options.seed.unwrap_or_else(rand::random), options.priors.clone()))),
+        _ => Err(usage_error(&format!("unknown strategy {:?}, expected one of {}", name, STRATEGIES.join(", ")))),
+    }
+}
+
+fn optimal(goals: &[Word], dict: &[Word], options: &Options) -> Result<(), Error> {
+    let objective = options.objective.unwrap_or(Objective::Total);
+    let mut search = OptimalSearch::new(dict, objective);
+    // A checkpoint is saved from the table, so it's kept even with --low-memory.
+    if !options.low_memory || options.checkpoint.is_some() { search = search.with_table(options.table.clone()); }
+    if let Some(breadth) = options.breadth { search = search.with_breadth(breadth); }
+    if let Some(threads) = options.threads { search = search.with_threads(threads); }
+    if let Some(path) = &options.checkpoint {
+        match options.table.load(path) {
+            Ok(n) => if !options.quiet { eprintln!("resuming from {} positions in {}", n, path) },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {},
+            Err(e) => return Err(file_error(path, e)),
+        }
+        search = search.with_checkpoint(path, Duration::from_secs(60));
+    }
+    if !options.quiet && io::stderr().is_terminal() { search = search.with_progress(); }
+    let start = Instant::now();
+    let (guess, cost) = search.solve(goals);
+    match objective {
+        Objective::Total => println!("{} solves all {} goals in {} guesses ({:.4} on average)",
+                                     guess, goals.len(), cost, cost as f64 / goals.len() as f64),
+        Objective::WorstCase => println!("{} solves all {} goals within {} guesses", guess, goals.len(), cost),
+    }
+    println!("  searched {} positions in {:.1?}{}", search.nodes(), start.elapsed(),
+             if options.breadth.is_some() { ", not exhaustively" } else { "" });
+    if options.verbose { print_table(options); }
+    Ok(())
+}
+
+// Replays `games` random games from `solver`'s position, each a random goal
+// and up to six random guesses, half of them goals so letters repeat and
+// tiles aren't all gray, checking every filter after each guess.
+fn verify(solver: &Solver, games: usize, options: &Options) -> Result<(), Error> {
+    let mut rng = options.seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+    let (goals, dict) = (solver.candidates(), solver.dict());
+    let mut diverged = 0;
+    for _ in 0..games {
+        let Some(&goal) = goals.choose(&mut rng) else { break };
+        let guesses: Vec<Word> = (0..rng.gen_range(1..=6)).map(|_| *if rng.gen() { goals } else { dict }.choose(&mut rng).unwrap()).collect();
+        if let Err(divergence) = verify_game(solver, goal, &guesses) {
+            if diverged < 10 { println!("{}: {}", goal, divergence); }
+            diverged += 1;
+        }
+    }
+    if diverged > 0 { return Err(Error::Input(format!("{} of {} games diverged", diverged, games))) }
+    println!("{} games, no divergences", games);
+    Ok(())
+}
+
+// Adds `strategy`'s choices for the first two turns from `solver`'s position
+// to the book at `path`: the first guess, unless `openers` are given, and the
+// reply to each outcome of each opener. Even positions with too many goals
+// for the strategy to search while playing are searched, so the book has
+// them. Returns how many positions it chose for, some of which may already
+// have been in the book.
+fn write_book(solver: &Solver, strategy: Box<dyn Strategy>, settings: &str, path: &str, openers: &[Word], options: &Options) -> usize {
+    let mut book = Cached::new(strategy, settings, path);
+    book.min_candidates = 0;
+    book.max_candidates = usize::MAX;
+    let rules = |solver: &mut Solver| {
+        if options.hard { solver.retain_hard_mode_guesses(); }
+        if options.ultra_hard { solver.retain_matching_guesses(); }
+    };
+    let mut added = 0;
+    let mut choose = |solver: &Solver| {
+        added += 1;
+        solver.choose(&book).expect("there are goals")
+    };
+    let mut solver = solver.clone();
+    rules(&mut solver);
+    let openers = if openers.is_empty() {
+        vec![choose(&solver)]
+    } else {
+        openers.to_vec()
+    };
+    for opener in openers {
+        let mut outcomes: Vec<Outcome> = solver.candidates().iter().map(|&goal| Outcome::compare(goal, opener)).collect();
+        outcomes.sort_by_key(|outcome| outcome.code());
+        outcomes.dedup();
+        for outcome in outcomes.into_iter().filter(|outcome| !outcome.is_win()) {
+            let mut next = solver.clone();
+            next.refine(opener, outcome);
+            rules(&mut next);
+            if next.candidates().len() >= 2 { choose(&next); }
+        }
+    }
+    added
+}
+
+// The best fixed openings over every goal, from the cache beside --cache's
+// if it has them. --top says how many to list.
+fn openers(goals: &[Word], dict: &[Word], options: &Options) {
+    let (n, breadth) = (options.top.unwrap_or(10), options.breadth.unwrap_or(10));
+    let report = || {
+        let matrix = OutcomeMatrix::new(goals, dict);
+        let line = |words: &[Word], split: &OpeningSplit| {
+            let words: Vec<String> = words.iter().map(Word::to_string).collect();
+            format!("  {}  {:.2} left on average, at most {}, {} outcomes", words.join(" "), split.expected, split.worst, split.outcomes)
+        };
+        let mut lines = vec![format!("best first guesses over {} goals:", goals.len())];
+        lines.extend(best_openers(&matrix, n).iter().map(|(guess, split)| line(&[*guess], split)));
+        if options.pairs {
+            lines.push(format!("best pairs, trying the {} best first guesses:", breadth));
+            lines.extend(best_pairs(&matrix, breadth, n).iter().map(|(pair, split)| line(pair, split)));
+        }
+        lines
+    };
+    let lines = match &options.cache {
+        Some(cache) => {
+            let path = std::path::Path::new(cache).with_file_name("report");
+            let settings = format!("openers alphabet {:?} top {} pairs {} breadth {}", alphabet().symbols(), n, options.pairs, breadth);
+            cached_report(&path.to_string_lossy(), &settings, goals, dict, report)
+        },
+        None => report(),
+    };
+    for line in lines { println!("{}", line); }
+}
+
+// Writes the strategy's decision tree over every goal as JSON, to --out with
+// Graphviz DOT beside it, or to stdout.
+fn tree(solver: &Solver, strategy: &dyn Strategy, options: &Options) -> Result<(), Error> {
+    let tree = DecisionTree::build(solver, strategy, PLAY_LIMIT).expect("there is always a goal");
+    let Some(out) = &options.out else {
+        println!("{}", tree.to_json());
+        return Ok(());
+    };
+    let dot = std::path::Path::new(out).with_extension("dot");
+    std::fs::write(out, tree.to_json() + "\n").map_err(|e| file_error(out, e))?;
+    std::fs::write(&dot, tree.to_dot()).map_err(|e| file_error(&dot.to_string_lossy(), e))?;
+    println!("wrote {} and {}", out, dot.display());
+    println!("{} goals in {} guesses ({:.4} on average), at most {}", tree.goals(), tree.total_guesses(),
+             tree.total_guesses() as f64 / tree.goals() as f64, tree.depth());
+    let unsolved = solver.candidates().len() - tree.goals();
+    if unsolved > 0 { println!("{} goals unsolved within {} guesses", unsolved, PLAY_LIMIT); }
+    Ok(())
+}
+
+// Guess counts as a bar chart, then the summary numbers.
+fn print_bench(report: &BenchReport, name: &str, limit: usize) {
+    println!("{} strategy over {} goals", name, report.results.len());
+    let histogram = report.histogram();
+    let most = histogram.iter().copied().max().unwrap_or(0).max(1);
+    for (n, &count) in histogram.iter().enumerate().skip(1) {
+        let line = format!("{:>3} {:>5} {}", n, count, "#".repeat((count * 40).div_ceil(most)));
+        println!("{}", line.trim_end());
+    }
+    println!("mean {:.4}, worst {}", report.mean(), report.worst().map_or("-".to_string(), |n| n.to_string()));
+    let failures = report.failures(limit);
+    println!("{} not solved within {} guesses, {:.2}% failed", failures.len(), limit, 100.0 * report.failure_rate(limit));
+    for w in failures { println!("  {}", w); }
+}
+
+// simulate's pooled guess counts, with how sure the mean is.
+fn print_simulation(report: &SimulationReport, name: &str, limit: usize) {
+    let pooled = report.pooled();
+    println!("{} strategy over {} goals, {} trials", name, pooled.results.len() / report.trials.len().max(1), report.trials.len());
+    let histogram = pooled.histogram();
+    let most = histogram.iter().copied().max().unwrap_or(0).max(1);
+    for (n, &count) in histogram.iter().enumerate().skip(1) {
+        let line = format!("{:>3} {:>5} {}", n, count, "#".repeat((count * 40).div_ceil(most)));
+        println!("{}", line.trim_end());
+    }
+    let (low, high) = report.confidence_interval();
+    println!("mean {:.4} (95% confidence {:.4} to {:.4}), worst {}", pooled.mean(), low, high,
+             pooled.worst().map_or("-".to_string(), |n| n.to_string()));
+    println!("{} of {} games not solved within {} guesses, {:.2}% failed", pooled.failures(limit).len(), pooled.results.len(), limit,
+             100.0 * pooled.failure_rate(limit));
+}
+
+// Each game lost in `report`, played again from `solver`: every guess with
+// its outcome and the goals it left, then the first turn that left too many
+// goals to be sure of finding in the guesses to go, from which the game
+// could be lost. Only turns with GUARANTEE_DEPTH or fewer to go are checked,
+// as the search is exponential in them.
+fn print_failures(solver: &Solver, report: &BenchReport, limit: usize) {
+    for ((goal, solved), (words, _)) in report.results.iter().zip(&report.games).filter(|((_, solved), _)| solved.is_none_or(|n| n > limit)) {
+        match solved {
+            Some(n) => println!("{}, solved in {} guesses", goal, n),
+            None => println!("{}, not solved within {} guesses", goal, words.len()),
+        }
+        let mut game = solver.clone();
+        let mut too_many = None;
+        for (turn, &guess) in words.iter().enumerate().map(|(i, w)| (i + 1, w)) {
+            let outcome = Outcome::compare(*goal, guess);
+            game.refine(guess, outcome);
+            let left = game.candidates().len();
+            println!("  {} {} {} {} left", turn, guess, outcome, left);
+            let to_go = limit.saturating_sub(turn);
+            let lost = left > to_go && to_go <= GUARANTEE_DEPTH && !solvable_within(game.candidates(), game.dict(), to_go);
+            if too_many.is_none() && !outcome.is_win() && lost { too_many = Some((turn, left)); }
+        }
+        if let Some((turn, left)) = too_many {
+            println!("  too many goals after turn {}: {} left to find in {} guesses", turn, left, limit.saturating_sub(turn));
+        }
+    }
+}
+
+// The goals that took the most guesses autoplaying every one, --top of them,
+// then the game against an adversary that keeps as many goals possible as
+// it can, as Absurdle does, and the word it's left holding.
+fn print_hardest(solver: &Solver, strategy: &dyn Strategy, options: &Options) {
+    let report = bench(solver, strategy, PLAY_LIMIT);
+    println!("hardest goals for the {} strategy:", strategy.name());
+    for (goal, guesses) in report.hardest(options.top.unwrap_or(10)) {
+        match guesses {
+            Some(n) => println!("  {} {} guesses", goal, n),
+            None => println!("  {} not solved within {} guesses", goal, PLAY_LIMIT),
+        }
+    }
+    let mut adversary = solver.clone();
+    match adversary.play_against(|goals, guess| adversarial_outcome(guess, goals), strategy, PLAY_LIMIT) {
+        Some(n) => {
+            let guesses: Vec<String> = adversary.history().iter().map(|(guess, _)| guess.to_string()).collect();
+            println!("against Absurdle, {} holds out for {} guesses: {}", adversary.history().last().unwrap().0, n, guesses.join(" "));
+        },
+        None => println!("against Absurdle, not solved within {} guesses", PLAY_LIMIT),
+    }
+}
+
+// Writes --report's file in the format its extension asks for, `title`
+// heading the web page.
+fn write_report(path: &str, title: &str, reports: &[(&str, BenchReport)], limit: usize) -> Result<(), Error> {
+    let text = if path.ends_with(".json") {
+        json_report(reports, limit)
+    } else if path.ends_with(".html") {
+        html_report(title, reports, limit)
+    } else {
+        csv_report(reports, limit)
+    };
+    std::fs::write(path, text).map_err(|e| file_error(path, e))
+}
+
+// A bench report with a row per strategy and goal: the guesses it took,
+// empty if unsolved, the words guessed and the seconds the game took. Then,
+// as # comments pandas and the like can skip, a summary per strategy.
+fn csv_report(reports: &[(&str, BenchReport)], limit: usize) -> String {
+    let mut csv = String::from("strategy,goal,guesses,words,seconds\n");
+    for (name, report) in reports {
+        for ((goal, solved), (words, time)) in report.results.iter().zip(&report.games) {
+            let words: Vec<String> = words.iter().map(Word::to_string).collect();
+            csv += &format!("{},{},{},{},{:.6}\n", name, goal, solved.map_or_else(String::new, |n| n.to_string()),
+                            words.join(" "), time.as_secs_f64());
+        }
+    }
+    for (name, report) in reports {
+        let seconds: f64 = report.games.iter().map(|g| g.1.as_secs_f64()).sum();
+        csv += &format!("# {}: mean {:.4}, worst {}, {} not solved within {}, failure rate {:.4}, {:.3} seconds\n", name, report.mean(),
+                        report.worst().unwrap_or(0), report.failures(limit).len(), limit, report.failure_rate(limit), seconds);
+    }
+    csv
+}
+
+// The same as csv_report, as a JSON object per strategy keyed on its name,
+// with its games and summary.
+fn json_report(reports: &[(&str, BenchReport)], limit: usize) -> String {
+    let strategies: Vec<String> = reports.iter().map(|(name, report)| {
+        let games: Vec<String> = report.results.iter().zip(&report.games).map(|((goal, solved), (words, time))| {
+            let words: Vec<String> = words.iter().map(|w| json_str(&w.to_string())).collect();
+            format!("{{\"goal\":{},\"guesses\":{},\"words\":[{}],\"seconds\":{:.6}}}", json_str(&goal.to_string()),
+                    solved.map_or_else(|| "null".to_string(), |n| n.to_string()), words.join(","), time.as_secs_f64())
+        }).collect();
+        let histogram: Vec<String> = report.histogram().iter().map(usize::to_string).collect();
+        let seconds: f64 = report.games.iter().map(|g| g.1.as_secs_f64()).sum();
+        format!("{}:{{\"games\":[{}],\"summary\":{{\"mean\":{:.4},\"worst\":{},\"failures\":{},\"failure_rate\":{:.4},\"histogram\":[{}],\"seconds\":{:.3}}}}}",
+                json_str(name), games.join(","), report.mean(), report.worst().unwrap_or(0), report.failures(limit).len(),
+                report.failure_rate(limit), histogram.join(","), seconds)
+    }).collect();
+    format!("{{{}}}\n", strategies.join(","))
+}
+
+// The same as csv_report, as a page needing nothing beside it: a summary
+// table, each strategy's guess counts as a bar chart and the goals it failed,
+// with what it guessed.
+fn html_report(title: &str, reports: &[(&str, BenchReport)], limit: usize) -> String {
+    let mut html = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n", html_escape(title));
+    html += "<style>\nbody { font-family: sans-serif; margin: 2em; }\ntable { border-collapse: collapse; }\n\
+             th, td { padding: 0.2em 0.6em; text-align: right; }\nth:first-child, td:first-child { text-align: left; }\n\
+             .bar { background: #6aaa64; color: white; padding: 0 0.3em; white-space: nowrap; }\n\
+             .lost { background: #787c7e; }\n</style>\n</head>\n<body>\n";
+    html += &format!("<h1>{}</h1>\n<table>\n<tr><th>strategy</th><th>mean</th><th>worst</th>\
+                      <th>not solved within {}</th><th>failed</th><th>seconds</th></tr>\n", html_escape(title), limit);
+    for (name, report) in reports {
+        let seconds: f64 = report.games.iter().map(|g| g.1.as_secs_f64()).sum();
+        html += &format!("<tr><td>{}</td><td>{:.4}</td><td>{}</td><td>{}</td><td>{:.2}%</td><td>{:.3}</td></tr>\n", html_escape(name),
+                         report.mean(), report.worst().map_or("-".to_string(), |n| n.to_string()), report.failures(limit).len(),
+                         100.0 * report.failure_rate(limit), seconds);
+    }
+    html += "</table>\n";
+    for (name, report) in reports {
+        html += &format!("<h2>{}</h2>\n<table>\n", html_escape(name));
+        let histogram = report.histogram();
+        let most = histogram.iter().copied().max().unwrap_or(0).max(1);
+        for (n, &count) in histogram.iter().enumerate().skip(1) {
+            html += &format!("<tr><td>{}</td><td style=\"text-align: left; width: 30em\"><div class=\"bar{}\" style=\"width: {:.1}%\">{}</div></td></tr>\n",
+                             n, if n > limit { " lost" } else { "" }, (count * 100) as f64 / most as f64, count);
+        }
+        html += "</table>\n";
+        let failed: Vec<String> = report.results.iter().zip(&report.games).filter(|((_, solved), _)| solved.is_none_or(|n| n > limit))
+            .map(|((goal, _), (words, _))| {
+                let words: Vec<String> = words.iter().map(|w| html_escape(&w.to_string())).collect();
+                format!("<li><b>{}</b>: {}</li>\n", html_escape(&goal.to_string()), words.join(" "))
+            }).collect();
+        html += &format!("<p>{} not solved within {} guesses</p>\n", failed.len(), limit);
+        if !failed.is_empty() { html += &format!("<ul>\n{}</ul>\n", failed.concat()); }
+    }
+    html + "</body>\n</html>\n"
+}
+
+// `text` with the characters HTML gives meaning to escaped.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Side-by-side summaries, then how each pair fared goal by goal, then every
+// goal the strategies disagree on. Unsolved goals count as worse than any
+// solved one.
+fn print_comparison(reports: &[(&str, BenchReport)], limit: usize) {
+    let column = |f: &dyn Fn(&BenchReport) -> String| -> String {
+        reports.iter().map(|(_, r)| format!("{:>10}", f(r))).collect()
+    };
+    println!("{:<8}{}", "", reports.iter().map(|(name, _)| format!("{:>10}", name)).collect::<String>());
+    println!("{:<8}{}", "mean", column(&|r| format!("{:.4}", r.mean())));
+    println!("{:<8}{}", "worst", column(&|r| r.worst().map_or("-".to_string(), |n| n.to_string())));
+    let longest = reports.iter().map(|(_, r)| r.histogram().len()).max().unwrap_or(0);
+    for n in 1..longest {
+        println!("{:<8}{}", n, column(&|r| r.histogram().get(n).copied().unwrap_or(0).to_string()));
+    }
+    println!("{:<8}{}", "failed", column(&|r| r.failures(limit).len().to_string()));
+    println!("{:<8}{}", "failed %", column(&|r| format!("{:.2}", 100.0 * r.failure_rate(limit))));
+
+    let guesses = |r: &BenchReport, i: usize| r.results[i].1.unwrap_or(usize::MAX);
+    let goals = reports[0].1.results.len();
+    for (a, (name_a, report_a)) in reports.iter().enumerate() {
+        for (name_b, report_b) in &reports[a + 1..] {
+            let (mut wins, mut losses) = (0, 0);
+            for i in 0..goals {
+                match guesses(report_a, i).cmp(&guesses(report_b, i)) {
+                    std::cmp::Ordering::Less => wins += 1,
+                    std::cmp::Ordering::Greater => losses += 1,
+                    std::cmp::Ordering::Equal => {},
+                }
+            }
+            println!("{} vs {}: {} better, {} worse, {} tied", name_a, name_b, wins, losses, goals - wins - losses);
+        }
+    }
+
+    let differing: Vec<usize> = (0..goals).filter(|&i| reports.iter().any(|(_, r)| guesses(r, i) != guesses(&reports[0].1, i))).collect();
+    println!("{} goals differ", differing.len());
+    for i in differing {
+        let counts: String = reports.iter().map(|(_, r)| format!("{:>10}", r.results[i].1.map_or("-".to_string(), |n| n.to_string()))).collect();
+        println!("{:<8}{}", reports[0].1.results[i].0, counts);
+    }
+}
+
+// Chat-plays-Wordle: each stdin line is a vote, either "guess" or "voter guess"
+// (a named voter's later vote replaces their earlier one). A blank line closes
+// the round and the most popular guess is played, ties going to the earliest.
+// Votes for words the other commands wouldn't take are left out.
+fn crowd(goal: Word, mut solver: Solver, options: &Options) -> io::Result<()> {
+    let mut votes: Vec<(Option<String>, Word)> = Vec::new();
+    let mut round = 1;
+    for line_result in io::stdin().lock().lines() {
+        let line = line_result?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (voter, text) = match fields[..] {
+            [] => {
+                if votes.is_empty() { continue }
+                let mut tally: Vec<(Word, u32)> = Vec::new();
+                for &(_, w) in &votes {
+                    match tally.iter_mut().find(|(t, _)| *t == w) {
+                        Some((_, n)) => *n += 1,
+                        None => tally.push((w, 1)),
+                    }
+                }
+                let winner = tally.iter().fold(tally[0], |best, &t| if t.1 > best.1 { t } else { best }).0;
+                println!("round {}: {} votes", round, votes.len());
+                for (w, n) in &tally { println!("  {} {}", w, n); }
+                let outcome = Outcome::compare(goal, winner);
+                println!("playing {} {}", winner, outcome);
+                if outcome.is_win() {
+                    println!("solved in {} rounds", round);
+                    return Ok(());
+                }
+                solver.refine(winner, outcome);
+                println!("  {} matching goal words", solver.candidates().len());
+                votes.clear();
+                round += 1;
+                continue;
+            },
+            [text] => (None, text),
+            [voter, text] => (Some(voter.to_string()), text),
+            _ => { eprintln!("ignoring malformed vote {:?}", line); continue },
+        };
+        let guess = match parse_guess(text, goal.len()) {
+            Some(guess) => guess,
+            None => { eprintln!("ignoring invalid guess {:?}", text); continue },
+        };
+        if let Some(message) = unknown_word(guess, options).or_else(|| broken_rule(guess, &solver, options)) {
+            eprintln!("ignoring vote: {}", message);
+            continue;
+        }
+        if voter.is_some() { votes.retain(|(v, _)| *v != voter); }
+        votes.push((voter, guess));
+    }
+    Ok(())
+}
+
+// A new game, picking up one in progress from a pasted board if given.
+fn start(goals: Vec<Word>, dict: Vec<Word>, options: &Options) -> Result<Solver, Error> {
+    let mut solver = indexed(Solver::new(goals, dict), options);
+    if options.verify { solver = solver.verifying(); }
+    if options.guarantee { solver = solver.guaranteeing(options.guesses); }
+    if let Some(filename) = &options.board {
+        for (guess, outcome) in parse_board(&read_file(filename)?, options.length) { solver.refine(guess, outcome); }
+    }
+    Ok(solver)
+}
+
+// `solver` with its outcomes precomputed, unless --low-memory says not to.
+fn indexed(solver: Solver, options: &Options) -> Solver {
+    if options.low_memory { solver } else { solver.with_matrix() }
+}
+
+// Writes the guesses so far as a board, one guess and its tiles per line,
+// which --resume, --board and analyze all read back.
+fn save(solver: &Solver, filename: &str) -> io::Result<()> {
+    let board: String = solver.history().iter().map(|(guess, outcome)| format!("{} {}\n", guess, outcome)).collect();
+    std::fs::write(filename, board)
+}
+
+// Shows what's known and the recommended guess, returning its confidence
+// where scoring it was affordable.
+fn print_turn(solver: &Solver, options: &Options, strategy: &dyn Strategy) -> Option<i32> {
+    if options.json { return print_json_turn(solver, strategy, options) }
+    if options.tui { return print_screen(solver, options, strategy).0 }
+    let goals = solver.candidates();
+    if options.quiet {
+        // Just the recommendation, below.
+    } else if options.compact {
+        print_board(solver.history(), options.colors());
+        print_keyboard(solver.pattern(), options.colors());
+        println!("{}", options.messages.text(Message::GoalsLeft, &[&goals.len()]));
+        if goals.len() <= 20 {
+            for row in listed(goals, options).chunks(4) {
+                println!("{}", row.iter().map(|(g, p)| format!("{} {:>2.0}%", g, 100.0 * p)).collect::<Vec<_>>().join(" "));
+            }
+        }
+    } else {
+        print_board(solver.history(), options.colors());
+        println!("{}", options.messages.text(Message::PatternIs, &[solver.pattern()]));
+        println!("{}", options.messages.text(Message::MatchingGoals, &[&goals.len()]));
+        if goals.len() <= 20 {
+            print_odds(&listed(goals, options));
+        }
+    }
+    if let Some(n) = options.top.filter(|_| !goals.is_empty()) { print_top(solver, n, options.compact); }
+    if let [answer] = goals {
+        println!("{}", options.messages.text(Message::AnswerMustBe, &[answer]));
+        return Some(-1);
+    }
+    let strategy = practical(strategy, goals.len());
+    let (recommended, metrics, elapsed) = choose_measured(solver, strategy, options);
+    let recommended = recommended?;
+    if options.verbose {
+        println!("{} searched {} guesses over {} goals in {:.2?}", chooser(strategy, &metrics), solver.dict().len(), goals.len(), elapsed);
+        print_metrics(&metrics);
+        print_table(options);
+    }
+    if goals.len() >= SCORING_LIMIT {
+        let message = if options.compact { Message::Try } else { Message::Recommended };
+        println!("{}", options.messages.text(message, &[&recommended, &chooser(strategy, &metrics)]));
+        return None;
+    }
+    let confidence = solver.confidence(recommended);
+    let message = if options.compact { Message::TryAtMost } else { Message::RecommendedAtMost };
+    println!("{}", options.messages.text(message, &[&recommended, &-confidence]));
+    Some(confidence)
+}
+
+// Columns a line takes up on the terminal, skipping ANSI escapes and counting
+// tile emoji as two.
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut escape = false;
+    for c in line.chars() {
+        match c {
+            '\x1b' => escape = true,
+            'm' if escape => escape = false,
+            _ if escape => {},
+            c if LetterOutcome::from_emoji(c).is_some() => width += 2,
+            c if is_direction_mark(c) => {},
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+// Where print_screen drew each word and tile that can be clicked: its row
+// and columns, counting from 1.
+struct Screen {
+    targets: Vec<(usize, Range<usize>, Target)>,
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Word {
-    bytes: [u8; 5],
-    letters: u32,
+enum Target {
+    Guess(Word),
+    // A tile of a guess on the board, by their indexes.
+    Tile(usize, usize),
 }
 
-impl Word {
-    fn new(text: &str) -> Self {
-        let mut bytes = [0; 5];
-        bytes.copy_from_slice(text.as_bytes());
-        let mut letters: u32 = 0;
-        for b in bytes { letters |= to_letter_mask(b) }
-        Self { bytes, letters }
+impl Screen {
+    fn target_at(&self, row: usize, column: usize) -> Option<Target> {
+        self.targets.iter().find(|t| t.0 == row && t.1.contains(&column)).map(|t| t.2)
     }
 }
 
-impl fmt::Display for Word {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        let s = unsafe { str::from_utf8_unchecked(&self.bytes) };
-        formatter.write_str(s)
+// The columns, counting from 1, of each tile of `guess` as board_lines or
+// picker_line draw it, the tiles `cell` wide when drawn without color, and
+// right to left if `in_order` draws them in reading order.
+fn tile_columns(guess: Word, color: bool, cell: usize, in_order: bool) -> Vec<Range<usize>> {
+    let reversed = in_order && alphabet().is_rtl();
+    let (start, width) = match (color, reversed) {
+        (true, _) => (1, 3),
+        (false, true) => (1, cell),
+        (false, false) => (visible_width(&guess.to_string()) + 2, cell),
+    };
+    let mut columns: Vec<Range<usize>> = (0..guess.len()).map(|i| start + i * width..start + (i + 1) * width).collect();
+    if reversed { columns.reverse(); }
+    columns
+}
+
+// print_turn for --tui: clears the screen and draws the board and keyboard
+// on the left, and the goals left and best guesses on the right, under
+// headings in the theme's color.
+fn print_screen(solver: &Solver, options: &Options, strategy: &dyn Strategy) -> (Option<i32>, Screen) {
+    let goals = solver.candidates();
+    let mut targets = Vec::new();
+    for (i, (guess, _)) in solver.history().iter().enumerate() {
+        let columns = tile_columns(*guess, options.color, 2, options.color);
+        targets.extend(columns.into_iter().enumerate().map(|(tile, columns)| (i + 2, columns, Target::Tile(i, tile))));
+    }
+    let heading = |text: String| options.colors().map_or_else(|| text.clone(), |theme| theme.heading(&text));
+    let mut left = vec![heading("board".to_string())];
+    left.extend(board_lines(solver.history(), options.colors()));
+    left.extend((solver.history().len()..options.guesses).map(|_| if options.color { " · ".repeat(options.length) } else { "·".repeat(options.length) }));
+    left.push(String::new());
+    left.extend(keyboard_lines(solver.pattern(), options.colors()));
+
+    // Words on the right, by line and their columns from the pane's edge.
+    let mut words: Vec<(usize, Range<usize>, Word)> = Vec::new();
+    let mut right = vec![heading(format!("{} goals left", goals.len()))];
+    if goals.len() <= 20 {
+        for row in listed(goals, options).chunks(5) {
+            let mut line = String::new();
+            for &(goal, _) in row {
+                if !line.is_empty() { line.push(' '); }
+                let start = visible_width(&line);
+                line.push_str(&goal.to_string());
+                words.push((right.len(), start..visible_width(&line), goal));
+            }
+            right.push(line);
+        }
+    }
+    let mut confidence = None;
+    if let [answer] = goals {
+        right.push(format!("the answer must be {}", answer));
+        confidence = Some(-1);
+    } else if !goals.is_empty() {
+        right.push(String::new());
+        right.push(heading(format!("{:<8} {:>5} {:>8}", "best", "worst", "expected")));
+        for (guess, worst, expected) in solver.ranked_guesses(options.top.unwrap_or(5), usize::MAX) {
+            words.push((right.len(), 0..visible_width(&guess.to_string()), guess));
+            right.push(format!("{:<8} {:>5} {:>8.2}", guess.to_string(), worst, expected));
+        }
+        let strategy = practical(strategy, goals.len());
+        if let (Some(recommended), metrics, _) = choose_measured(solver, strategy, options) {
+            right.push(String::new());
+            let line = format!("{} recommends ", chooser(strategy, &metrics));
+            let start = visible_width(&line);
+            words.push((right.len(), start..start + visible_width(&recommended.to_string()), recommended));
+            right.push(format!("{}{}", line, recommended));
+            if goals.len() < SCORING_LIMIT { confidence = Some(solver.confidence(recommended)); }
+        }
+    }
+
+    let width = left.iter().map(|l| visible_width(l)).max().unwrap_or(0) + 4;
+    targets.extend(words.into_iter().map(|(i, columns, word)| (i + 1, columns.start + width + 1..columns.end + width + 1, Target::Guess(word))));
+    print!("\x1b[2J\x1b[H");
+    for i in 0..left.len().max(right.len()) {
+        let l = left.get(i).map_or("", String::as_str);
+        let line = format!("{}{}{}", l, " ".repeat(width - visible_width(l)), right.get(i).map_or("", String::as_str));
+        println!("{}", line.trim_end());
+    }
+    println!();
+    (confidence, Screen { targets })
+}
+
+// What a key bound with --key-accept and the like does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Accept,
+    Undo,
+    Hint,
+    Cycle,
+}
+
+// The action bound to `key`, if any.
+fn bound(key: Key, options: &Options) -> Option<Action> {
+    options.keys.iter().find(|b| b.1 == key).map(|b| b.0)
+}
+
+// The action a line read the usual way starts with the key of, and the rest
+// of the line, which only the accept key can be followed by. A character
+// bound has to be alone or followed by a space.
+fn bound_line<'a>(line: &'a str, options: &Options) -> Option<(Action, &'a str)> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let &(action, key) = options.keys.iter().find(|b| key_char(b.1).is_some_and(|c| line.starts_with(c)))?;
+    let rest = &line[key_char(key).unwrap().len_utf8()..];
+    if matches!(key, Key::Char(_)) && !rest.is_empty() && !rest.starts_with(char::is_whitespace) { return None }
+    let rest = rest.trim();
+    (rest.is_empty() || action == Action::Accept).then_some((action, rest))
+}
+
+// Input from the --tui screen.
+enum Input {
+    Line(String),
+    Clicked(Target),
+    Bound(Action),
+}
+
+// A line typed on the --tui screen, starting with `typed` and echoed as it's
+// typed, what was clicked on it instead, or what a bound key asked for. The
+// accept key takes a line already typed. None at the end of input.
+fn read_screen_line(screen: &Screen, typed: &str, options: &Options) -> io::Result<Option<Input>> {
+    let _raw = RawMode::with_mouse()?;
+    let mut stdin = io::stdin().lock();
+    let mut line = typed.to_string();
+    print!("{}", line);
+    io::stdout().flush()?;
+    loop {
+        let key = match read_key(&mut stdin)? {
+            None => return Ok(None),
+            Some(key) => key,
+        };
+        match (key, bound(key, options)) {
+            (Key::Char(_), Some(_)) if !line.is_empty() => {},
+            (_, Some(Action::Accept)) if !line.is_empty() => { println!(); return Ok(Some(Input::Line(line))) },
+            // The next suggestion replaces the line.
+            (_, Some(Action::Cycle)) => { print!("\r\x1b[K"); return Ok(Some(Input::Bound(Action::Cycle))) },
+            (_, Some(action)) => { println!(); return Ok(Some(Input::Bound(action))) },
+            _ => {},
+        }
+        match key {
+            Key::Enter => { println!(); return Ok(Some(Input::Line(line))) },
+            Key::Ctrl('c') => { println!(); return Ok(None) },
+            Key::Ctrl('d') if line.is_empty() => { println!(); return Ok(None) },
+            Key::Char(c) => { print!("{}", c); line.push(c); },
+            Key::Backspace if line.pop().is_some() => print!("\x08 \x08"),
+            Key::Click { row, column } => if let Some(target) = screen.target_at(row, column) {
+                println!();
+                return Ok(Some(Input::Clicked(target)));
+            },
+            _ => {},
+        }
+        io::stdout().flush()?;
+    }
+}
+
+// The guesses a cycle key steps through: the strategy's, then the best
+// ranked ones.
+fn suggestions(solver: &Solver, options: &Options, strategy: &dyn Strategy) -> Vec<Word> {
+    let goals = solver.candidates();
+    if goals.is_empty() { return Vec::new() }
+    let mut words: Vec<Word> = choose_measured(solver, practical(strategy, goals.len()), options).0.into_iter().collect();
+    for (guess, ..) in solver.ranked_guesses(options.top.unwrap_or(5), usize::MAX) {
+        if !words.contains(&guess) { words.push(guess); }
+    }
+    words
+}
+
+// A JSON string literal.
+fn json_str(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Prints one line of --json output: an object with these already encoded
+// values.
+fn print_json(fields: &[(&str, String)]) {
+    let fields: Vec<String> = fields.iter().map(|(key, value)| format!("{}:{}", json_str(key), value)).collect();
+    println!("{{{}}}", fields.join(","));
+}
+
+// Either a --json error object or the message as text.
+fn print_error(options: &Options, message: &str) {
+    if options.json { print_json(&[("error", json_str(message))]) } else { println!("{}", message) }
+}
+
+// print_turn for --json: the state of the game and the recommendation, if
+// there's one, with the most goals it could leave where that's affordable.
+fn print_json_turn(solver: &Solver, strategy: &dyn Strategy, options: &Options) -> Option<i32> {
+    let goals = solver.candidates();
+    let mut fields = vec![
+        ("turn", (solver.history().len() + 1).to_string()),
+        ("pattern", json_str(&solver.pattern().to_string())),
+        ("remaining", goals.len().to_string()),
+    ];
+    if goals.len() <= 20 {
+        let listed = listed(goals, options);
+        let goals: Vec<String> = listed.iter().map(|g| json_str(&g.0.to_string())).collect();
+        let odds: Vec<String> = listed.iter().map(|g| format!("{:.4}", g.1)).collect();
+        fields.push(("candidates", format!("[{}]", goals.join(","))));
+        fields.push(("probabilities", format!("[{}]", odds.join(","))));
+    }
+    let strategy = practical(strategy, goals.len());
+    let (recommended, metrics, _) = choose_measured(solver, strategy, options);
+    let confidence = recommended.filter(|_| goals.len() < SCORING_LIMIT).map(|r| solver.confidence(r));
+    if let Some(recommended) = recommended {
+        fields.push(("recommended", json_str(&recommended.to_string())));
+        fields.push(("strategy", json_str(chooser(strategy, &metrics))));
+    }
+    if let Some(confidence) = confidence { fields.push(("worst_case", (-confidence).to_string())); }
+    if let Some(n) = options.top.filter(|_| !goals.is_empty()) {
+        let ranked: Vec<String> = solver.ranked_guesses(n, usize::MAX).iter().map(|&(guess, worst, expected)| {
+            format!("{{\"guess\":{},\"worst_case\":{},\"expected\":{:.3}}}", json_str(&guess.to_string()), worst, expected)
+        }).collect();
+        fields.push(("top", format!("[{}]", ranked.join(","))));
+    }
+    print_json(&fields);
+    confidence
+}
+
+fn print_solved(options: &Options, guesses: usize) {
+    if options.json { print_json(&[("solved", guesses.to_string())]) } else { println!("{}", options.messages.text(Message::Solved, &[&guesses])) }
+}
+
+// The block Wordle shares at the end of a game, e.g. "Wordle 3/6" and a row
+// of tiles per guess, each followed by its word unless that would spoil it.
+fn print_share(history: &[(Word, Outcome)], options: &Options) {
+    if options.json { return }
+    let solved = history.last().is_some_and(|(_, o)| o.is_win()) && history.len() <= options.guesses;
+    println!();
+    println!("Wordle {}/{}", if solved { history.len().to_string() } else { "X".to_string() }, options.guesses);
+    println!();
+    for (guess, outcome) in history {
+        if options.spoiler_free { println!("{}", outcome) } else { println!("{} {}", outcome, spoiler(*guess, options)) }
+    }
+}
+
+// `word` behind --spoiler-markup's markup, if it's given.
+fn spoiler(word: Word, options: &Options) -> String {
+    if options.spoiler_markup { format!("||{}||", word) } else { word.to_string() }
+}
+
+// Warns when the game is down to its last couple of guesses.
+fn print_guesses_left(solver: &Solver, options: &Options) {
+    let left = options.guesses.saturating_sub(solver.history().len());
+    if left > 2 { return }
+    if options.json {
+        print_json(&[("guesses_left", left.to_string())]);
+    } else if left == 1 {
+        println!("{}", options.messages.text(Message::LastGuess, &[]));
+    } else {
+        println!("{}", options.messages.text(Message::GuessesLeft, &[&left]));
+    }
+}
+
+// Reports a game lost by running out of guesses, with the answer if it's
+// known or else what it could have been.
+fn print_lost(solver: &Solver, answer: Option<Word>, options: &Options) {
+    let goals = solver.candidates();
+    if options.json {
+        let mut fields = vec![("failed", solver.history().len().to_string())];
+        if let Some(answer) = answer { fields.push(("answer", json_str(&answer.to_string()))); }
+        print_json(&fields);
+        return;
+    }
+    match answer {
+        Some(answer) => println!("{}", options.messages.text(Message::LostAnswer, &[&answer])),
+        None if goals.len() <= 20 => {
+            println!("{}", options.messages.text(Message::LostOneOf, &[&goals.iter().map(|g| g.to_string()).collect::<Vec<_>>().join(" ")]));
+        },
+        None => println!("{}", options.messages.text(Message::LostLeft, &[&goals.len()])),
+    }
+    print_share(solver.history(), options);
+}
+
+// A guess typed by the user, if it's a word of the game's length. Case and
+// surrounding whitespace, such as a pasted line's "\r\n", are ignored.
+fn parse_guess(text: &str, length: usize) -> Option<Word> {
+    let text = text.trim();
+    Word::parse(text).or_else(|| Word::parse(&text.to_lowercase())).filter(|w| w.len() == length)
+}
+
+// What's wrong with text parse_guess rejected.
+fn guess_problem(text: &str, length: usize) -> String {
+    let text = text.trim();
+    let letters = text.to_lowercase();
+    if text.is_empty() { return "no word was typed".to_string() }
+    match letters.chars().find(|&c| alphabet().lookup(c).is_none() && !is_direction_mark(c)) {
+        Some(c) if c.is_whitespace() => format!("{:?} is more than one word", text),
+        Some(c) => format!("{:?} has {:?} in it, which isn't a letter", text, c),
+        None => format!("{:?} has {} letters, not {}", text, letters.chars().count(), length),
+    }
+}
+
+// Tiles typed by the user for a word of the game's length, or what's wrong
+// with them.
+fn parse_tiles(text: &str, length: usize) -> Result<Outcome, String> {
+    let outcome: Outcome = text.trim().parse()?;
+    if outcome.tiles().len() != length { return Err(format!("{:?} is {} tiles, not {}", text.trim(), outcome.tiles().len(), length)) }
+    Ok(outcome)
+}
+
+// Why a typed guess isn't allowed, if it isn't one of the loaded words.
+fn unknown_word(guess: Word, options: &Options) -> Option<String> {
+    if options.allow_any || options.words.contains(&guess) { return None }
+    Some(options.messages.text(Message::NotInList, &[&guess]))
+}
+
+// Which of --hard's or --ultra-hard's rules a typed guess breaks in
+// `solver`'s position, if either.
+fn broken_rule(guess: Word, solver: &Solver, options: &Options) -> Option<String> {
+    if options.hard && !solver.is_hard_mode_guess(guess) { return Some(options.messages.text(Message::BreaksHard, &[&guess])) }
+    if options.ultra_hard && !solver.pattern().matches(guess) { return Some(options.messages.text(Message::BreaksUltraHard, &[&guess])) }
+    None
+}
+
+// Why a line of input wasn't taken as a guess.
+fn not_a_guess(line: &str, length: usize) -> String {
+    format!("{}, and it isn't a command; try help", guess_problem(line, length))
+}
+
+// A goal given on the command line.
+fn goal_arg(text: &str, options: &Options) -> Result<Word, Error> {
+    parse_guess(text, options.length).ok_or_else(|| usage_error(&guess_problem(text, options.length)))
+}
+
+// `goal`, if it's one of the goals the game can have.
+fn known_goal(goal: Word, goals: &[Word], options: &Options) -> Result<Word, Error> {
+    if goals.contains(&goal) { return Ok(goal) }
+    let filtered = if options.goal_filters.iter().any(|f| f.excludes(goal)) { ", as the suffix filters rule it out" } else { "" };
+    Err(Error::Input(format!("{} isn't one of the {} goal words{}", goal, goals.len(), filtered)))
+}
+
+const COMMANDS: &str = "\
+commands between guesses:
+  hint [1|2|3]      a nudge: 1 names a letter worth trying, 2 says how many
+                    greens and yellows the recommended guess should get, and
+                    3 gives it away, even when there are many goals left
+  explain WORD      show how WORD splits the goals left
+  preview WORD      expected greens and yellows for WORD
+  probs             letter probabilities at each position
+  letters           each letter's chance of being in the answer, commonest
+                    first, and where
+  heatmap           the same, shaded, with ruled out letters dotted out
+  burner [weighted] the guess probing the most letters not yet ruled in or
+                    out, answer or not, weighted by how many goals left
+                    have each with weighted
+  history           each guess so far, its tiles and the goals it left
+  save FILE         save the guesses so far, for --resume
+  retract N         in assist, take back guess N and the tiles entered for it
+  WORD              in assist at a terminal, pick WORD's tiles with the keys
+  tab [TILES]       in assist, play the suggested guess, with TILES if given;
+                    ctrl-b takes back the last guess, ctrl-t prints a hint
+                    and ctrl-n suggests the next best guess, or the keys
+                    --key-accept and the like set
+  restart           start the game over
+  help              print this
+  quit              stop (as does end of input)";
+
+// What to do with a line of input once any command in it has run.
+enum Command {
+    // The line was a command, and has been handled.
+    Handled,
+    Quit,
+    Restart,
+    // The line wasn't a command, so it should be a guess.
+    Guess,
+}
+
+fn print_history(solver: &Solver) {
+    if solver.history().is_empty() { return println!("no guesses yet") }
+    for turn in solver.turns() {
+        println!("{}. {} {} {} left", turn.number, turn.guess, turn.outcome, turn.remaining);
+    }
+}
+
+// The guesses so far for the JSON protocol, an object per turn.
+fn json_history(solver: &Solver) -> String {
+    let turns: Vec<String> = solver.turns().iter().map(|turn| {
+        format!("{{\"turn\":{},\"guess\":{},\"outcome\":{},\"remaining\":{}}}", turn.number,
+                json_str(&turn.guess.to_string()), json_str(&turn.outcome.letters()), turn.remaining)
+    }).collect();
+    format!("[{}]", turns.join(","))
+}
+
+// The goals and other allowed words `match` finds, each group sorted.
+fn print_matches(pattern: &Pattern, goals: &[Word], dict: &[Word], options: &Options) {
+    let goal_set: HashSet<Word> = goals.iter().copied().collect();
+    let mut matched: Vec<Word> = goals.iter().copied().filter(|&w| pattern.matches(w)).collect();
+    let mut others: Vec<Word> = dict.iter().copied().filter(|&w| !goal_set.contains(&w) && pattern.matches(w)).collect();
+    matched.sort();
+    others.sort();
+    others.dedup();
+    let list = |words: &[Word]| words.iter().map(Word::to_string).collect::<Vec<_>>();
+    if options.json {
+        let json = |words: &[Word]| format!("[{}]", words.iter().map(|w| json_str(&w.to_string())).collect::<Vec<_>>().join(","));
+        return print_json(&[("goals", json(&matched)), ("others", json(&others))]);
     }
+    println!("{} goal words: {}", matched.len(), list(&matched).join(" "));
+    println!("{} other allowed words: {}", others.len(), list(&others).join(" "));
 }
 
-#[derive(Debug, Clone)]
-struct Pattern {
-    // Letters known to be present.
-    positive_letters: u32,
+// How good a guess is in itself, whatever its outcome turns out to be.
+fn print_guess_score(solver: &Solver, guess: Word) {
+    let goals = solver.candidates();
+    if goals.is_empty() { return }
+    println!("{} leaves at most {} and {:.1} expected of {}, learns {:.2} bits, and ranks {} of {} guesses",
+             guess, worst_bucket(guess, goals), expected_remaining(guess, goals), goals.len(), outcome_entropy(guess, goals),
+             solver.rank(guess), solver.dict().len());
+}
 
-    // Letters known to be absent.
-    negative_letters: u32,
+// The same for --json.
+fn guess_scores(solver: &Solver, guess: Word) -> Vec<(&'static str, String)> {
+    let goals = solver.candidates();
+    if goals.is_empty() { return Vec::new() }
+    vec![
+        ("guess_worst_case", worst_bucket(guess, goals).to_string()),
+        ("guess_expected", format!("{:.3}", expected_remaining(guess, goals))),
+        ("guess_entropy", format!("{:.3}", outcome_entropy(guess, goals))),
+        ("guess_rank", solver.rank(guess).to_string()),
+    ]
+}
 
-    // Letter masks per character.
-    per_char: [u32; 5]
+// A guess spent on learning letters rather than on winning.
+fn print_burner(solver: &Solver, weighted: bool) {
+    match burner_guess(solver.pattern(), solver.candidates(), solver.dict(), weighted) {
+        Some(guess) => println!("burn {}, leaving at most {} of {}", guess, worst_bucket(guess, solver.candidates()), solver.candidates().len()),
+        None => println!("no guesses left to burn"),
+    }
 }
 
-impl Pattern {
-    fn new() -> Self {
-        Self { positive_letters: 0, negative_letters: 0, per_char: [(1 << 26) - 1; 5] }
+// A hint about the recommended guess, giving away more at each level.
+fn print_hint(solver: &Solver, strategy: &dyn Strategy, level: u8) {
+    let goals = solver.candidates();
+    let Some(recommended) = solver.choose(practical(strategy, goals.len())) else { return println!("no goal words left") };
+    // The recommended guess's letter the goals are closest to evenly split
+    // on, which is the most there is to learn about.
+    let presence = letter_presence(goals);
+    let letter = recommended.symbols().iter().copied().filter(|&l| presence[l as usize] > 0.0 && presence[l as usize] < 1.0)
+        .max_by(|&a, &b| {
+            let spread = |l: u8| presence[l as usize] * (1.0 - presence[l as usize]);
+            spread(a).total_cmp(&spread(b)).then(b.cmp(&a))
+        });
+    match (level, letter) {
+        (1, Some(l)) => println!("try a word with {}, which {:.0}% of the goals left have", alphabet().symbol(l), 100.0 * presence[l as usize]),
+        (1 | 2, _) => {
+            let (greens, yellows) = expected_tiles(recommended, goals);
+            println!("the recommended guess should get {:.1} greens and {:.1} yellows, leaving at most {}",
+                     greens, yellows, worst_bucket(recommended, goals));
+        },
+        _ => println!("try {}", recommended),
     }
+}
 
-    fn matches(&self, word: Word) -> bool {
-        if word.letters & self.positive_letters != self.positive_letters { return false }
-        if word.letters & self.negative_letters != 0 { return false }
-        word.bytes.iter().zip(self.per_char.iter()).all(|(&w, &m)| (m & to_letter_mask(w)) != 0)
+// Handles the commands available between guesses.
+fn run_command(line: &str, solver: &Solver, options: &Options, strategy: &dyn Strategy) -> Command {
+    let length = options.length;
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["quit"] | ["exit"] => return Command::Quit,
+        ["restart"] => return Command::Restart,
+        ["help"] | ["?"] => println!("{}", COMMANDS),
+        ["hint"] => print_hint(solver, strategy, 1),
+        ["hint", level] => match level.parse() {
+            Ok(level @ 1..=3) => print_hint(solver, strategy, level),
+            _ => println!("hint levels are 1, 2 and 3"),
+        },
+        ["heatmap"] => print_heatmap(solver.pattern(), solver.candidates(), options.color),
+        ["probs"] => print_probabilities(solver.candidates()),
+        ["letters"] => print_letters(solver.candidates()),
+        ["history"] => print_history(solver),
+        ["burner"] => print_burner(solver, false),
+        ["burner", "weighted"] => print_burner(solver, true),
+        ["preview", word] => match parse_guess(word, length) {
+            Some(word) => print_preview(word, solver.candidates()),
+            None => println!("invalid"),
+        },
+        ["explain", word] => match parse_guess(word, length) {
+            Some(word) => print_explanation(word, solver.candidates()),
+            None => println!("invalid"),
+        },
+        ["save", filename] => match save(solver, filename) {
+            Ok(()) => println!("saved {} guesses to {}", solver.history().len(), filename),
+            Err(e) => println!("couldn't save to {}: {}", filename, e),
+        },
+        _ => return Command::Guess,
     }
+    Command::Handled
+}
 
-    fn refine(&mut self, word: Word, Outcome(letter_outcomes): Outcome) {
-        for i in 0..5 {
-            let m = to_letter_mask(word.bytes[i]);
-            match letter_outcomes[i] {
-                LetterOutcome::Nowhere => {
-                    self.negative_letters |= m;
-                    for x in self.per_char.iter_mut() { *x &= !m; }
+// Advice for a game whose answer we don't know, like today's real puzzle:
+// each line is a guess and the tiles it got, e.g. "crane gybgg" or
+// "crane ⬜🟨⬜🟩🟩".
+fn assist(mut solver: Solver, options: &Options, strategy: &dyn Strategy) -> io::Result<()> {
+    let mut buf = String::new();
+    let initial = solver.clone();
+    'game: loop {
+        if options.hard { solver.retain_hard_mode_guesses(); }
+        if options.ultra_hard { solver.retain_matching_guesses(); }
+        if solver.candidates().is_empty() && !options.answers_anywhere {
+            let widened = widen(&initial, &solver);
+            if !widened.candidates().is_empty() {
+                let n = widened.candidates().len();
+                print_error(options, &format!("no goal words fit, so any allowed word might be the answer; {} {}", n, if n == 1 { "does" } else { "do" }));
+                solver = widened;
+            }
+        }
+        print_guesses_left(&solver, options);
+        // On the --tui screen at a terminal, words and tiles can be clicked.
+        let screen = if options.tui && at_terminal(options) {
+            Some(print_screen(&solver, options, strategy).1)
+        } else {
+            print_turn(&solver, options, strategy);
+            None
+        };
+        if solver.candidates().is_empty() { print_error(options, &contradiction(&initial, &solver)); }
+        // What the accept and cycle keys play, worked out when first asked.
+        let (mut suggested, mut next) = (Vec::new(), 0);
+        let mut typed = String::new();
+        let (guess, outcome) = loop {
+            buf.clear();
+            let action = match &screen {
+                Some(screen) => match read_screen_line(screen, &std::mem::take(&mut typed), options)? {
+                    None => return Ok(()),
+                    Some(Input::Line(line)) => { buf = line; None },
+                    // Clicking a word plays it, so its tiles are picked next.
+                    Some(Input::Clicked(Target::Guess(word))) => { buf = word.to_string(); None },
+                    Some(Input::Clicked(Target::Tile(i, tile))) if i >= initial.history().len() => {
+                        solver = with_tile_cycled(&initial, solver.history(), i, tile);
+                        continue 'game;
+                    },
+                    Some(Input::Clicked(Target::Tile(..))) => { print_error(options, "only the guesses entered here can be changed"); continue },
+                    Some(Input::Bound(action)) => Some((action, String::new())),
                 },
-                LetterOutcome::Elsewhere => {
-                    self.positive_letters |= m;
-                    self.per_char[i] &= !m;
+                None => {
+                    if io::stdin().read_line(&mut buf)? == 0 { return Ok(()) }
+                    bound_line(&buf, options).map(|(action, rest)| (action, rest.to_string()))
                 },
-                LetterOutcome::Here => {
-                    self.positive_letters |= m;
-                    self.per_char[i] = m;
+            };
+            match action {
+                Some((Action::Undo, _)) => match solver.history().len().checked_sub(1).filter(|&n| n >= initial.history().len()) {
+                    Some(last) => { solver = without_guess(&initial, solver.history(), last); continue 'game },
+                    None => { print_error(options, &options.messages.text(Message::NothingToUndo, &[])); continue },
                 },
+                Some((Action::Hint, _)) => { print_hint(&solver, strategy, 1); continue },
+                Some((action, tiles)) => {
+                    if suggested.is_empty() { suggested = suggestions(&solver, options, strategy); }
+                    let Some(&word) = suggested.get(next) else { print_error(options, "there's no guess to suggest"); continue };
+                    if action == Action::Accept {
+                        buf = format!("{} {}", word, tiles);
+                    } else {
+                        next = (next + 1) % suggested.len();
+                        if screen.is_some() {
+                            typed = suggested[next].to_string();
+                        } else {
+                            let word = suggested[next].to_string();
+                            if options.json { print_json(&[("suggestion", json_str(&word))]) } else { println!("next suggestion is {} ({} of {})", word, next + 1, suggested.len()) }
+                        }
+                        continue;
+                    }
+                },
+                None => {},
+            }
+            if let ["retract", n] = buf.split_whitespace().collect::<Vec<_>>()[..] {
+                let entered = initial.history().len() + 1..=solver.history().len();
+                match n.parse().ok().filter(|n| entered.contains(n)) {
+                    Some(n) => { solver = without_guess(&initial, solver.history(), n - 1); continue 'game },
+                    None if entered.is_empty() => print_error(options, "no guesses have been entered to retract"),
+                    None => print_error(options, &format!("retract takes a guess number, {} to {}", entered.start(), entered.end())),
+                }
+                continue;
             }
+            match run_command(&buf, &solver, options, strategy) {
+                Command::Handled => continue,
+                Command::Quit => return Ok(()),
+                Command::Restart => { solver = initial.clone(); continue 'game },
+                Command::Guess => {},
+            }
+            let message = match buf.split_whitespace().collect::<Vec<_>>()[..] {
+                [text, tiles] => match (parse_guess(text, options.length), parse_tiles(tiles, options.length)) {
+                    (None, _) => guess_problem(text, options.length),
+                    (_, Err(message)) => message,
+                    (Some(guess), Ok(outcome)) => match unknown_word(guess, options).or_else(|| broken_rule(guess, &solver, options)) {
+                        Some(message) => message,
+                        None => break (guess, outcome),
+                    },
+                },
+                // At a terminal, a word alone has its tiles picked with keys.
+                [text] if at_terminal(options) => match parse_guess(text, options.length) {
+                    None => guess_problem(text, options.length),
+                    Some(guess) => match unknown_word(guess, options).or_else(|| broken_rule(guess, &solver, options)) {
+                        Some(message) => message,
+                        None => match pick_tiles(guess, options)? {
+                            Some(outcome) => break (guess, outcome),
+                            None => continue,
+                        },
+                    },
+                },
+                _ => options.messages.text(Message::ExpectedGuess, &[]),
+            };
+            print_error(options, &message);
+        };
+        if options.json {
+            let mut fields = vec![("guess", json_str(&guess.to_string()))];
+            fields.extend(guess_scores(&solver, guess));
+            print_json(&fields);
+        } else if !options.quiet && !options.compact {
+            print_guess_score(&solver, guess);
+        }
+        solver.refine(guess, outcome);
+        let over = outcome.is_win() || solver.history().len() >= options.guesses;
+        if over { record(options, "assist", Some(solver.history().len()).filter(|_| outcome.is_win())); }
+        if outcome.is_win() {
+            print_solved(options, solver.history().len());
+            print_share(solver.history(), options);
+        } else if over {
+            print_lost(&solver, None, options);
+        }
+        if over {
+            let history = &solver.history()[initial.history().len()..];
+            if options.review { print_review(&initial, history, None, strategy, options); }
+            if options.markdown_summary { print_markdown_summary(&initial, history, None, strategy, options); }
+            return Ok(());
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum LetterOutcome { Nowhere, Elsewhere, Here }
+// Whether standard input and output are a terminal that keys can be read
+// from and drawn to.
+fn at_terminal(options: &Options) -> bool {
+    !options.json && io::stdin().is_terminal() && io::stdout().is_terminal()
+}
 
-impl fmt::Display for LetterOutcome {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str(match self {
-            LetterOutcome::Here => "🟩",
-            LetterOutcome::Elsewhere => "🟨",
-            LetterOutcome::Nowhere => "⬜",
-        })
+// Tiles for `guess` picked a key at a time at the terminal and drawn as they
+// change, or None if backspace gave up on them. With --tui, clicking a tile
+// changes its color too.
+fn pick_tiles(guess: Word, options: &Options) -> io::Result<Option<Outcome>> {
+    if !options.quiet {
+        println!("left and right choose a tile, space or up and down change its color, and g, y or b set it;");
+        println!("enter takes the tiles, and backspace gives up on them");
+    }
+    let _raw = if options.tui { RawMode::with_mouse()? } else { RawMode::new()? };
+    let mut picker = TilePicker::new(guess.len());
+    let mut stdin = io::stdin().lock();
+    let row = if options.tui { cursor_row(&mut stdin)? } else { None };
+    let columns = tile_columns(guess, options.color, 4, true);
+    loop {
+        print!("\r\x1b[K{}", picker_line(guess, &picker, options.colors()));
+        io::stdout().flush()?;
+        let picked = match read_key(&mut stdin)? {
+            Some(Key::Click { row: clicked, column }) => {
+                if let Some(tile) = columns.iter().position(|c| c.contains(&column)).filter(|_| Some(clicked) == row) { picker.click(tile); }
+                Picked::Editing
+            },
+            Some(key) => picker.press(key),
+            None => Picked::Cancelled,
+        };
+        match picked {
+            Picked::Editing => {},
+            Picked::Done(outcome) => { println!(); return Ok(Some(outcome)) },
+            Picked::Cancelled => { println!(); return Ok(None) },
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Outcome([LetterOutcome; 5]);
-
-impl Outcome {
-    fn compare(goal: Word, guess: Word) -> Self {
-        let mut result = [LetterOutcome::Nowhere; 5];
-        for i in 0..5 {
-            result[i] = if goal.bytes[i] == guess.bytes[i] {
-                LetterOutcome::Here
-            } else if goal.bytes.contains(&guess.bytes[i]) {
-                LetterOutcome::Elsewhere
+// `guess` with the tiles picked so far, the one at the cursor bracketed, in
+// reading order, as the picker is only drawn at a terminal.
+fn picker_line(guess: Word, picker: &TilePicker, colors: Option<&Theme>) -> String {
+    let cell = |i: usize, text: String| if i == picker.cursor { format!("[{}]", text) } else { format!(" {} ", text) };
+    let Some(theme) = colors else {
+        let mut cells: Vec<String> = guess.to_string().chars().map(String::from).collect();
+        cells.push(" ".to_string());
+        cells.extend(picker.tiles.iter().enumerate().map(|(i, tile)| cell(i, tile.to_string())));
+        return in_reading_order(cells)
+    };
+    in_reading_order(guess.to_string().chars().zip(&picker.tiles).enumerate().map(|(i, (c, &tile))| theme.tile(tile, &cell(i, c.to_uppercase().to_string()))).collect())
+}
+
+// The game from `initial` with the guesses entered after it, but the color of
+// one tile of the one at index `guess` changed, as the picker changes them.
+fn with_tile_cycled(initial: &Solver, history: &[(Word, Outcome)], guess: usize, tile: usize) -> Solver {
+    let mut solver = initial.clone();
+    for (i, &(word, outcome)) in history.iter().enumerate().skip(initial.history().len()) {
+        let mut picker = TilePicker { tiles: outcome.tiles().to_vec(), ..TilePicker::new(word.len()) };
+        if i == guess { picker.click(tile); }
+        solver.refine(word, Outcome::new(&picker.tiles));
+    }
+    solver
+}
+
+// The game `solver` has played since `initial`, with every word `initial`
+// allowed as a goal, for when the answer is missing from the goal list.
+fn widen(initial: &Solver, solver: &Solver) -> Solver {
+    let words = initial.dict().to_vec();
+    solver.replayed(words.clone(), words)
+}
+
+// The game from `initial` with the guesses entered after it, except the one
+// at index `skip`.
+fn without_guess(initial: &Solver, history: &[(Word, Outcome)], skip: usize) -> Solver {
+    let mut solver = initial.clone();
+    for (i, &(guess, outcome)) in history.iter().enumerate().skip(initial.history().len()) {
+        if i != skip { solver.refine(guess, outcome); }
+    }
+    solver
+}
+
+// Why no word fits the tiles entered since `initial`, not even once assist
+// has widened the goals to every allowed word: the guesses some allowed word
+// would fit without.
+fn contradiction(initial: &Solver, solver: &Solver) -> String {
+    let history = solver.history();
+    let fits_without = |skip: usize| initial.dict().iter().any(|&word| {
+        history.iter().enumerate().all(|(i, &(guess, outcome))| i == skip || Outcome::compare(word, guess) == outcome)
+    });
+    let suspects: Vec<usize> = (initial.history().len()..history.len()).filter(|&i| fits_without(i)).collect();
+    match suspects[..] {
+        [i] => format!("no words fit; guess {} ({} {}) conflicts with the others, so \"retract {}\" takes it back",
+                       i + 1, history[i].0, history[i].1.letters(), i + 1),
+        [.., last] => {
+            let numbers: Vec<String> = suspects.iter().map(|i| (i + 1).to_string()).collect();
+            format!("no words fit; taking back any of guesses {} would fix it, e.g. \"retract {}\"", numbers.join(", "), last + 1)
+        },
+        [] => "no words fit the tiles; were more than one of them entered wrong?".to_string(),
+    }
+}
+
+// A line of JSON input: an object whose values are all strings.
+fn parse_json_object(line: &str) -> Result<Vec<(String, String)>, String> {
+    let malformed = || format!("{:?} isn't a JSON object of strings", line.trim());
+    let mut rest = line.trim().strip_prefix('{').ok_or_else(malformed)?.trim_start();
+    // The four hex digits of a \u escape.
+    let hex = |chars: &mut std::str::CharIndices| -> Option<u32> { (0..4).try_fold(0, |n, _| Some(n * 16 + chars.next()?.1.to_digit(16)?)) };
+    // A string at the start of `rest`, and what follows it.
+    let string = |rest: &str| -> Option<(String, usize)> {
+        let mut chars = rest.strip_prefix('"')?.char_indices();
+        let mut string = String::new();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Some((string, i + 2)),
+                '\\' => string.push(match chars.next()?.1 {
+                    c @ ('"' | '\\' | '/') => c,
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => {
+                        let mut code = hex(&mut chars)?;
+                        // Characters beyond the first 65536 come as two
+                        // escapes, a surrogate pair.
+                        if (0xd800..0xdc00).contains(&code) {
+                            if chars.next()?.1 != '\\' || chars.next()?.1 != 'u' { return None }
+                            let low = hex(&mut chars).filter(|low| (0xdc00..0xe000).contains(low))?;
+                            code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                        }
+                        char::from_u32(code)?
+                    },
+                    _ => return None,
+                }),
+                c => string.push(c),
+            }
+        }
+        None
+    };
+    let mut fields = Vec::new();
+    if let Some(end) = rest.strip_prefix('}') { return if end.trim().is_empty() { Ok(fields) } else { Err(malformed()) } }
+    loop {
+        let (key, n) = string(rest).ok_or_else(malformed)?;
+        rest = rest[n..].trim_start().strip_prefix(':').ok_or_else(malformed)?.trim_start();
+        let (value, n) = string(rest).ok_or_else(malformed)?;
+        fields.push((key, value));
+        rest = rest[n..].trim_start();
+        if let Some(next) = rest.strip_prefix(',') {
+            rest = next.trim_start();
+        } else {
+            let end = rest.strip_prefix('}').ok_or_else(malformed)?;
+            return if end.trim().is_empty() { Ok(fields) } else { Err(malformed()) };
+        }
+    }
+}
+
+// Assist mode driven by JSON commands, one per line: a guess with its
+// outcome, e.g. {"guess":"crane","outcome":"gybgg"}, or {"command":...}
+// with "state", "history", "restart" or "quit". Every line gets a line of
+// JSON back, and a won or lost game starts over.
+fn protocol(mut solver: Solver, options: &Options, strategy: &dyn Strategy) -> io::Result<()> {
+    let initial = solver.clone();
+    print_json_turn(&solver, strategy, options);
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() { continue }
+        let fields = match parse_json_object(&line) {
+            Ok(fields) => fields,
+            Err(e) => { print_error(options, &e); continue },
+        };
+        let field = |name: &str| fields.iter().find(|f| f.0 == name).map(|f| f.1.as_str());
+        match (field("command"), field("guess"), field("outcome")) {
+            (Some("quit"), None, None) => return Ok(()),
+            (Some("restart"), None, None) => solver = initial.clone(),
+            (Some("state"), None, None) => {},
+            (Some("history"), None, None) => { print_json(&[("history", json_history(&solver))]); continue },
+            (None, Some(guess), Some(tiles)) => {
+                let (guess, outcome) = match (parse_guess(guess, options.length), parse_tiles(tiles, options.length)) {
+                    (Some(guess), Ok(outcome)) => (guess, outcome),
+                    (None, _) => { print_error(options, &guess_problem(guess, options.length)); continue },
+                    (_, Err(message)) => { print_error(options, &message); continue },
+                };
+                if let Some(message) = unknown_word(guess, options).or_else(|| broken_rule(guess, &solver, options)) {
+                    print_error(options, &message);
+                    continue;
+                }
+                solver.refine(guess, outcome);
+                if outcome.is_win() {
+                    print_solved(options, solver.history().len());
+                    solver = initial.clone();
+                } else if solver.history().len() >= options.guesses {
+                    print_lost(&solver, None, options);
+                    solver = initial.clone();
+                }
+            },
+            _ => { print_error(options, "expected a guess and outcome, or a command of state, history, restart or quit"); continue },
+        }
+        if options.hard { solver.retain_hard_mode_guesses(); }
+        if options.ultra_hard { solver.retain_matching_guesses(); }
+        print_json_turn(&solver, strategy, options);
+    }
+    Ok(())
+}
+
+// Assist mode for several boards sharing guesses: each line is a guess and
+// the tiles it got on every board, in order, e.g. "crane gybgg bbbyb".
+fn assist_boards(mut solver: MultiSolver, length: usize) -> io::Result<()> {
+    let mut buf = String::new();
+    let n = solver.boards().len();
+    loop {
+        for (i, board) in solver.boards().iter().enumerate() {
+            let goals = board.candidates();
+            if solver.is_solved(i) {
+                println!("board {}: solved", i + 1);
+            } else if goals.len() <= 6 {
+                println!("board {}: {}", i + 1, goals.iter().map(|g| g.to_string()).collect::<Vec<_>>().join(" "));
             } else {
-                LetterOutcome::Nowhere
+                println!("board {}: {} matching goal words", i + 1, goals.len());
+            }
+        }
+        if let Some(recommended) = solver.choose() { println!("recommended guess is {}", recommended); }
+        let (guess, outcomes) = loop {
+            buf.clear();
+            if io::stdin().read_line(&mut buf)? == 0 { return Ok(()) }
+            let fields: Vec<&str> = buf.split_whitespace().collect();
+            if fields.len() == n + 1 {
+                let outcomes: Result<Vec<Outcome>, _> = fields[1..].iter().map(|t| t.parse()).collect();
+                if let (Some(guess), Ok(outcomes)) = (parse_guess(fields[0], length), outcomes) {
+                    if outcomes.iter().all(|o| o.tiles().len() == length) { break (guess, outcomes) }
+                }
             }
+            println!("expected a guess and its tiles on each of the {} boards", n);
+        };
+        solver.refine(guess, &outcomes);
+        if solver.all_solved() {
+            println!("all {} boards solved", n);
+            return Ok(());
         }
-        Self(result)
     }
 }
 
-impl fmt::Display for Outcome {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        for o in self.0 { o.fmt(formatter)? }
-        Ok(())
-    }
-}
-
-fn recommended_guess(pattern: &Pattern, goals: &Vec<Word>, dict: &Vec<Word>) -> (Word, i32) {
-    let best_from = |dict: &Vec<Word>| -> (Word, i32) {
-        let mut n = 0;
-        dict.iter().map(|&guess| {
-            n += 1;
-            if n % 100 == 0 { eprint!("."); }
-            let min_confidence = goals.iter().map(|&goal| -> i32 {
-                let outcome = Outcome::compare(goal, guess);
-                let mut hypothetical_pattern = pattern.clone();
-                hypothetical_pattern.refine(guess, outcome);
-                -goals.iter().fold(0, |c, &g| c + hypothetical_pattern.matches(g) as i32)
-            }).min().unwrap();
-            (guess, min_confidence)
-        }).max_by_key(|p| p.1).unwrap()
-    };
-    let (bd, bdc) = best_from(dict);
-    let (bg, bgc) = best_from(goals);
-    assert!(bdc >= bgc);
-    if bgc+1 >= bdc { (bg, bgc) } else { (bd, bdc) }
-}
-
-fn read_dict(filename: &str) -> io::Result<Vec<Word>> {
-    let file = File::open(filename)?;
-    let mut dict = Vec::new();
-    for line_result in io::BufReader::new(file).lines() {
-        let line = line_result?;
-        if line.len() != 5 || line.chars().any(|c| !c.is_ascii_lowercase()) {
-            Err(io::Error::new(io::ErrorKind::InvalidData, "malformed word"))?;
+// Hosts a game with no fixed answer: each guess gets whichever outcome keeps
+// the most goals in play, so the answer is only pinned down at the end.
+fn absurdle(mut solver: Solver, options: &Options, strategy: &dyn Strategy) -> io::Result<()> {
+    let mut buf = String::new();
+    let initial = solver.clone();
+    'game: loop {
+        if options.hard { solver.retain_hard_mode_guesses(); }
+        if options.ultra_hard { solver.retain_matching_guesses(); }
+        print_turn(&solver, options, strategy);
+        let guess = loop {
+            buf.clear();
+            if io::stdin().read_line(&mut buf)? == 0 { return Ok(()) }
+            match run_command(&buf, &solver, options, strategy) {
+                Command::Handled => continue,
+                Command::Quit => return Ok(()),
+                Command::Restart => { solver = initial.clone(); continue 'game },
+                Command::Guess => {},
+            }
+            match parse_guess(buf.trim(), options.length) {
+                Some(guess) => match unknown_word(guess, options).or_else(|| broken_rule(guess, &solver, options)) {
+                    Some(message) => print_error(options, &message),
+                    None => break guess,
+                },
+                None => print_error(options, &not_a_guess(&buf, options.length)),
+            }
+        };
+        let outcome = adversarial_outcome(guess, solver.candidates());
+        if options.json {
+            print_json(&[("guess", json_str(&guess.to_string())), ("outcome", json_str(&outcome.letters()))]);
+        } else {
+            println!("outcome is {}", outcome);
         }
+        solver.refine(guess, outcome);
+        if outcome.is_win() {
+            print_solved(options, solver.history().len());
+            print_share(solver.history(), options);
+            return Ok(());
+        }
+    }
+}
 
-        dict.push(Word::new(&line));
+// Days since 1970-01-01 of a date written YYYY-MM-DD, or of today's in UTC.
+fn parse_date(text: &str) -> Option<i64> {
+    if text == "today" {
+        let since = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+        return Some((since.as_secs() / 86400) as i64);
     }
-    Ok(dict)
+    let mut parts = text.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) { return None }
+    // Howard Hinnant's days_from_civil, with years starting in March.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some(era * 146097 + day_of_era - 719468)
+}
+
+// The date `days` after 1970-01-01, as YYYY-MM-DD: civil_from_days, the
+// inverse of parse_date's.
+fn format_date(days: i64) -> String {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Adds a finished game to the stats file, if games of `mode` are recorded:
+// a line of today's date, the variant, the mode and the guesses it took, or
+// X if it was lost, e.g. "2026-01-31 wordle host 4".
+// A daily game is recorded as such, under the day of its puzzle.
+fn record(options: &Options, mode: &str, solved: Option<usize>) {
+    if !options.record.unwrap_or(mode == "host" || options.daily.is_some()) { return }
+    let Some(path) = &options.stats else { return };
+    let mode = if options.daily.is_some() { "daily" } else { mode };
+    let date = options.daily.or_else(|| parse_date("today")).map_or_else(|| "?".to_string(), format_date);
+    let line = format!("{} {} {} {}\n", date, options.variant.name, mode, solved.map_or("X".to_string(), |n| n.to_string()));
+    if let Some(dir) = std::path::Path::new(path).parent() { let _ = std::fs::create_dir_all(dir); }
+    let appended = std::fs::OpenOptions::new().create(true).append(true).open(path).and_then(|mut file| {
+        use std::io::Write;
+        file.write_all(line.as_bytes())
+    });
+    if let Err(e) = appended { eprintln!("couldn't record the game in {}: {}", path, e); }
+}
+
+// Whether the variant's puzzle for `day` is recorded as played already.
+fn played_daily(options: &Options, day: i64) -> bool {
+    let Some(text) = options.stats.as_ref().and_then(|path| std::fs::read_to_string(path).ok()) else { return false };
+    let date = format_date(day);
+    text.lines().any(|line| line.split_whitespace().take(3).eq([date.as_str(), options.variant.name, "daily"]))
 }
 
-fn main() -> io::Result<()> {
-    let mut goals = read_dict("goals.txt")?;
-    let mut dict = read_dict("extra.txt")?;
-    dict.extend(&goals);
+// The goal for `day`, the same whatever the --seed or shuffle, and whatever
+// version of the program picks it, by a hash that won't change. By the
+// original's rules, it's the next of the `ordered` goals instead, starting
+// over once they run out, and the ones already used that time through are
+// no longer goals.
+fn daily_goal(goals: &mut Vec<Word>, ordered: &[Word], day: i64, options: &Options) -> Result<Word, Error> {
+    if goals.is_empty() { return Err(Error::Input("there are no goals".to_string())) }
+    if options.profile == Some(Profile::Original) {
+        if ordered.is_empty() { return Err(Error::Input("there are no goals".to_string())) }
+        if day < ORIGINAL_EPOCH { return Err(Error::Input(format!("the original game began on {}", format_date(ORIGINAL_EPOCH)))) }
+        let i = ((day - ORIGINAL_EPOCH) as u64 % ordered.len() as u64) as usize;
+        goals.retain(|g| !ordered[..i].contains(g));
+        return Ok(ordered[i])
+    }
+    goals.sort();
+    Ok(goals[(fnv(day.to_le_bytes()) % goals.len() as u64) as usize])
+}
 
-    goals.shuffle(&mut thread_rng());
-    dict.shuffle(&mut thread_rng());
+// The recorded games of the variant being played, like the game's
+// statistics panel: how many were won, the streaks of wins in a row, and
+// how many guesses the wins took.
+fn print_stats(text: &str, options: &Options) {
+    let results: Vec<Option<usize>> = text.lines().filter_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 4 || fields[1] != options.variant.name { return None }
+        Some(fields[3].parse().ok())
+    }).collect();
+    if results.is_empty() { return println!("no {} games recorded", options.variant.name) }
+    let wins: Vec<usize> = results.iter().flatten().copied().collect();
+    let streaks: Vec<usize> = results.split(Option::is_none).map(<[_]>::len).collect();
+    println!("played {}, won {:.0}%, current streak {}, max streak {}", results.len(),
+             100.0 * wins.len() as f64 / results.len() as f64, streaks.last().unwrap(), streaks.iter().max().unwrap());
+    if wins.is_empty() { return }
+    println!("average {:.2} guesses a win", wins.iter().sum::<usize>() as f64 / wins.len() as f64);
+    let most = wins.iter().copied().max().unwrap().max(options.guesses);
+    let counts: Vec<usize> = (1..=most).map(|n| wins.iter().filter(|&&w| w == n).count()).collect();
+    let top = *counts.iter().max().unwrap();
+    for (n, &count) in counts.iter().enumerate() {
+        println!("{:>3} {:>5} {}", n + 1, count, "#".repeat((count * 40).div_ceil(top)));
+    }
+}
 
-    let args: Vec<String> = env::args().collect();
-    let goal = Word::new(&args[1]);
-    let mut pattern = Pattern::new();
+// A game of Wordle with the program as host: guesses are checked against the
+// allowed words, and the board is redrawn after each, until the goal is found
+// or the guesses run out. Typing quit gives up and shows the goal.
+fn host(goal: Word, mut solver: Solver, options: &Options) -> io::Result<()> {
     let mut buf = String::new();
-    let stdin = io::stdin();
+    if !options.json { println!("guess the {}-letter word in {} tries", options.length, options.guesses); }
     loop {
-        println!("pattern is {:?}", pattern);
-        goals.retain(|w| pattern.matches(*w));
-        println!("  {} matching goal words", goals.len());
-        if goals.len() <= 20 {
-            for g in &goals { println!("  {}", g); }
+        if options.hard { solver.retain_hard_mode_guesses(); }
+        if options.ultra_hard { solver.retain_matching_guesses(); }
+        print_guesses_left(&solver, options);
+        let guess = loop {
+            buf.clear();
+            if io::stdin().read_line(&mut buf)? == 0 || ["quit", "exit"].contains(&buf.trim()) {
+                record(options, "host", None);
+                if options.json { print_json(&[("answer", json_str(&goal.to_string()))]) } else { println!("the answer was {}", goal) }
+                return Ok(());
+            }
+            let guess = match parse_guess(buf.trim(), options.length) {
+                Some(guess) => guess,
+                None => { print_error(options, &guess_problem(&buf, options.length)); continue },
+            };
+            match unknown_word(guess, options).or_else(|| broken_rule(guess, &solver, options)) {
+                Some(message) => print_error(options, &message),
+                None => break guess,
+            }
+        };
+        let outcome = Outcome::compare(goal, guess);
+        solver.refine(guess, outcome);
+        if options.json {
+            print_json(&[("guess", json_str(&guess.to_string())), ("outcome", json_str(&outcome.letters()))]);
+        } else {
+            print_board(solver.history(), options.colors());
+            println!();
+            print_keyboard(solver.pattern(), options.colors());
         }
-        if goals.len() < 1000 {
-            let (recommended, confidence) = recommended_guess(&pattern, &goals, &dict);
-            println!("recommended guess is {} (at most {} possible words)", recommended, -confidence);
+        if outcome.is_win() {
+            record(options, "host", Some(solver.history().len()));
+            print_solved(options, solver.history().len());
+            print_share(solver.history(), options);
+            return Ok(());
         }
+        if solver.history().len() >= options.guesses {
+            record(options, "host", None);
+            print_lost(&solver, Some(goal), options);
+            return Ok(());
+        }
+    }
+}
 
-        buf.clear();
-        let length = stdin.read_line(&mut buf)?;
-        if length != 6 || buf[0..5].chars().any(|c| !c.is_ascii_lowercase()) {
-            println!("invalid");
-            continue;
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("wordlesolve: {}", e);
+            ExitCode::from(e.status())
+        },
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // The config file's flags go first, so the command line's override them.
+    let args: Vec<String> = env::args().skip(1).collect();
+    let config = match args.iter().rposition(|a| a == "--config") {
+        Some(i) => Some((flag_value::<String>("--config", args.get(i + 1).cloned())?, true)),
+        None => default_config().map(|path| (path, false)),
+    };
+    let mut flags = Vec::new();
+    if let Some((path, given)) = config.filter(|_| !args.iter().any(|a| a == "--no-config")) {
+        match std::fs::read_to_string(&path) {
+            Ok(text) => flags = config_args(&path, &text)?,
+            Err(e) if given || e.kind() != io::ErrorKind::NotFound => return Err(file_error(&path, e)),
+            Err(_) => {},
+        }
+    }
+    let (mut options, args) = Options::parse(flags.into_iter().chain(args))?;
+    if options.help {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+    let command = args.first().ok_or_else(|| usage_error("no command given"))?.as_str();
+    let expected = match command {
+        "wordlist" => 4..=4,
+        "update-wordlists" => 1..=3,
+        "dict" => 3..=usize::MAX,
+        "book" => 2..=usize::MAX,
+        "verify" | "burner" | "simulate" => 1..=2,
+        "match" => 2..=4,
+        "play" | "crowd" => 2..=2,
+        "analyze" | "absurdle" | "import" | "review" | "host" => 1..=2,
+        "daily" => 1..=3,
+        _ => 1..=1,
+    };
+    if !expected.contains(&args.len()) { return Err(usage_error(&format!("wrong number of arguments to {}", command))) }
+    if command == "wordlist" {
+        if args[1] != "diff" { return Err(usage_error(&format!("unknown wordlist command {}", args[1]))) }
+        let list = |filename: &str| read_dict(filename, options.length, options.max_words).map_err(|e| list_error(filename, e));
+        wordlist_diff(&list(&args[2])?, &list(&args[3])?);
+        return Ok(());
+    }
+
+    if command == "dict" { return dict_command(&args[1..], &options) }
+    if command == "update-wordlists" { return update_wordlists(&args[1..], &options) }
+
+    let list = |filename: &Option<String>, builtin: &str, name| match filename {
+        Some(filename) => read_dict(filename, options.length, options.max_words).map_err(|e| list_error(filename, e)),
+        None => read_words(builtin.as_bytes(), name, options.length, options.max_words).map_err(|e| list_error(name, e)),
+    };
+    if options.dict.is_some() && options.allowed.is_some() { return Err(usage_error("--dict and --allowed can't both be given")) }
+    // Lists update-wordlists installed stand in for the built-in ones.
+    let goals_file = options.goals.clone().or_else(|| installed_list(&options, "goals.txt"));
+    let dict_file = options.allowed.clone().or_else(|| options.dict.clone()).or_else(|| installed_list(&options, "extra.txt"));
+    let mut goals = list(&goals_file, options.variant.goals, "built-in goals")?;
+    let mut dict = list(&dict_file, options.variant.allowed, "built-in guesses")?;
+    if goals.is_empty() {
+        return Err(Error::Input(format!("no {}-letter goal words", options.length)));
+    }
+    if let Some(filename) = &options.frequencies {
+        options.priors = Some(Arc::new(read_priors(filename, options.length).map_err(|e| file_error(filename, e))?));
+    }
+    // Lists may overlap, as Wordle's did before extra.txt left the goals out.
+    dedup(&mut goals);
+    dedup(&mut dict);
+    match &options.allowed {
+        Some(filename) if !options.goals_are_allowed => {
+            let allowed: HashSet<Word> = dict.iter().copied().collect();
+            let missing = goals.iter().filter(|g| !allowed.contains(g)).count();
+            if missing > 0 { eprintln!("{} of the goals aren't in {}, so can't be guessed; --goals-are-allowed adds them", missing, filename); }
+        },
+        _ => {
+            dict.extend(&goals);
+            dedup(&mut dict);
+        },
+    }
+    if !options.allow_any { options.words = Arc::new(dict.iter().copied().collect()); }
+    goals.retain(|&w| !options.goal_filters.iter().any(|f| f.excludes(w)));
+    if let Some(filename) = options.past_answers.as_ref().filter(|_| options.profile != Some(Profile::HelloWordl)) {
+        let past: HashSet<Word> = read_dict(filename, options.length, None).map_err(|e| list_error(filename, e))?.into_iter().collect();
+        goals.retain(|w| !past.contains(w));
+    }
+    // The original's daily answers go in the goal list's order.
+    let ordered = goals.clone();
+    if options.answers_anywhere { goals = dict.clone(); }
+
+    if options.shuffle {
+        let mut rng = options.seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+        goals.shuffle(&mut rng);
+        dict.shuffle(&mut rng);
+    }
+
+    // A progress bar would only clutter a benchmark's or a tree's many games,
+    // or stderr written to a log.
+    let progress_every = if ["bench", "tree", "hardest", "book"].contains(&command) || options.quiet || !io::stderr().is_terminal() { usize::MAX } else if options.compact { 1000 } else { 100 };
+    // Each game gets a strategy of its own, since sampled ones are seeded,
+    // so without the cache or the book.
+    if command == "simulate" {
+        let trials = args.get(1).map(|n| n.parse().ok().filter(|&n| n > 0).ok_or_else(|| usage_error("simulate takes a number of trials")))
+            .transpose()?.unwrap_or(10);
+        strategy(&options.strategy, &options, usize::MAX)?;
+        let make = |seed| -> Box<dyn Strategy> {
+            if options.strategy == "sampled" { return Box::new(Sampled::new(seed, options.priors.clone())) }
+            strategy(&options.strategy, &options, usize::MAX).unwrap()
+        };
+        let solver = start(goals, dict, &options)?;
+        let report = simulate(&solver, make, trials, options.guesses.max(PLAY_LIMIT), options.seed.unwrap_or(0));
+        print_simulation(&report, &options.strategy, options.guesses);
+        if options.show_failures { print_failures(&solver, &report.pooled(), options.guesses); }
+        if let Some(path) = &options.report {
+            let title = format!("{} trials over {} goals", report.trials.len(), report.trials[0].results.len());
+            write_report(path, &title, &[(&options.strategy, report.pooled())], options.guesses)?;
+        }
+        return Ok(());
+    }
+    let mut strategy = strategy(&options.strategy, &options, progress_every)?;
+    // Everything the choices depend on besides the lists, which the cache and
+    // the book key on separately: words are hashed as symbol numbers, and the
+    // frequency list is included whole. --prune is left out when unset so
+    // the shipped book's keys still match.
+    let frequencies = options.frequencies.as_deref().map(read_file).transpose()?;
+    let mut settings = format!("alphabet {:?} depth {} frequencies {:?}", alphabet().symbols(), options.depth, frequencies);
+    if let Some(prune) = options.prune { settings += &format!(" prune {}", prune); }
+    // With a budget, choices depend on how fast the search ran, so they
+    // aren't kept for runs that may have more time, and a stochastic
+    // strategy's are only one draw of many.
+    let keep = options.budget.is_none() && strategy.is_deterministic();
+    if let Some(path) = options.cache.as_ref().filter(|_| keep) { strategy = Box::new(Cached::new(strategy, &settings, path)); }
+    if command == "book" {
+        if !strategy.is_deterministic() { return Err(usage_error(&format!("can't write a book for the {} strategy, whose choices vary", strategy.name()))) }
+        let openers = args[2..].iter().map(|arg| {
+            let opener = goal_arg(arg, &options)?;
+            unknown_word(opener, &options).map_or(Ok(opener), |e| Err(Error::Input(e)))
+        }).collect::<Result<Vec<_>, _>>()?;
+        let solver = start(goals, dict, &options)?;
+        let added = write_book(&solver, strategy, &settings, &args[1], &openers, &options);
+        println!("wrote {} positions to {}", added, args[1]);
+        return Ok(());
+    }
+    if !options.no_book && strategy.is_deterministic() {
+        let text = match &options.book {
+            Some(path) => read_file(path)?,
+            None => BOOK.to_string(),
+        };
+        strategy = Box::new(Book::new(strategy, &settings, &text));
+    }
+    if let Some(filename) = &options.tree {
+        let tree: DecisionTree = read_file(filename)?.parse().map_err(|e| Error::Input(format!("{}: {}", filename, e)))?;
+        strategy = Box::new(Planned::new(&tree, &goals, strategy));
+    }
+    if command == "crowd" { return Ok(crowd(known_goal(goal_arg(&args[1], &options)?, &goals, &options)?, Solver::new(goals, dict), &options)?) }
+    if command == "analyze" {
+        let text = match args.get(1) {
+            Some(filename) => read_file(filename)?,
+            None => io::read_to_string(io::stdin())?,
+        };
+        analyze(&parse_board(&text, options.length), goals, dict, strategy.as_ref(), &options);
+        return Ok(());
+    }
+    if command == "review" {
+        let text = match args.get(1) {
+            Some(filename) => read_file(filename)?,
+            None => io::read_to_string(io::stdin())?,
+        };
+        let (solver, board) = (indexed(Solver::new(goals, dict), &options), parse_board(&text, options.length));
+        if options.markdown_summary {
+            print_markdown_summary(&solver, &board, None, strategy.as_ref(), &options);
+        } else {
+            print_review(&solver, &board, None, strategy.as_ref(), &options);
+        }
+        return Ok(());
+    }
+    if command == "import" {
+        let text = match args.get(1) {
+            Some(filename) => read_file(filename)?,
+            None => io::read_to_string(io::stdin())?,
+        };
+        let board = parse_share(&text, &options.history).map_err(Error::Input)?;
+        analyze(&board, goals, dict, strategy.as_ref(), &options);
+        return Ok(());
+    }
+    if command == "tree" { return tree(&indexed(Solver::new(goals, dict), &options), strategy.as_ref(), &options) }
+    if command == "openers" {
+        openers(&goals, &dict, &options);
+        return Ok(());
+    }
+    if command == "optimal" {
+        optimal(&goals, &dict, &options)?;
+        return Ok(());
+    }
+    if command == "best" {
+        let pattern = options.constraints.clone().unwrap_or_default();
+        recommend_once(&indexed(Solver::with_pattern(goals, dict, pattern), &options), strategy.as_ref(), &options);
+        return Ok(());
+    }
+    if command == "match" {
+        let template = &args[1];
+        if template.chars().count() != options.length {
+            return Err(usage_error(&format!("the template should have {} letters, as words do", options.length)))
+        }
+        let arg = |i: usize| args.get(i).map_or("", String::as_str);
+        let pattern = Pattern::from_template(template, arg(2), arg(3)).map_err(|e| usage_error(&e))?;
+        print_matches(&pattern, &goals, &dict, &options);
+        return Ok(());
+    }
+    if command == "burner" {
+        let weighted = match args.get(1).map(String::as_str) {
+            None => false,
+            Some("weighted") => true,
+            Some(other) => return Err(usage_error(&format!("unknown burner option {}", other))),
+        };
+        print_burner(&Solver::with_pattern(goals, dict, options.constraints.clone().unwrap_or_default()), weighted);
+        return Ok(());
+    }
+    if command == "play" {
+        let goal = known_goal(goal_arg(&args[1], &options)?, &goals, &options)?;
+        let mut solver = start(goals, dict, &options)?;
+        let solved = solver.play(goal, strategy.as_ref(), options.guesses);
+        if !solver.history().is_empty() { record(&options, "play", solved); }
+        if options.json {
+            for (i, &(guess, outcome)) in solver.history().iter().enumerate() {
+                print_json(&[("turn", (i + 1).to_string()), ("guess", json_str(&guess.to_string())), ("outcome", json_str(&outcome.letters()))]);
+            }
+            match solved {
+                Some(n) => print_solved(&options, n),
+                None if solver.history().is_empty() => print_error(&options, &format!("{} isn't a goal word", goal)),
+                None => print_lost(&solver, Some(goal), &options),
+            }
+            return Ok(());
+        }
+        for (i, (guess, outcome)) in solver.history().iter().enumerate() { println!("{} {} {}", i + 1, guess, outcome); }
+        match solved {
+            Some(n) => {
+                println!("solved {} in {} guesses", goal, n);
+                print_share(solver.history(), &options);
+            },
+            None if solver.history().is_empty() => println!("{} isn't a goal word", goal),
+            None => print_lost(&solver, Some(goal), &options),
+        }
+        return Ok(());
+    }
+    if command == "bench" {
+        let solver = start(goals, dict, &options)?;
+        let (before, began) = (Metrics::now(), Instant::now());
+        let mut reports = Vec::new();
+        if options.compare.is_empty() {
+            let report = bench(&solver, strategy.as_ref(), PLAY_LIMIT);
+            print_bench(&report, strategy.name(), options.guesses);
+            if options.show_failures { print_failures(&solver, &report, options.guesses); }
+            reports.push((strategy.name(), report));
+        } else {
+            for name in &options.compare {
+                let strategy = self::strategy(name, &options, progress_every)?;
+                reports.push((strategy.name(), bench(&solver, strategy.as_ref(), PLAY_LIMIT)));
+            }
+            print_comparison(&reports, options.guesses);
+        }
+        let metrics = Metrics::now().since(&before);
+        log_metrics(&options, vec![("command", json_str("bench")), ("goals", solver.candidates().len().to_string())], &metrics, began.elapsed());
+        if options.verbose {
+            print_metrics(&metrics);
+            print_table(&options);
+        }
+        if let Some(path) = &options.report {
+            write_report(path, &format!("bench over {} goals", solver.candidates().len()), &reports, options.guesses)?;
+        }
+        return Ok(());
+    }
+    if command == "verify" {
+        let games = args.get(1).map(|n| n.parse().map_err(|_| usage_error(&format!("bad number of games {:?}", n)))).transpose()?;
+        return verify(&start(goals, dict, &options)?, games.unwrap_or(1000), &options);
+    }
+    if command == "hardest" {
+        let solver = start(goals, dict, &options)?;
+        print_hardest(&solver, strategy.as_ref(), &options);
+        return Ok(());
+    }
+    if command == "absurdle" {
+        let mut solver = start(goals, dict, &options)?;
+        if args.get(1).map(String::as_str) == Some("auto") {
+            let solved = solver.play_against(|goals, guess| adversarial_outcome(guess, goals), strategy.as_ref(), PLAY_LIMIT);
+            for (i, (guess, outcome)) in solver.history().iter().enumerate() { println!("{} {} {}", i + 1, guess, outcome); }
+            match solved {
+                Some(n) => println!("{} strategy needs {} guesses against the adversary", strategy.name(), n),
+                None => println!("gave up after {} guesses", PLAY_LIMIT),
+            }
+            return Ok(());
+        }
+        return Ok(absurdle(solver, &options, strategy.as_ref())?);
+    }
+    if command == "stats" {
+        let path = options.stats.as_ref().ok_or_else(|| usage_error("no --stats file"))?;
+        let text = match std::fs::read_to_string(path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            text => text.map_err(|e| file_error(path, e))?,
+        };
+        print_stats(&text, &options);
+        return Ok(());
+    }
+    let day = |date: &str| parse_date(date).ok_or_else(|| usage_error(&format!("bad date {:?}, expected YYYY-MM-DD or today", date)));
+    if command == "host" {
+        let goal = match args.get(1) {
+            Some(date) => daily_goal(&mut goals, &ordered, day(date)?, &options)?,
+            None => {
+                let mut rng = options.seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+                *goals.choose(&mut rng).ok_or_else(|| Error::Input("there are no goals".to_string()))?
+            },
+        };
+        return Ok(host(goal, Solver::new(goals, dict), &options)?);
+    }
+    if command == "daily" {
+        let assisting = args[1..].first().is_some_and(|a| a == "assist");
+        let dates = &args[1 + assisting as usize..];
+        if dates.len() > 1 { return Err(usage_error("daily takes assist and a date, in that order")) }
+        let day = day(dates.first().map_or("today", String::as_str))?;
+        options.daily = Some(day);
+        if options.record != Some(false) && played_daily(&options, day) {
+            return Err(Error::Input(format!("the {} puzzle for {} is recorded as played already; --no-record plays it again without recording it",
+                                            options.variant.name, format_date(day))))
+        }
+        if !options.json { println!("{} puzzle for {}", options.variant.name, format_date(day)); }
+        if assisting {
+            if options.profile == Some(Profile::Original) { daily_goal(&mut goals, &ordered, day, &options)?; }
+            return Ok(assist(start(goals, dict, &options)?, &options, strategy.as_ref())?);
         }
-        let guess = Word::new(&buf[0..5]);
+        let goal = daily_goal(&mut goals, &ordered, day, &options)?;
+        return Ok(host(goal, Solver::new(goals, dict), &options)?);
+    }
+    if command == "assist" && options.boards.is_some_and(|n| n > 1) {
+        return Ok(assist_boards(MultiSolver::new(options.boards.unwrap(), goals, dict), options.length)?);
+    }
+    if command == "assist" && options.protocol { return Ok(protocol(start(goals, dict, &options)?, &options, strategy.as_ref())?) }
+    if command == "assist" { return Ok(assist(start(goals, dict, &options)?, &options, strategy.as_ref())?) }
+    let goal = parse_guess(command, options.length)
+        .ok_or_else(|| usage_error(&format!("{:?} is neither a command nor a {}-letter goal", command, options.length)))?;
+    let goal = known_goal(goal, &goals, &options)?;
+    let mut solver = start(goals, dict, &options)?;
+    for &guess in &options.history { solver.refine(guess, Outcome::compare(goal, guess)); }
+    let mut buf = String::new();
+    let stdin = io::stdin();
+    let initial = solver.clone();
+    'game: loop {
+        if options.hard { solver.retain_hard_mode_guesses(); }
+        if options.ultra_hard { solver.retain_matching_guesses(); }
+        print_guesses_left(&solver, &options);
+        let recommended_confidence = print_turn(&solver, &options, strategy.as_ref());
 
-        println!("guess matches pattern? {}", pattern.matches(guess));
+        let guess = loop {
+            buf.clear();
+            if stdin.read_line(&mut buf)? == 0 { return Ok(()) }
+            match run_command(&buf, &solver, &options, strategy.as_ref()) {
+                Command::Handled => continue,
+                Command::Quit => return Ok(()),
+                Command::Restart => { solver = initial.clone(); continue 'game },
+                Command::Guess => {},
+            }
+            let guess = match parse_guess(buf.trim(), options.length) {
+                Some(guess) => guess,
+                None => { print_error(&options, &not_a_guess(&buf, options.length)); continue },
+            };
+            if let Some(message) = unknown_word(guess, &options).or_else(|| broken_rule(guess, &solver, &options)) {
+                print_error(&options, &message);
+                continue;
+            }
+            break guess;
+        };
 
         let outcome = Outcome::compare(goal, guess);
-        println!("outcome is {}", outcome);
-        pattern.refine(guess, outcome);
+        if options.json {
+            let mut fields = vec![("guess", json_str(&guess.to_string())), ("outcome", json_str(&outcome.letters()))];
+            if let Some(best) = recommended_confidence {
+                let confidence = solver.confidence(guess);
+                fields.push(("grade", json_str(&grade(confidence, best).to_string())));
+                fields.push(("worst_case", (-confidence).to_string()));
+            }
+            fields.extend(guess_scores(&solver, guess));
+            print_json(&fields);
+        } else {
+            if let Some(best) = recommended_confidence.filter(|_| !options.quiet) {
+                let confidence = solver.confidence(guess);
+                if options.compact {
+                    println!("grade {} (<= {} left, best {})", grade(confidence, best), -confidence, -best);
+                } else {
+                    println!("grade {} (at most {} possible words, best {})", grade(confidence, best), -confidence, -best);
+                }
+            }
+            if options.quiet {
+                println!("outcome is {}", outcome);
+            } else if !options.compact {
+                println!("guess matches pattern? {}", solver.pattern().matches(guess));
+                print_guess_score(&solver, guess);
+                println!("outcome is {}", outcome);
+            }
+        }
+        solver.refine(guess, outcome);
+        let over = outcome.is_win() || solver.history().len() >= options.guesses;
+        if over { record(&options, "goal", Some(solver.history().len()).filter(|_| outcome.is_win())); }
+        if outcome.is_win() {
+            print_solved(&options, solver.history().len());
+            print_share(solver.history(), &options);
+        } else if over {
+            print_lost(&solver, Some(goal), &options);
+        }
+        if over {
+            let history = &solver.history()[initial.history().len()..];
+            if options.review { print_review(&initial, history, Some(goal), strategy.as_ref(), &options); }
+            if options.markdown_summary { print_markdown_summary(&initial, history, Some(goal), strategy.as_ref(), &options); }
+            return Ok(());
+        }
     }
 }
@@ -0,0 +1,185 @@
+use crate::metrics::{self, Counter};
+use crate::outcome::Outcome;
+use crate::pattern::Pattern;
+use crate::solver::{cutoff_order, score_all, score_guesses, SearchLimits};
+use crate::word::Word;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// [`Outcome::code`] for every guess against every goal, computed once.
+///
+/// Rows are guesses and columns goals, both addressed by index. Goals are
+/// also guesses, so every goal has a row; see [`OutcomeMatrix::goal_row`].
+#[derive(Debug, Clone)]
+pub struct OutcomeMatrix {
+    goals: Vec<Word>,
+    guesses: Vec<Word>,
+    rows: HashMap<Word, u32>,
+    codes: Vec<u16>,
+    states: usize,
+}
+
+impl OutcomeMatrix {
+    /// Scores each of `dict` and `goals` against each of `goals`, using all
+    /// available cores.
+    pub fn new(goals: &[Word], dict: &[Word]) -> Self {
+        let mut guesses = Vec::new();
+        let mut rows = HashMap::new();
+        for &w in dict.iter().chain(goals) {
+            rows.entry(w).or_insert_with(|| {
+                guesses.push(w);
+                guesses.len() as u32 - 1
+            });
+        }
+        let scored = score_all(&guesses, usize::MAX, |guess| {
+            goals.iter().map(|&goal| Outcome::compare(goal, guess).code()).collect::<Vec<u16>>()
+        });
+        let codes = scored.into_iter().flat_map(|(_, row)| row).collect();
+        let states = Outcome::states(goals.first().map_or(0, Word::len));
+        Self { goals: goals.to_vec(), guesses, rows, codes, states }
+    }
+
+    /// The goals, in column order.
+    pub fn goals(&self) -> &[Word] {
+        &self.goals
+    }
+
+    /// The guesses, in row order.
+    pub fn guesses(&self) -> &[Word] {
+        &self.guesses
+    }
+
+    /// The row for `guess`, if it was in either list.
+    pub fn row(&self, guess: Word) -> Option<usize> {
+        self.rows.get(&guess).map(|&r| r as usize)
+    }
+
+    /// The row for the goal in column `goal`.
+    pub fn goal_row(&self, goal: usize) -> usize {
+        self.rows[&self.goals[goal]] as usize
+    }
+
+    /// How many outcome codes there are, one more than the highest.
+    pub fn states(&self) -> usize {
+        self.states
+    }
+
+    /// The outcome code for guess `row` against goal `goal`.
+    pub fn code(&self, row: usize, goal: usize) -> u16 {
+        self.codes[row * self.goals.len() + goal]
+    }
+
+    /// How many of the `candidates` goals give each outcome for guess `row`.
+    pub fn outcome_counts(&self, row: usize, candidates: &[u32]) -> Vec<u32> {
+        let codes = &self.codes[row * self.goals.len()..][..self.goals.len()];
+        let mut counts = vec![0; self.states];
+        for &c in candidates { counts[codes[c as usize] as usize] += 1; }
+        counts
+    }
+
+    /// Size of the largest set of `candidates` sharing one outcome for guess
+    /// `row`, like [`worst_bucket`](crate::worst_bucket).
+    pub fn worst_bucket(&self, row: usize, candidates: &[u32]) -> usize {
+        *self.outcome_counts(row, candidates).iter().max().unwrap() as usize
+    }
+
+    /// Like [`worst_bucket`](OutcomeMatrix::worst_bucket), but gives up once
+    /// a set has more than `bound` goals, returning its size then. Goals are
+    /// counted in the order `candidates` lists them.
+    pub fn worst_bucket_within(&self, row: usize, candidates: &[u32], bound: usize) -> usize {
+        let codes = &self.codes[row * self.goals.len()..][..self.goals.len()];
+        let mut counts = vec![0; self.states];
+        let mut worst = 0;
+        for &c in candidates {
+            let count = &mut counts[codes[c as usize] as usize];
+            *count += 1;
+            worst = worst.max(*count);
+            if worst > bound {
+                metrics::count(Counter::GuessesCutOff, 1);
+                break;
+            }
+        }
+        worst
+    }
+
+    /// [`recommended_guess`](crate::recommended_guess) for the `candidates`
+    /// goals, by table lookup: the row of `rows` with the best worst case and
+    /// its negated worst bucket, preferring a candidate within one word of it
+    /// and then the alphabetically first guess, searching within `limits`.
+    pub fn minimax(&self, candidates: &[u32], rows: &[u32], progress_every: usize, limits: SearchLimits) -> (usize, i32) {
+        let deadline = limits.budget.map(|b| Instant::now() + b);
+        let goals: Vec<Word> = candidates.iter().map(|&c| self.goals[c as usize]).collect();
+        let counted: Vec<u32> = cutoff_order(&goals).into_iter().map(|i| candidates[i]).collect();
+        // Guesses with a worse worst case than `bound` are cut off; see
+        // limited_guess.
+        let best_from = |rows: &[u32], bound: usize| -> (usize, i32) {
+            let bound = AtomicUsize::new(bound);
+            let (row, confidence) = score_guesses(rows, progress_every, deadline, |row| self.guesses[row as usize], |row| {
+                let worst = self.worst_bucket_within(row as usize, &counted, bound.load(Ordering::Relaxed));
+                bound.fetch_min(worst, Ordering::Relaxed);
+                -(worst as i32)
+            }).into_iter().max_by_key(|&(row, c)| (c, Reverse(self.guesses[row as usize]))).unwrap();
+            (row as usize, confidence)
+        };
+        let goal_rows: Vec<u32> = candidates.iter().map(|&c| self.goal_row(c as usize) as u32).collect();
+        let (bg, bgc) = best_from(&goal_rows, usize::MAX);
+        let bound = (-bgc as usize).saturating_sub(2);
+        let (bd, bdc) = if limits == SearchLimits::default() {
+            best_from(rows, bound)
+        } else {
+            let guesses: Vec<Word> = rows.iter().map(|&r| self.guesses[r as usize]).collect();
+            let ordered: Vec<u32> = limits.order(&Pattern::new(), &goals, &guesses).into_iter().map(|w| self.rows[&w]).collect();
+            best_from(&ordered, bound)
+        };
+        if bgc+1 >= bdc { (bg, bgc) } else { (bd, bdc) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+    use crate::verify::verify_game;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn lists() -> (Vec<Word>, Vec<Word>) {
+        let words = |text: &str| text.lines().step_by(7).take(300).map(Word::new).collect::<Vec<_>>();
+        let goals = words(include_str!("../goals.txt"));
+        let dict = goals.iter().copied().chain(words(include_str!("../extra.txt"))).collect();
+        (goals, dict)
+    }
+
+    #[test]
+    fn codes_agree_with_compare() {
+        let (goals, dict) = lists();
+        let matrix = OutcomeMatrix::new(&goals, &dict);
+        let all: Vec<u32> = (0..goals.len() as u32).collect();
+        for &guess in &dict {
+            let row = matrix.row(guess).unwrap();
+            let mut counts = vec![0; matrix.states()];
+            for (i, &goal) in goals.iter().enumerate() {
+                let code = Outcome::compare(goal, guess).code();
+                assert_eq!(matrix.code(row, i), code, "{} against {}", guess, goal);
+                counts[code as usize] += 1;
+            }
+            assert_eq!(matrix.outcome_counts(row, &all), counts);
+            assert_eq!(matrix.worst_bucket(row, &all), counts.iter().copied().max().unwrap() as usize);
+        }
+    }
+
+    #[test]
+    fn matrix_filtering_agrees_with_compare() {
+        let (goals, dict) = lists();
+        let solver = Solver::new(goals.clone(), dict.clone()).with_matrix();
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..200 {
+            let goal = goals[rng.gen_range(0..goals.len())];
+            // Half goals, so letters repeat and tiles aren't all gray.
+            let guesses: Vec<Word> = (0..6).map(|i| if i % 2 == 0 { goals[rng.gen_range(0..goals.len())] } else { dict[rng.gen_range(0..dict.len())] }).collect();
+            if let Err(divergence) = verify_game(&solver, goal, &guesses) { panic!("{}", divergence) }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Display;
+
+// The messages of a game's turns, which assist and the other games print,
+// each with a {} for every value filled in, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    PatternIs,
+    MatchingGoals,
+    GoalsLeft,
+    AnswerMustBe,
+    Recommended,
+    RecommendedAtMost,
+    Try,
+    TryAtMost,
+    Solved,
+    LostAnswer,
+    LostOneOf,
+    LostLeft,
+    LastGuess,
+    GuessesLeft,
+    NotInList,
+    BreaksHard,
+    BreaksUltraHard,
+    ExpectedGuess,
+    NothingToUndo,
+}
+
+// A language's messages, by its ISO 639-1 code. English has every message;
+// other languages fall back to it for any they lack.
+#[derive(Debug, Clone, Copy)]
+pub struct Catalog {
+    pub lang: &'static str,
+    messages: &'static [(Message, &'static str)],
+}
+
+const ENGLISH: Catalog = Catalog { lang: "en", messages: &[
+    (Message::PatternIs, "pattern is {}"),
+    (Message::MatchingGoals, "  {} matching goal words"),
+    (Message::GoalsLeft, "{} goals left"),
+    (Message::AnswerMustBe, "the answer must be {}"),
+    (Message::Recommended, "recommended guess is {} ({} strategy)"),
+    (Message::RecommendedAtMost, "recommended guess is {} (at most {} possible words)"),
+    (Message::Try, "try {} ({} strategy)"),
+    (Message::TryAtMost, "try {} (<= {} left)"),
+    (Message::Solved, "solved in {} guesses"),
+    (Message::LostAnswer, "out of guesses; the answer was {}"),
+    (Message::LostOneOf, "out of guesses; it was one of {}"),
+    (Message::LostLeft, "out of guesses, with {} goal words left"),
+    (Message::LastGuess, "last guess!"),
+    (Message::GuessesLeft, "{} guesses left"),
+    (Message::NotInList, "{} isn't in the word list; --allow-any accepts it anyway"),
+    (Message::BreaksHard, "{} breaks hard mode rules"),
+    (Message::BreaksUltraHard, "{} breaks ultra-hard rules"),
+    (Message::ExpectedGuess, "expected a guess and its tiles, e.g. \"crane gybgg\", or a command; try help"),
+    (Message::NothingToUndo, "no guesses have been entered to take back"),
+] };
+
+const SPANISH: Catalog = Catalog { lang: "es", messages: &[
+    (Message::PatternIs, "el patrón es {}"),
+    (Message::MatchingGoals, "  {} palabras objetivo encajan"),
+    (Message::GoalsLeft, "quedan {} palabras"),
+    (Message::AnswerMustBe, "la respuesta tiene que ser {}"),
+    (Message::Recommended, "se recomienda {} (estrategia {})"),
+    (Message::RecommendedAtMost, "se recomienda {} (como mucho {} palabras posibles)"),
+    (Message::Try, "prueba {} (estrategia {})"),
+    (Message::TryAtMost, "prueba {} (quedan <= {})"),
+    (Message::Solved, "resuelto en {} intentos"),
+    (Message::LostAnswer, "sin intentos; la respuesta era {}"),
+    (Message::LostOneOf, "sin intentos; era una de {}"),
+    (Message::LostLeft, "sin intentos; quedaban {} palabras objetivo"),
+    (Message::LastGuess, "¡último intento!"),
+    (Message::GuessesLeft, "quedan {} intentos"),
+    (Message::NotInList, "{} no está en la lista de palabras; --allow-any la acepta igualmente"),
+    (Message::BreaksHard, "{} rompe las reglas del modo difícil"),
+    (Message::BreaksUltraHard, "{} rompe las reglas del modo ultradifícil"),
+    (Message::ExpectedGuess, "se esperaba un intento y sus casillas, p. ej. \"crane gybgg\", o un comando; prueba help"),
+    (Message::NothingToUndo, "no se ha introducido ningún intento que deshacer"),
+] };
+
+pub const CATALOGS: [Catalog; 2] = [ENGLISH, SPANISH];
+
+impl Default for Catalog {
+    fn default() -> Self {
+        ENGLISH
+    }
+}
+
+impl Catalog {
+    // The catalog for the language `lang` is the code of, if there is one.
+    pub fn find(lang: &str) -> Option<Self> {
+        CATALOGS.iter().copied().find(|c| c.lang == lang)
+    }
+
+    // `message` in this language, with `values` in place of its {}s.
+    pub fn text(&self, message: Message, values: &[&dyn Display]) -> String {
+        let find = |catalog: &Catalog| catalog.messages.iter().find(|m| m.0 == message).map(|m| m.1);
+        let template = find(self).or_else(|| find(&ENGLISH)).expect("English has every message");
+        let mut parts = template.split("{}");
+        let mut text = parts.next().unwrap().to_string();
+        for (part, value) in parts.zip(values.iter().chain(std::iter::repeat(&(&"" as &dyn Display)))) {
+            text += &value.to_string();
+            text += part;
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_messages() {
+        let spanish = Catalog::find("es").unwrap();
+        assert_eq!(spanish.text(Message::TryAtMost, &[&"crane", &12]), "prueba crane (quedan <= 12)");
+        assert_eq!(Catalog::default().text(Message::LastGuess, &[]), "last guess!");
+        assert!(Catalog::find("xx").is_none());
+    }
+
+    #[test]
+    fn every_language_has_every_message() {
+        for catalog in CATALOGS {
+            for (message, template) in catalog.messages {
+                let english = ENGLISH.messages.iter().find(|m| m.0 == *message).unwrap().1;
+                assert_eq!(template.matches("{}").count(), english.matches("{}").count(), "{:?} in {}", message, catalog.lang);
+            }
+            assert_eq!(catalog.messages.len(), ENGLISH.messages.len(), "{}", catalog.lang);
+        }
+    }
+}
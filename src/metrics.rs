@@ -0,0 +1,95 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts of the work searches have done in this process so far, from every
+/// thread, to see what pruning and caching save. Take one with
+/// [`now`](Metrics::now) before and after a search and subtract them with
+/// [`since`](Metrics::since) for what that search did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Guesses minimax searches scored, and how many of those were cut off
+    /// part way, once they couldn't beat the best guess so far.
+    pub guesses_scored: usize,
+    pub guesses_cut_off: usize,
+
+    /// Lookups in [`TranspositionTable`](crate::TranspositionTable)s, and
+    /// how many found a result.
+    pub table_lookups: usize,
+    pub table_hits: usize,
+
+    /// Lookups in [`Cached`](crate::Cached) strategies and
+    /// [`Book`](crate::Book)s, and how many found a guess.
+    pub cache_lookups: usize,
+    pub cache_hits: usize,
+
+    /// Positions those found no guess for and left to [`Quick`](crate::Quick),
+    /// having too many candidates for the strategy.
+    pub quick_fallbacks: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Counter {
+    GuessesScored,
+    GuessesCutOff,
+    TableLookups,
+    TableHits,
+    CacheLookups,
+    CacheHits,
+    QuickFallbacks,
+}
+
+static COUNTERS: [AtomicUsize; 7] = [
+    AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+
+// Adds `n` to a counter.
+pub(crate) fn count(counter: Counter, n: usize) {
+    if n > 0 { COUNTERS[counter as usize].fetch_add(n, Ordering::Relaxed); }
+}
+
+impl Metrics {
+    /// The counts so far.
+    pub fn now() -> Self {
+        let get = |counter: Counter| COUNTERS[counter as usize].load(Ordering::Relaxed);
+        Self {
+            guesses_scored: get(Counter::GuessesScored),
+            guesses_cut_off: get(Counter::GuessesCutOff),
+            table_lookups: get(Counter::TableLookups),
+            table_hits: get(Counter::TableHits),
+            cache_lookups: get(Counter::CacheLookups),
+            cache_hits: get(Counter::CacheHits),
+            quick_fallbacks: get(Counter::QuickFallbacks),
+        }
+    }
+
+    /// What was counted between `earlier` and these counts.
+    pub fn since(&self, earlier: &Metrics) -> Self {
+        Self {
+            guesses_scored: self.guesses_scored - earlier.guesses_scored,
+            guesses_cut_off: self.guesses_cut_off - earlier.guesses_cut_off,
+            table_lookups: self.table_lookups - earlier.table_lookups,
+            table_hits: self.table_hits - earlier.table_hits,
+            cache_lookups: self.cache_lookups - earlier.cache_lookups,
+            cache_hits: self.cache_hits - earlier.cache_hits,
+            quick_fallbacks: self.quick_fallbacks - earlier.quick_fallbacks,
+        }
+    }
+
+    /// The share of scored guesses cut off, of table lookups that hit and
+    /// of cache lookups that hit, each 0 with nothing to count.
+    pub fn cut_off_rate(&self) -> f64 {
+        rate(self.guesses_cut_off, self.guesses_scored)
+    }
+
+    pub fn table_hit_rate(&self) -> f64 {
+        rate(self.table_hits, self.table_lookups)
+    }
+
+    pub fn cache_hit_rate(&self) -> f64 {
+        rate(self.cache_hits, self.cache_lookups)
+    }
+}
+
+fn rate(part: usize, whole: usize) -> f64 {
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 }
+}
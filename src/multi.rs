@@ -0,0 +1,57 @@
+use crate::analysis::outcome_entropy;
+use crate::outcome::Outcome;
+use crate::solver::Solver;
+use crate::strategy::best_by;
+use crate::word::Word;
+
+/// Several boards played with the same guesses, as in Dordle, Quordle and
+/// Octordle: one [`Solver`] per board, each with its own answer.
+#[derive(Debug, Clone)]
+pub struct MultiSolver {
+    boards: Vec<Solver>,
+    solved: Vec<bool>,
+}
+
+impl MultiSolver {
+    /// Starts `n` boards whose answers are each one of `goals`.
+    pub fn new(n: usize, goals: Vec<Word>, dict: Vec<Word>) -> Self {
+        Self { boards: vec![Solver::new(goals, dict); n], solved: vec![false; n] }
+    }
+
+    /// Each board's game so far.
+    pub fn boards(&self) -> &[Solver] {
+        &self.boards
+    }
+
+    /// Whether board `i` has been won.
+    pub fn is_solved(&self, i: usize) -> bool {
+        self.solved[i]
+    }
+
+    /// Whether every board has been won.
+    pub fn all_solved(&self) -> bool {
+        self.solved.iter().all(|&s| s)
+    }
+
+    /// Records a guess's outcome on each board, in order. Outcomes for boards
+    /// already won are ignored.
+    pub fn refine(&mut self, guess: Word, outcomes: &[Outcome]) {
+        for ((board, solved), &outcome) in self.boards.iter_mut().zip(&mut self.solved).zip(outcomes) {
+            if *solved { continue }
+            board.refine(guess, outcome);
+            *solved = outcome.is_win();
+        }
+    }
+
+    /// The guess making the most combined progress: the answer of any board
+    /// that's down to one candidate, and otherwise the guess with the most
+    /// information summed over the open boards. `None` once no board is open.
+    pub fn choose(&self) -> Option<Word> {
+        let open: Vec<&Solver> = self.boards.iter().zip(&self.solved)
+            .filter(|&(b, &solved)| !solved && !b.candidates().is_empty()).map(|(b, _)| b).collect();
+        if open.is_empty() { return None }
+        if let Some(board) = open.iter().find(|b| b.candidates().len() == 1) { return Some(board.candidates()[0]) }
+        let candidates: Vec<Word> = open.iter().flat_map(|b| b.candidates()).copied().collect();
+        Some(best_by(&candidates, open[0].dict(), |g| open.iter().map(|b| outcome_entropy(g, b.candidates())).sum()))
+    }
+}
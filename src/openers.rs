@@ -0,0 +1,85 @@
+use crate::matrix::OutcomeMatrix;
+use crate::solver::score_all;
+use crate::word::Word;
+
+/// How well a fixed opening, played whatever tiles it gets, splits the goals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpeningSplit {
+    /// Goals left on average, if the answer is any goal equally likely.
+    pub expected: f64,
+
+    /// The most goals any combination of tiles leaves.
+    pub worst: usize,
+
+    /// How many combinations of tiles some goal gives.
+    pub outcomes: usize,
+}
+
+impl OpeningSplit {
+    // How guess `row` splits each of the `buckets` of goals the guesses
+    // before it left, `goals` in all.
+    fn new(matrix: &OutcomeMatrix, row: usize, buckets: &[Vec<u32>], goals: usize) -> Self {
+        let (mut squares, mut worst, mut outcomes) = (0, 0, 0);
+        // Counts for one bucket at a time, cleared after it, which is cheaper
+        // than counting every combination of tiles at once.
+        let mut counts = vec![0; matrix.states()];
+        for bucket in buckets {
+            for &goal in bucket {
+                let count = &mut counts[matrix.code(row, goal as usize) as usize];
+                if *count == 0 { outcomes += 1; }
+                *count += 1;
+            }
+            // Each goal adds the size of its set, so the squares add up.
+            for &goal in bucket {
+                let count = &mut counts[matrix.code(row, goal as usize) as usize];
+                squares += *count;
+                worst = worst.max(*count);
+            }
+            for &goal in bucket { counts[matrix.code(row, goal as usize) as usize] = 0; }
+        }
+        Self { expected: squares as f64 / goals.max(1) as f64, worst, outcomes }
+    }
+
+    // Best first: fewest goals left on average, then in the worst case.
+    fn rank(&self) -> (f64, usize) {
+        (self.expected, self.worst)
+    }
+}
+
+/// The `n` best first guesses of `matrix`, by the goals they leave on
+/// average, then at worst.
+pub fn best_openers(matrix: &OutcomeMatrix, n: usize) -> Vec<(Word, OpeningSplit)> {
+    let goals = matrix.goals().len();
+    let all = [(0..goals as u32).collect()];
+    let rows: Vec<usize> = (0..matrix.guesses().len()).collect();
+    let mut splits: Vec<(Word, OpeningSplit)> = score_all(&rows, usize::MAX, |row| OpeningSplit::new(matrix, row, &all, goals))
+        .into_iter().map(|(row, split)| (matrix.guesses()[row], split)).collect();
+    sort(&mut splits, n);
+    splits
+}
+
+/// The `n` best fixed pairs of first and second guesses, ranked like
+/// [`best_openers`] by the goals both leave together. Only the `breadth` best
+/// first guesses are tried, each with every second guess.
+pub fn best_pairs(matrix: &OutcomeMatrix, breadth: usize, n: usize) -> Vec<([Word; 2], OpeningSplit)> {
+    let goals = matrix.goals().len();
+    let mut pairs = Vec::new();
+    for (first, _) in best_openers(matrix, breadth) {
+        let first_row = matrix.row(first).unwrap();
+        // The goals giving each outcome for the first guess.
+        let mut buckets: Vec<Vec<u32>> = vec![Vec::new(); matrix.states()];
+        for goal in 0..goals as u32 { buckets[matrix.code(first_row, goal as usize) as usize].push(goal); }
+        buckets.retain(|b| !b.is_empty());
+        let rows: Vec<usize> = (0..matrix.guesses().len()).filter(|&row| row != first_row).collect();
+        let scored = score_all(&rows, usize::MAX, |row| OpeningSplit::new(matrix, row, &buckets, goals));
+        pairs.extend(scored.into_iter().map(|(row, split)| ([first, matrix.guesses()[row]], split)));
+        sort(&mut pairs, n);
+    }
+    pairs
+}
+
+// Keeps the `n` best, best first, ties in word order.
+fn sort<T: PartialOrd>(splits: &mut Vec<(T, OpeningSplit)>, n: usize) {
+    splits.sort_by(|a, b| a.1.rank().partial_cmp(&b.1.rank()).unwrap().then(a.0.partial_cmp(&b.0).unwrap()));
+    splits.truncate(n);
+}
@@ -0,0 +1,328 @@
+use crate::analysis::outcome_counts;
+use crate::outcome::Outcome;
+use crate::transposition::TranspositionTable;
+use crate::word::Word;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What an optimal decision tree minimizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Total guesses over all goals, i.e. the mean solve length.
+    Total,
+    /// The most guesses any goal needs.
+    WorstCase,
+}
+
+impl FromStr for Objective {
+    type Err = String;
+
+    /// Reads "total" or "worst".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "total" => Ok(Objective::Total),
+            "worst" => Ok(Objective::WorstCase),
+            _ => Err(format!("unknown objective {:?}, expected total or worst", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Bound {
+    Exact(u32, Word),
+    AtLeast(u32),
+}
+
+/// Exhaustive branch-and-bound search for a decision tree minimizing an
+/// [`Objective`], memoized on candidate sets.
+///
+/// The first guesses are tried in parallel, each thread taking the next
+/// untried one and sharing the best tree found so far and a
+/// [`TranspositionTable`]. Over the full word lists this can still take a very
+/// long time; see [`OptimalSearch::with_breadth`] for a faster approximation,
+/// and [`OptimalSearch::with_checkpoint`] to resume.
+#[derive(Debug)]
+pub struct OptimalSearch<'a> {
+    dict: &'a [Word],
+    objective: Objective,
+    breadth: Option<usize>,
+    memo: HashMap<Vec<Word>, Bound>,
+    table: Option<(Arc<TranspositionTable>, u64)>,
+    nodes: u64,
+    threads: usize,
+    checkpoint: Option<(String, Duration)>,
+    progress: bool,
+}
+
+impl<'a> OptimalSearch<'a> {
+    /// Searches over guesses from `dict`, with a thread per core.
+    pub fn new(dict: &'a [Word], objective: Objective) -> Self {
+        Self {
+            dict, objective, breadth: None, memo: HashMap::new(), table: None, nodes: 0,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()), checkpoint: None, progress: false,
+        }
+    }
+
+    /// Searches with `threads` threads instead.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Saves the table to `path` every `every` while searching, and at the
+    /// end, so a search stopped partway can pick up where it left off by
+    /// loading it with [`TranspositionTable::load`]. Needs a table, so adds
+    /// one if there isn't one.
+    pub fn with_checkpoint(mut self, path: &str, every: Duration) -> Self {
+        if self.table.is_none() { self = self.with_table(Arc::default()); }
+        self.checkpoint = Some((path.to_string(), every));
+        self
+    }
+
+    /// Reports on stderr as each first guess is done.
+    pub fn with_progress(mut self) -> Self {
+        self.progress = true;
+        self
+    }
+
+    /// Only tries the `breadth` most promising guesses at each node, ranked
+    /// by expected candidates remaining. Much faster, but no longer provably
+    /// optimal.
+    pub fn with_breadth(mut self, breadth: usize) -> Self {
+        self.breadth = Some(breadth);
+        self
+    }
+
+    /// Also keeps the best trees found in `table`, and looks them up there,
+    /// so searches sharing it don't repeat each other.
+    pub fn with_table(mut self, table: Arc<TranspositionTable>) -> Self {
+        self.table = Some((table, TranspositionTable::hash(self.dict)));
+        self
+    }
+
+    /// The best first guess for `goals` and the objective's value for the
+    /// whole tree it starts. Panics if there are no goals.
+    pub fn solve(&mut self, goals: &[Word]) -> (Word, u32) {
+        let mut goals = goals.to_vec();
+        goals.sort();
+        goals.dedup();
+        assert!(!goals.is_empty());
+        if goals.len() == 1 { return (goals[0], 1) }
+        if let Some((guess, cost)) = self.key(&goals).and_then(|key| self.table.as_ref()?.0.get(key)) { return (guess, cost as u32) }
+        let guesses = self.guesses(&goals);
+        // The best tree so far, as its cost and the index of its guess:
+        // trees costing the same are searched too, so the first of them in
+        // order wins however the threads go.
+        let best: Mutex<Option<(u32, usize)>> = Mutex::new(None);
+        let (next, done) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let last_saved = Mutex::new(Instant::now());
+        let (goals, guesses, best, next, done, last_saved) = (&goals, &guesses, &best, &next, &done, &last_saved);
+        let threads = self.threads.min(guesses.len());
+        let nodes: u64 = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads).map(|_| {
+                let mut search = OptimalSearch {
+                    dict: self.dict, objective: self.objective, breadth: self.breadth, memo: HashMap::new(),
+                    table: self.table.clone(), nodes: 0, threads: 1, checkpoint: None, progress: false,
+                };
+                let (checkpoint, progress) = (&self.checkpoint, self.progress);
+                scope.spawn(move || {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= guesses.len() { break }
+                        let bound = best.lock().unwrap().map_or(u32::MAX, |(cost, _)| cost.saturating_add(1));
+                        if let Some(cost) = search.evaluate(goals, guesses[i], bound) {
+                            let mut best = best.lock().unwrap();
+                            if best.is_none_or(|b| (cost, i) < b) { *best = Some((cost, i)); }
+                        }
+                        let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                        if progress {
+                            let best = best.lock().unwrap().map_or("none yet".to_string(), |(cost, i)| format!("{} costing {}", guesses[i], cost));
+                            eprintln!("tried {} of {} first guesses, best {}", done, guesses.len(), best);
+                        }
+                        if let (Some((path, every)), Some((table, _))) = (checkpoint, &search.table) {
+                            let mut last_saved = last_saved.lock().unwrap();
+                            if last_saved.elapsed() >= *every {
+                                if let Err(e) = table.save(path) { eprintln!("couldn't save the search to {}: {}", path, e); }
+                                *last_saved = Instant::now();
+                            }
+                        }
+                    }
+                    search.nodes
+                })
+            }).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+        self.nodes += nodes + 1;
+        let (cost, i) = best.lock().unwrap().expect("an unbounded search always finds a tree");
+        if let (Some(key), Some((table, _))) = (self.key(goals), &self.table) { table.insert(key, (guesses[i], cost as i32)); }
+        if let (Some((path, _)), Some((table, _))) = (&self.checkpoint, &self.table) {
+            if let Err(e) = table.save(path) { eprintln!("couldn't save the search to {}: {}", path, e); }
+        }
+        (guesses[i], cost)
+    }
+
+    /// Number of distinct candidate sets searched so far.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    fn lower_bound(&self, n: usize) -> u32 {
+        match self.objective {
+            // At best the first guess is right for one goal and splits the
+            // rest into singletons solved on the second.
+            Objective::Total => 2 * n as u32 - 1,
+            Objective::WorstCase => if n == 1 { 1 } else { 2 },
+        }
+    }
+
+    // The table's key for a candidate set. Only exact trees are kept there,
+    // so any bound can use them.
+    fn key(&self, goals: &[Word]) -> Option<u64> {
+        let (_, dict) = self.table.as_ref()?;
+        Some(TranspositionTable::key_hashed(&format!("optimal {:?} breadth {:?}", self.objective, self.breadth), goals, *dict))
+    }
+
+    // The best tree for a sorted candidate set, if it costs less than `bound`.
+    fn search(&mut self, goals: &[Word], bound: u32) -> Option<(u32, Word)> {
+        if goals.len() == 1 { return (1 < bound).then_some((1, goals[0])) }
+        if self.lower_bound(goals.len()) >= bound { return None }
+        let key = self.key(goals);
+        if let Some((guess, cost)) = key.and_then(|key| self.table.as_ref()?.0.get(key)) {
+            return ((cost as u32) < bound).then_some((cost as u32, guess));
+        }
+        match self.memo.get(goals) {
+            Some(&Bound::Exact(cost, guess)) => return (cost < bound).then_some((cost, guess)),
+            Some(&Bound::AtLeast(cost)) if cost >= bound => return None,
+            _ => {},
+        }
+        self.nodes += 1;
+
+        // Every guess is evaluated, each cut off once it can't beat the best
+        // so far, so whatever is found is exact.
+        let mut best = None;
+        let mut limit = bound;
+        for guess in self.guesses(goals) {
+            if let Some(cost) = self.evaluate(goals, guess, limit) {
+                best = Some((cost, guess));
+                limit = cost;
+            }
+        }
+        if let (Some(key), Some((table, _)), Some((cost, guess))) = (key, &self.table, best) { table.insert(key, (guess, cost as i32)); }
+        self.memo.insert(goals.to_vec(), match best {
+            Some((cost, guess)) => Bound::Exact(cost, guess),
+            None => Bound::AtLeast(bound),
+        });
+        best
+    }
+
+    // Guesses that split the candidates, most promising first.
+    fn guesses(&self, goals: &[Word]) -> Vec<Word> {
+        let mut ranked: Vec<(u64, bool, Word)> = self.dict.iter().chain(goals).filter_map(|&guess| {
+            let counts = outcome_counts(guess, goals);
+            // Winning has the highest code.
+            let candidate = *counts.last().unwrap() > 0;
+            if counts.iter().any(|&c| c as usize == goals.len()) && !candidate { return None }
+            Some((counts.iter().map(|&c| c as u64 * c as u64).sum(), !candidate, guess))
+        }).collect();
+        ranked.sort();
+        ranked.dedup_by_key(|r| r.2);
+        if let Some(breadth) = self.breadth { ranked.truncate(breadth); }
+        ranked.into_iter().map(|r| r.2).collect()
+    }
+
+    // The cost of the tree starting with `guess`, if it's less than `bound`.
+    fn evaluate(&mut self, goals: &[Word], guess: Word, bound: u32) -> Option<u32> {
+        let mut buckets: Vec<Vec<Word>> = vec![Vec::new(); Outcome::states(guess.len())];
+        for &goal in goals { buckets[Outcome::compare(goal, guess).code() as usize].push(goal); }
+        // Winning, the highest code, needs no more guesses.
+        buckets.pop();
+        buckets.retain(|b| !b.is_empty());
+        buckets.sort_by_key(|b| std::cmp::Reverse(b.len()));
+        match self.objective {
+            Objective::Total => {
+                let mut cost = goals.len() as u32 + buckets.iter().map(|b| self.lower_bound(b.len())).sum::<u32>();
+                if cost >= bound { return None }
+                for bucket in &buckets {
+                    let lower = self.lower_bound(bucket.len());
+                    let (sub, _) = self.search(bucket, bound - (cost - lower))?;
+                    cost += sub - lower;
+                }
+                Some(cost)
+            },
+            Objective::WorstCase => {
+                let mut depth = 1;
+                for bucket in &buckets {
+                    let (sub, _) = self.search(bucket, bound - 1)?;
+                    depth = depth.max(1 + sub);
+                }
+                (depth < bound).then_some(depth)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<Word> {
+        text.split(' ').map(Word::new).collect()
+    }
+
+    // Only their first letters tell these apart, so guessing them one at a
+    // time takes 1 + 2 + 3 + 4 guesses; blmpz tries all four first letters
+    // at once, for 4 + 4.
+    const GOALS: &str = "batch latch match patch";
+
+    #[test]
+    fn finds_the_best_tree() {
+        let goals = words(GOALS);
+        let mut dict = goals.clone();
+        assert_eq!(OptimalSearch::new(&dict, Objective::Total).with_threads(2).solve(&goals), (goals[0], 10));
+        assert_eq!(OptimalSearch::new(&dict, Objective::WorstCase).solve(&goals), (goals[0], 4));
+        dict.push(Word::new("blmpz"));
+        assert_eq!(OptimalSearch::new(&dict, Objective::Total).with_threads(2).solve(&goals), (Word::new("blmpz"), 8));
+        assert_eq!(OptimalSearch::new(&dict, Objective::WorstCase).solve(&goals), (Word::new("blmpz"), 2));
+        let three = words("cigar rebut sissy");
+        assert_eq!(OptimalSearch::new(&three, Objective::Total).solve(&three), (three[0], 5));
+    }
+
+    #[test]
+    fn remembers_bounds() {
+        let goals = words(GOALS);
+        let mut search = OptimalSearch::new(&goals, Objective::Total);
+        assert_eq!(search.search(&goals, 10), None);
+        assert!(matches!(search.memo.get(&goals), Some(Bound::AtLeast(10))));
+        // Known not to cost less than 10, so it isn't searched again.
+        let nodes = search.nodes();
+        assert_eq!(search.search(&goals, 9), None);
+        assert_eq!(search.nodes(), nodes);
+        assert_eq!(search.search(&goals, 11), Some((10, goals[0])));
+        assert!(matches!(search.memo.get(&goals), Some(Bound::Exact(10, _))));
+        let nodes = search.nodes();
+        assert_eq!(search.search(&goals, 12), Some((10, goals[0])));
+        assert_eq!(search.nodes(), nodes);
+    }
+
+    #[test]
+    fn shares_trees_through_the_table() {
+        let goals = words(GOALS);
+        let mut dict = goals.clone();
+        dict.push(Word::new("blmpz"));
+        let table = Arc::new(TranspositionTable::new());
+        let mut first = OptimalSearch::new(&dict, Objective::Total).with_table(table.clone());
+        let solved = first.solve(&goals);
+        assert!(!table.is_empty());
+        let mut second = OptimalSearch::new(&dict, Objective::Total).with_table(table.clone());
+        assert_eq!(second.solve(&goals), solved);
+        assert_eq!(second.nodes(), 0);
+        // Another objective doesn't share the trees.
+        let mut worst = OptimalSearch::new(&dict, Objective::WorstCase).with_table(table);
+        assert_eq!(worst.solve(&goals), (Word::new("blmpz"), 2));
+        assert!(worst.nodes() > 0);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::alphabet::MAX_SYMBOLS;
+use crate::word::{Word, MAX_LEN};
+use std::fmt;
+use std::str::FromStr;
+
+// A symbol index no alphabet uses, marking goal letters already claimed.
+const CLAIMED: u8 = MAX_SYMBOLS as u8;
+
+/// Feedback for a single tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LetterOutcome { Nowhere, Elsewhere, Here }
+
+impl LetterOutcome {
+    /// Reads a tile emoji in the light, dark or high-contrast share formats.
+    pub fn from_emoji(c: char) -> Option<Self> {
+        match c {
+            '🟩' | '🟧' => Some(LetterOutcome::Here),
+            '🟨' | '🟦' => Some(LetterOutcome::Elsewhere),
+            '⬜' | '⬛' => Some(LetterOutcome::Nowhere),
+            _ => None,
+        }
+    }
+
+    /// Reads a tile typed as a letter: g for green, y for yellow, and b
+    /// (black), w (white), x or . for gray.
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c.to_ascii_lowercase() {
+            'g' => Some(LetterOutcome::Here),
+            'y' => Some(LetterOutcome::Elsewhere),
+            'b' | 'w' | 'x' | '.' => Some(LetterOutcome::Nowhere),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LetterOutcome {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self {
+            LetterOutcome::Here => "🟩",
+            LetterOutcome::Elsewhere => "🟨",
+            LetterOutcome::Nowhere => "⬜",
+        })
+    }
+}
+
+/// Feedback for a whole guess, one tile per position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    // The tiles, padded with grays.
+    tiles: [LetterOutcome; MAX_LEN],
+    len: u8,
+}
+
+impl Outcome {
+    /// An outcome with these tiles. Panics if there are more than
+    /// [`MAX_LEN`].
+    pub fn new(tiles: &[LetterOutcome]) -> Self {
+        let mut padded = [LetterOutcome::Nowhere; MAX_LEN];
+        padded[..tiles.len()].copy_from_slice(tiles);
+        Self { tiles: padded, len: tiles.len() as u8 }
+    }
+
+    /// The feedback Wordle gives for `guess` when the answer is `goal`, which
+    /// must be the same length.
+    ///
+    /// Greens claim their goal letters first; each remaining goal letter can
+    /// then turn at most one other copy of it in the guess yellow, left to right.
+    pub fn compare(goal: Word, guess: Word) -> Self {
+        debug_assert_eq!(goal.len(), guess.len());
+        let (goal_symbols, guess_symbols) = (goal.symbols(), guess.symbols());
+        let mut result = Self { tiles: [LetterOutcome::Nowhere; MAX_LEN], len: guess.len() as u8 };
+        // The goal's letters not yet claimed, by position: a few to search
+        // beats clearing a count for every symbol of a big alphabet.
+        let mut unclaimed = [CLAIMED; MAX_LEN];
+        for (i, r) in result.tiles[..guess_symbols.len()].iter_mut().enumerate() {
+            if goal_symbols[i] == guess_symbols[i] {
+                *r = LetterOutcome::Here;
+            } else {
+                unclaimed[i] = goal_symbols[i];
+            }
+        }
+        for (i, r) in result.tiles[..guess_symbols.len()].iter_mut().enumerate() {
+            if *r == LetterOutcome::Here { continue }
+            if let Some(j) = unclaimed.iter().position(|&s| s == guess_symbols[i]) {
+                *r = LetterOutcome::Elsewhere;
+                unclaimed[j] = CLAIMED;
+            }
+        }
+        result
+    }
+
+    /// The tiles, one per letter of the guess.
+    pub fn tiles(&self) -> &[LetterOutcome] {
+        &self.tiles[..self.len as usize]
+    }
+
+    /// The tiles as letters, g for green, y for yellow and b for gray, e.g.
+    /// "gybbg", which [`FromStr`] reads back.
+    pub fn letters(&self) -> String {
+        self.tiles().iter().map(|&o| match o {
+            LetterOutcome::Here => 'g',
+            LetterOutcome::Elsewhere => 'y',
+            LetterOutcome::Nowhere => 'b',
+        }).collect()
+    }
+
+    /// How many distinct outcomes there are for words of `len` letters.
+    pub fn states(len: usize) -> usize {
+        3usize.pow(len as u32)
+    }
+
+    /// A distinct number below [`Outcome::states`] for each possible outcome,
+    /// reading the tiles as base-3 digits. Winning is always the highest.
+    pub fn code(&self) -> u16 {
+        self.tiles().iter().fold(0, |c, &o| c * 3 + o as u16)
+    }
+
+    /// The outcome of `len` tiles with this [`code`](Outcome::code). Panics
+    /// unless the code is below [`Outcome::states`].
+    pub fn from_code(code: u16, len: usize) -> Self {
+        assert!((code as usize) < Self::states(len));
+        let mut tiles = [LetterOutcome::Nowhere; MAX_LEN];
+        let mut code = code;
+        for tile in tiles[..len].iter_mut().rev() {
+            *tile = [LetterOutcome::Nowhere, LetterOutcome::Elsewhere, LetterOutcome::Here][(code % 3) as usize];
+            code /= 3;
+        }
+        Self { tiles, len: len as u8 }
+    }
+
+    /// Whether every tile is green.
+    pub fn is_win(&self) -> bool {
+        self.tiles().iter().all(|&o| o == LetterOutcome::Here)
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = String;
+
+    /// Reads tiles, each typed as a letter or pasted as an emoji, e.g.
+    /// "gybbg" or "🟩🟨⬜⬜🟩".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tiles: Option<Vec<LetterOutcome>> = s.chars()
+            .map(|c| LetterOutcome::from_letter(c).or_else(|| LetterOutcome::from_emoji(c))).collect();
+        match tiles {
+            Some(tiles) if !tiles.is_empty() && tiles.len() <= MAX_LEN => Ok(Self::new(&tiles)),
+            _ => Err(format!("{:?} isn't a row of tiles", s)),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for o in self.tiles() { o.fmt(formatter)? }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_scores_duplicate_letters() {
+        for &(goal, guess, tiles) in &[
+            ("crane", "crane", "ggggg"),
+            ("crane", "nacre", "yyyyg"),
+            // Both of babes' b's find a b in abbey.
+            ("abbey", "babes", "yyggb"),
+            ("abbey", "kebab", "bygyy"),
+            // The green e of eerie claims one of there's two, leaving one yellow.
+            ("there", "eerie", "ybybg"),
+            // Only one e is left once the green takes its own.
+            ("lever", "eerie", "ygybb"),
+            ("sissy", "essay", "bygbg"),
+            ("sissy", "sassy", "gbggg"),
+            // More copies guessed than there are: the extras are gray.
+            ("mamma", "aaaaa", "bgbbg"),
+            ("algae", "aaaaa", "gbbgb"),
+        ] {
+            let outcome = Outcome::compare(Word::new(goal), Word::new(guess));
+            assert_eq!(outcome.letters(), tiles, "{} against {}", guess, goal);
+        }
+    }
+
+    #[test]
+    fn text_and_codes_round_trip() {
+        for code in 0..Outcome::states(5) as u16 {
+            let outcome = Outcome::from_code(code, 5);
+            assert_eq!(outcome.code(), code);
+            assert_eq!(outcome.letters().parse::<Outcome>(), Ok(outcome));
+            assert_eq!(outcome.to_string().parse::<Outcome>(), Ok(outcome));
+        }
+        assert!(Outcome::from_code(Outcome::states(5) as u16 - 1, 5).is_win());
+        assert_eq!("GY.wx".parse::<Outcome>().map(|o| o.letters()), Ok("gybbb".to_string()));
+        assert!("gyq".parse::<Outcome>().is_err());
+        assert!("".parse::<Outcome>().is_err());
+    }
+}
@@ -0,0 +1,408 @@
+use crate::alphabet::{alphabet, LetterMask, MAX_SYMBOLS};
+use crate::outcome::{LetterOutcome, Outcome};
+use crate::word::{to_letter_mask, Word, MAX_LEN};
+use std::fmt;
+use std::str;
+
+// The mask for a symbol of the alphabet, or no bits for anything else.
+fn letter_mask(letter: char) -> LetterMask {
+    alphabet().lookup(letter).map_or(0, to_letter_mask)
+}
+
+/// What the game's keyboard shows for a letter: the most a pattern knows
+/// about it, in increasing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeyState {
+    /// Nothing is known, usually because it hasn't been guessed.
+    Unused,
+    Absent,
+    /// In the answer, though not known where.
+    Present,
+    /// Known to be at some position.
+    Placed,
+}
+
+/// Everything known about the answer from the feedback so far.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    // Letters known to be present.
+    pub(crate) positive_letters: LetterMask,
+
+    // Letters known to be absent.
+    pub(crate) negative_letters: LetterMask,
+
+    // Letter masks per character.
+    pub(crate) per_char: [LetterMask; MAX_LEN],
+
+    // Bounds on how many times each letter occurs.
+    pub(crate) min_counts: [u8; MAX_SYMBOLS],
+    pub(crate) max_counts: [u8; MAX_SYMBOLS],
+
+    // Letters whose bounds say more than present/absent, so need counting.
+    counted_letters: LetterMask,
+}
+
+impl Pattern {
+    /// A pattern every word matches.
+    pub fn new() -> Self {
+        Self {
+            positive_letters: 0,
+            negative_letters: 0,
+            per_char: [!0; MAX_LEN],
+            min_counts: [0; MAX_SYMBOLS],
+            max_counts: [MAX_LEN as u8; MAX_SYMBOLS],
+            counted_letters: 0,
+        }
+    }
+
+    /// The pattern left after playing each guess and getting its outcome, in order.
+    pub fn from_history(history: &[(Word, Outcome)]) -> Self {
+        let mut pattern = Self::new();
+        for &(word, outcome) in history { pattern.refine(word, outcome); }
+        pattern
+    }
+
+    /// Whether `word` is consistent with everything known.
+    pub fn matches(&self, word: Word) -> bool {
+        if word.letters & self.positive_letters != self.positive_letters { return false }
+        if word.letters & self.negative_letters != 0 { return false }
+        if !word.symbols().iter().zip(self.per_char.iter()).all(|(&w, &m)| (m & to_letter_mask(w)) != 0) { return false }
+        self.counts_allow(word)
+    }
+
+    /// Bit `i` is set if `words[i]` matches. Takes at most 32 words.
+    ///
+    /// A plain loop over the batch, testing each word's letters without
+    /// branching; count bounds, which few patterns have, are checked after
+    /// for the survivors.
+    pub fn batch_mask(&self, words: &[Word]) -> u32 {
+        assert!(words.len() <= 32);
+        let mut mask = 0;
+        for (i, w) in words.iter().enumerate() {
+            let letters = (w.letters & self.positive_letters == self.positive_letters) & (w.letters & self.negative_letters == 0);
+            let placed = w.symbols().iter().zip(&self.per_char).fold(true, |ok, (&b, &m)| ok & (m & to_letter_mask(b) != 0));
+            mask |= ((letters & placed) as u32) << i;
+        }
+        if self.counted_letters != 0 {
+            let mut survivors = mask;
+            while survivors != 0 {
+                let i = survivors.trailing_zeros();
+                if !self.counts_allow(words[i as usize]) { mask &= !(1 << i); }
+                survivors &= survivors - 1;
+            }
+        }
+        mask
+    }
+
+    /// How many of `words` match, in batches of [`batch_mask`](Pattern::batch_mask).
+    pub fn count_matching(&self, words: &[Word]) -> usize {
+        words.chunks(32).map(|batch| self.batch_mask(batch).count_ones() as usize).sum()
+    }
+
+    /// Drops the words that don't match, in batches of
+    /// [`batch_mask`](Pattern::batch_mask).
+    pub fn retain_matching(&self, words: &mut Vec<Word>) {
+        let mut kept = 0;
+        for start in (0..words.len()).step_by(32) {
+            let end = (start + 32).min(words.len());
+            let mut mask = self.batch_mask(&words[start..end]);
+            while mask != 0 {
+                words[kept] = words[start + mask.trailing_zeros() as usize];
+                kept += 1;
+                mask &= mask - 1;
+            }
+        }
+        words.truncate(kept);
+    }
+
+    fn counts_allow(&self, word: Word) -> bool {
+        let mut counted = word.letters & self.counted_letters;
+        while counted != 0 {
+            let l = counted.trailing_zeros() as usize;
+            let n = word.symbols().iter().filter(|&&s| s as usize == l).count() as u8;
+            if n < self.min_counts[l] || n > self.max_counts[l] { return false }
+            counted &= counted - 1;
+        }
+        true
+    }
+
+    /// Whether the answer is known to contain `letter`.
+    pub fn is_present(&self, letter: char) -> bool {
+        self.positive_letters & letter_mask(letter) != 0
+    }
+
+    /// Whether the answer is known not to contain `letter`.
+    pub fn is_absent(&self, letter: char) -> bool {
+        self.negative_letters & letter_mask(letter) != 0
+    }
+
+    /// What the keyboard shows for `letter`, which is
+    /// [`Unused`](KeyState::Unused) for anything not in the alphabet.
+    pub fn key_state(&self, letter: char) -> KeyState {
+        let m = letter_mask(letter);
+        if m == 0 { return KeyState::Unused }
+        if self.positive_letters & m != 0 && self.per_char.contains(&m) { return KeyState::Placed }
+        if self.positive_letters & m != 0 { return KeyState::Present }
+        if self.negative_letters & m != 0 { return KeyState::Absent }
+        KeyState::Unused
+    }
+
+    /// Whether `letter` may still appear at `position` (counting from 0).
+    pub fn allows(&self, position: usize, letter: char) -> bool {
+        self.per_char[position] & letter_mask(letter) != 0
+    }
+
+    // Narrows the number of times a letter may occur to min..=max.
+    fn limit_count(&mut self, s: u8, min: u8, max: u8) {
+        let l = s as usize;
+        let m = to_letter_mask(s);
+        self.min_counts[l] = self.min_counts[l].max(min);
+        self.max_counts[l] = self.max_counts[l].min(max);
+        if self.min_counts[l] > 0 { self.positive_letters |= m; }
+        if self.max_counts[l] == 0 {
+            self.negative_letters |= m;
+            for x in self.per_char.iter_mut() { *x &= !m; }
+        }
+        if self.min_counts[l] > 1 || (1..MAX_LEN as u8).contains(&self.max_counts[l]) { self.counted_letters |= m; }
+    }
+
+    /// The pattern of a crossword-style query: `template` has a letter at
+    /// each known position and `_`, `.` or `?` elsewhere, every one of
+    /// `required` is present, as often as it's repeated there, and none of
+    /// `excluded` is.
+    pub fn from_template(template: &str, required: &str, excluded: &str) -> Result<Self, String> {
+        let symbol = |c: char| alphabet().lookup(c).ok_or_else(|| format!("{:?} isn't a letter", c));
+        let mut pattern = Self::new();
+        if template.chars().count() > MAX_LEN { return Err(format!("templates have at most {} letters", MAX_LEN)) }
+        for (i, c) in template.chars().enumerate().filter(|&(_, c)| !matches!(c, '_' | '.' | '?')) {
+            let s = symbol(c)?;
+            pattern.per_char[i] = to_letter_mask(s);
+            pattern.limit_count(s, 1, MAX_LEN as u8);
+        }
+        let mut counts = [0; MAX_SYMBOLS];
+        for c in required.chars() { counts[symbol(c)? as usize] += 1; }
+        for (s, &n) in counts.iter().enumerate().filter(|&(_, &n)| n > 0) { pattern.limit_count(s as u8, n, MAX_LEN as u8); }
+        for c in excluded.chars() {
+            let s = symbol(c)?;
+            if pattern.min_counts[s as usize] > 0 { return Err(format!("{} is both required and excluded", c)) }
+            pattern.limit_count(s, 0, 0);
+        }
+        Ok(pattern)
+    }
+
+    /// Adds what `outcome` reveals about the answer when `word` was guessed.
+    ///
+    /// Each green or yellow tile is one known copy of its letter; a gray tile
+    /// means there are no copies beyond those.
+    pub fn refine(&mut self, word: Word, outcome: Outcome) {
+        let mut scored = [0u8; MAX_SYMBOLS];
+        let mut grayed = 0;
+        for (i, (&s, &o)) in word.symbols().iter().zip(outcome.tiles()).enumerate() {
+            let m = to_letter_mask(s);
+            match o {
+                LetterOutcome::Nowhere => {
+                    grayed |= m;
+                    self.per_char[i] &= !m;
+                },
+                LetterOutcome::Elsewhere => {
+                    scored[s as usize] += 1;
+                    self.per_char[i] &= !m;
+                },
+                LetterOutcome::Here => {
+                    scored[s as usize] += 1;
+                    self.per_char[i] = m;
+                },
+            }
+        }
+        for &s in word.symbols() {
+            let n = scored[s as usize];
+            self.limit_count(s, n, if grayed & to_letter_mask(s) != 0 { n } else { MAX_LEN as u8 });
+        }
+    }
+}
+
+/// Parses comma-separated constraints: `+a@1` for a at position 1, `?r@3@4`
+/// for r present but not at positions 3 or 4 (`?r` alone for just present),
+/// `-eio` for e, i and o absent, and `e=2`, `e>=2` or `e<=1` for letter
+/// counts. Positions count from 1. `Display` writes the same syntax, so
+/// patterns round-trip through text.
+impl str::FromStr for Pattern {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        let mut pattern = Self::new();
+        for constraint in text.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            let bad = || format!("bad constraint {:?}", constraint);
+            let (kind, rest) = constraint.split_at(constraint.chars().next().map_or(0, char::len_utf8));
+            if kind == "-" {
+                let symbols: Option<Vec<u8>> = rest.chars().map(|c| alphabet().lookup(c)).collect();
+                match symbols {
+                    Some(symbols) if !symbols.is_empty() => for s in symbols { pattern.limit_count(s, 0, 0); },
+                    _ => return Err(bad()),
+                }
+                continue;
+            }
+            if let Some(b) = kind.chars().next().and_then(|c| alphabet().lookup(c)) {
+                let count = |n: &str| n.parse::<u8>().ok().filter(|&n| n as usize <= MAX_LEN).ok_or_else(bad);
+                match (rest.strip_prefix(">="), rest.strip_prefix("<="), rest.strip_prefix('=')) {
+                    (Some(n), _, _) => pattern.limit_count(b, count(n)?, MAX_LEN as u8),
+                    (_, Some(n), _) => pattern.limit_count(b, 0, count(n)?),
+                    (_, _, Some(n)) => { let n = count(n)?; pattern.limit_count(b, n, n) },
+                    _ => return Err(bad()),
+                }
+                continue;
+            }
+            let mut parts = rest.split('@');
+            let mut letter = parts.next().ok_or_else(bad)?.chars();
+            let b = match (letter.next().and_then(|c| alphabet().lookup(c)), letter.next()) {
+                (Some(b), None) => b,
+                _ => return Err(bad()),
+            };
+            let positions = parts.map(|n| n.parse::<usize>().ok().filter(|n| (1..=MAX_LEN).contains(n)).map(|n| n - 1))
+                .collect::<Option<Vec<usize>>>().ok_or_else(bad)?;
+            let m = to_letter_mask(b);
+            match (kind, &positions[..]) {
+                ("+", &[i]) => {
+                    pattern.per_char[i] = m;
+                    pattern.limit_count(b, 1, MAX_LEN as u8);
+                },
+                ("?", positions) => {
+                    for &i in positions { pattern.per_char[i] &= !m; }
+                    pattern.limit_count(b, 1, MAX_LEN as u8);
+                },
+                _ => return Err(bad()),
+            }
+        }
+        Ok(pattern)
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let letters = (0..alphabet().len() as u8).map(|l| (alphabet().symbol(l), to_letter_mask(l)));
+        let placed = |m: LetterMask| self.positive_letters & m != 0 && m.count_ones() == 1;
+        let mut constraints = Vec::new();
+        for (i, &x) in self.per_char.iter().enumerate() {
+            if placed(x) { constraints.push(format!("+{}@{}", alphabet().symbol(x.trailing_zeros() as u8), i + 1)); }
+        }
+        for (c, m) in letters.clone().filter(|&(_, m)| self.positive_letters & m != 0) {
+            let excluded: String = self.per_char.iter().enumerate()
+                .filter(|&(_, &x)| x & m == 0 && !placed(x))
+                .map(|(i, _)| format!("@{}", i + 1)).collect();
+            if !self.per_char.contains(&m) || !excluded.is_empty() { constraints.push(format!("?{}{}", c, excluded)); }
+        }
+        for (l, (c, _)) in letters.clone().enumerate().filter(|&(_, (_, m))| self.counted_letters & m != 0) {
+            let (min, max) = (self.min_counts[l], self.max_counts[l]);
+            if min == max {
+                constraints.push(format!("{}={}", c, min));
+            } else {
+                if min > 1 { constraints.push(format!("{}>={}", c, min)); }
+                if (max as usize) < MAX_LEN { constraints.push(format!("{}<={}", c, max)); }
+            }
+        }
+        let absent: String = letters.filter(|&(_, m)| self.negative_letters & m != 0).map(|(c, _)| c).collect();
+        if !absent.is_empty() { constraints.push(format!("-{}", absent)); }
+        formatter.write_str(&constraints.join(","))
+    }
+}
+
+impl Default for Pattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goals() -> Vec<Word> {
+        include_str!("../goals.txt").lines().map(Word::new).collect()
+    }
+
+    fn matching(pattern: &Pattern, words: &[Word]) -> Vec<Word> {
+        words.iter().copied().filter(|&w| pattern.matches(w)).collect()
+    }
+
+    fn refined(history: &[(&str, &str)]) -> Pattern {
+        let history: Vec<(Word, Outcome)> = history.iter().map(|&(w, o)| (Word::new(w), o.parse().unwrap())).collect();
+        Pattern::from_history(&history)
+    }
+
+    #[test]
+    fn refine_keeps_the_goal_and_what_gives_the_same_outcomes() {
+        let goals = goals();
+        for (goal, guesses) in [("abbey", ["babes", "kebab"]), ("there", ["eerie", "three"]), ("sissy", ["essay", "sassy"])] {
+            let goal = Word::new(goal);
+            let history: Vec<(Word, Outcome)> = guesses.iter().map(|&g| (Word::new(g), Outcome::compare(goal, Word::new(g)))).collect();
+            let pattern = Pattern::from_history(&history);
+            let expected: Vec<Word> = goals.iter().copied()
+                .filter(|&w| history.iter().all(|&(guess, outcome)| Outcome::compare(w, guess) == outcome)).collect();
+            assert!(expected.contains(&goal));
+            assert_eq!(matching(&pattern, &goals), expected, "{}", goal);
+        }
+    }
+
+    #[test]
+    fn gray_copies_cap_the_count() {
+        // The second e of eerie is gray against there, so it has exactly two.
+        let pattern = refined(&[("eerie", "ybybg")]);
+        assert_eq!(pattern.to_string(), "+e@5,?e@1@2,?r@3,e=2,-i");
+        assert!(pattern.matches(Word::new("there")));
+        assert!(!pattern.matches(Word::new("purge")));
+        assert!(!pattern.matches(Word::new("reeve")));
+    }
+
+    #[test]
+    fn duplicate_yellows_need_two_copies() {
+        let pattern = refined(&[("eerie", "yybbb")]);
+        assert!(pattern.matches(Word::new("bleed")));
+        assert!(!pattern.matches(Word::new("bland")));
+        assert!(!pattern.matches(Word::new("bread")));
+        let pattern: Pattern = "e>=2,-ri".parse().unwrap();
+        assert!(pattern.matches(Word::new("bleed")));
+        assert!(!pattern.matches(Word::new("bread")));
+    }
+
+    #[test]
+    fn text_round_trips() {
+        let goals = goals();
+        for text in ["", "+s@1,?r@3@4,-eio", "+e@5,?e@1@2,?r@3,e=2,-i", "?a,a<=1,-esoir", "t>=2", "?l@1@2@3@4@5"] {
+            let pattern: Pattern = text.parse().unwrap();
+            let written = pattern.to_string();
+            let reread: Pattern = written.parse().unwrap();
+            assert_eq!(reread.to_string(), written, "{:?}", text);
+            assert_eq!(matching(&reread, &goals), matching(&pattern, &goals), "{:?}", text);
+        }
+        let pattern = refined(&[("crane", "bybby"), ("toile", "bbbyg")]);
+        let reread: Pattern = pattern.to_string().parse().unwrap();
+        assert_eq!(matching(&reread, &goals), matching(&pattern, &goals));
+    }
+
+    #[test]
+    fn bad_constraints_are_rejected() {
+        for text in ["+a", "+a@0", "+a@9", "?ab", "-", "a=9", "a>", "*a"] {
+            assert!(text.parse::<Pattern>().is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn batches_agree_with_matches() {
+        let goals = goals();
+        let pattern = refined(&[("eerie", "ybybg")]);
+        let mut kept = goals.clone();
+        pattern.retain_matching(&mut kept);
+        assert_eq!(kept, matching(&pattern, &goals));
+        assert_eq!(pattern.count_matching(&goals), kept.len());
+    }
+
+    #[test]
+    fn templates() {
+        let pattern = Pattern::from_template("s_a__", "tt", "e").unwrap();
+        assert!(pattern.matches(Word::new("start")));
+        assert!(!pattern.matches(Word::new("stair")));
+        assert!(!pattern.matches(Word::new("steal")));
+        assert!(Pattern::from_template("s_a__", "e", "e").is_err());
+        assert!(Pattern::from_template("s_a_1", "", "").is_err());
+    }
+}
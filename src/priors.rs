@@ -0,0 +1,55 @@
+use crate::word::Word;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+/// How likely each goal is to be the answer, from word frequencies.
+///
+/// Weights are relative; words missing from the list get the smallest weight
+/// that is listed, so rare words stay possible.
+#[derive(Debug, Clone)]
+pub struct Priors {
+    weights: HashMap<Word, f64>,
+    default: f64,
+}
+
+impl Priors {
+    /// Priors from `(word, weight)` pairs. Panics on a negative or NaN weight.
+    pub fn new(weights: impl IntoIterator<Item = (Word, f64)>) -> Self {
+        let weights: HashMap<Word, f64> = weights.into_iter().collect();
+        assert!(weights.values().all(|&w| w >= 0.0), "weights must be non-negative");
+        let default = weights.values().copied().filter(|&w| w > 0.0).fold(f64::INFINITY, f64::min);
+        Self { weights, default: if default.is_finite() { default } else { 1.0 } }
+    }
+
+    /// The relative weight of `word`.
+    pub fn weight(&self, word: Word) -> f64 {
+        self.weights.get(&word).copied().unwrap_or(self.default)
+    }
+
+    /// The probability of each of `goals` being the answer, in order.
+    pub fn probabilities(&self, goals: &[Word]) -> Vec<f64> {
+        let weights: Vec<f64> = goals.iter().map(|&g| self.weight(g)).collect();
+        let total: f64 = weights.iter().sum();
+        weights.iter().map(|w| if total > 0.0 { w / total } else { 1.0 / goals.len() as f64 }).collect()
+    }
+
+    /// Sorts `goals` from most to least likely, alphabetically among equals.
+    pub fn sort_by_likelihood(&self, goals: &mut [Word]) {
+        goals.sort_by(|&a, &b| self.weight(b).total_cmp(&self.weight(a)).then(a.cmp(&b)));
+    }
+}
+
+/// Reads a frequency list of `word,weight` lines, also accepting whitespace
+/// between the two. Words that aren't `length` letters of the
+/// [`alphabet`](crate::alphabet), in either case, are skipped.
+pub fn read_priors(filename: &str, length: usize) -> io::Result<Priors> {
+    let mut weights = Vec::new();
+    for line in fs::read_to_string(filename)?.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let malformed = || io::Error::new(io::ErrorKind::InvalidData, format!("malformed frequency line {:?}", line));
+        let (word, weight) = line.split_once(',').or_else(|| line.split_once(char::is_whitespace)).ok_or_else(malformed)?;
+        let weight: f64 = weight.trim().parse().ok().filter(|w: &f64| *w >= 0.0).ok_or_else(malformed)?;
+        if let Some(word) = Word::parse(&word.trim().to_lowercase()).filter(|w| w.len() == length) { weights.push((word, weight)); }
+    }
+    Ok(Priors::new(weights))
+}
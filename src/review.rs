@@ -0,0 +1,176 @@
+use crate::analysis::{expected_remaining, outcome_counts, worst_bucket};
+use crate::outcome::Outcome;
+use crate::solver::{score_all, Solver};
+use crate::strategy::{Quick, Strategy};
+use crate::word::Word;
+
+/// How one guess of a game went, judged against the goals possible before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnReview {
+    pub guess: Word,
+    pub outcome: Outcome,
+
+    /// How many goals were possible before the guess, and after its outcome.
+    pub before: usize,
+    pub after: usize,
+
+    /// The strategy's guess here, falling back to [`Quick`] while there were
+    /// too many goals for it, as [`Solver::play`] does.
+    pub recommended: Option<Word>,
+
+    /// The most goals the guess, and the recommended guess, could have left.
+    pub worst: usize,
+    pub recommended_worst: usize,
+
+    /// Goals the guess, and the recommended guess, were expected to leave,
+    /// if the answer is any goal equally likely.
+    pub expected: f64,
+    pub recommended_expected: f64,
+
+    /// How close the guess came to the recommended one, from 0 to 1: the
+    /// goals the recommended guess was expected to leave over those this one
+    /// was, or 1 if it did as well.
+    pub skill: f64,
+
+    /// How lucky the outcome was, from 0 to 1: the chance another goal would
+    /// have left more, counting half of those leaving as many.
+    pub luck: f64,
+}
+
+/// Reviews each guess of a game in `history` played from `solver`'s position.
+/// Guesses after the goals run out are left out.
+pub fn review(solver: &Solver, history: &[(Word, Outcome)], strategy: &dyn Strategy) -> Vec<TurnReview> {
+    let mut solver = solver.clone();
+    let mut turns = Vec::new();
+    for &(guess, outcome) in history {
+        let goals = solver.candidates();
+        if goals.is_empty() { break }
+        let practical = if goals.len() > strategy.max_candidates() { &Quick } else { strategy };
+        let recommended = solver.choose(practical);
+        let counts = outcome_counts(guess, goals);
+        let actual = counts[outcome.code() as usize] as usize;
+        let expected = expected_remaining(guess, goals);
+        let recommended_expected = recommended.map_or(expected, |r| expected_remaining(r, goals));
+        let (more, same): (usize, usize) = counts.iter().map(|&c| c as usize).fold((0, 0), |(more, same), c| {
+            if c > actual { (more + c, same) } else if c == actual { (more, same + c) } else { (more, same) }
+        });
+        turns.push(TurnReview {
+            guess,
+            outcome,
+            before: goals.len(),
+            after: actual,
+            recommended,
+            worst: worst_bucket(guess, goals),
+            recommended_worst: recommended.map_or(0, |r| worst_bucket(r, goals)),
+            expected,
+            recommended_expected,
+            skill: (recommended_expected / expected).min(1.0),
+            luck: (more as f64 + same as f64 / 2.0) / goals.len() as f64,
+        });
+        solver.refine(guess, outcome);
+    }
+    turns
+}
+
+/// The skill and luck of a whole game reviewed as `turns`: the mean of its
+/// turns', or 0 with none.
+pub fn skill_and_luck(turns: &[TurnReview]) -> (f64, f64) {
+    if turns.is_empty() { return (0.0, 0.0) }
+    let n = turns.len() as f64;
+    (turns.iter().map(|t| t.skill).sum::<f64>() / n, turns.iter().map(|t| t.luck).sum::<f64>() / n)
+}
+
+/// A game's guesses beside the line the strategy plays against the same goal
+/// from the same position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineComparison {
+    /// The strategy's guesses and their outcomes, until it found the goal or
+    /// gave up.
+    pub line: Vec<(Word, Outcome)>,
+
+    /// The first turn, counting from 0, whose guess wasn't the strategy's,
+    /// if there was one.
+    pub divergence: Option<usize>,
+
+    /// How many more guesses the game's guess at the divergence was expected
+    /// to take to win than the strategy's, negative if fewer, by
+    /// [`expected_guesses`]; 0 without a divergence.
+    pub cost: f64,
+}
+
+/// Compares the game in `history`, played from `solver`'s position against
+/// `goal`, with the line `strategy` plays against it, giving up after
+/// `limit` guesses as [`Solver::play`] does.
+pub fn compare_lines(solver: &Solver, history: &[(Word, Outcome)], goal: Word, strategy: &dyn Strategy, limit: usize) -> LineComparison {
+    let mut played = solver.clone();
+    played.play(goal, strategy, limit);
+    let line = played.history()[solver.history().len()..].to_vec();
+    let divergence = history.iter().zip(&line).position(|(a, b)| a.0 != b.0);
+    let Some(turn) = divergence else { return LineComparison { line, divergence, cost: 0.0 } };
+    let mut position = solver.clone();
+    for &(guess, outcome) in &history[..turn] { position.refine(guess, outcome); }
+    let cost = expected_guesses(&position, history[turn].0, strategy, limit) - expected_guesses(&position, line[turn].0, strategy, limit);
+    LineComparison { line, divergence, cost }
+}
+
+/// The guesses it takes on average to find each of `solver`'s candidates,
+/// playing `guess` and then `strategy` as [`Solver::play`] does, on all
+/// available cores. Goals not found within `limit` guesses count as
+/// `limit` + 1.
+pub fn expected_guesses(solver: &Solver, guess: Word, strategy: &dyn Strategy, limit: usize) -> f64 {
+    let goals = solver.candidates();
+    let played = score_all(goals, usize::MAX, |goal| {
+        if goal == guess { return 1 }
+        let mut game = solver.clone();
+        game.refine(guess, Outcome::compare(goal, guess));
+        1 + game.play(goal, strategy, limit.saturating_sub(1)).unwrap_or(limit)
+    });
+    played.iter().map(|p| p.1).sum::<usize>() as f64 / goals.len().max(1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Minimax;
+
+    #[test]
+    fn judges_guesses_against_the_recommended_ones() {
+        let goals: Vec<Word> = include_str!("../goals.txt").lines().take(100).map(Word::new).collect();
+        let solver = Solver::new(goals.clone(), goals.clone());
+        let strategy = Minimax { progress_every: usize::MAX, ..Minimax::default() };
+        let mut played = solver.clone();
+        played.play(goals[0], &strategy, 6).unwrap();
+        let turns = review(&solver, played.history(), &strategy);
+        assert_eq!(turns.len(), played.history().len());
+        assert!(turns.iter().all(|t| t.skill == 1.0 && Some(t.guess) == t.recommended));
+        assert_eq!(skill_and_luck(&turns).0, 1.0);
+
+        // Guessing the goal outright is lucky, if rarely the best guess.
+        let history = [(goals[1], Outcome::compare(goals[1], goals[1]))];
+        let turn = &review(&solver, &history, &strategy)[0];
+        assert_eq!((turn.before, turn.after), (100, 1));
+        assert!(turn.skill < 1.0 && turn.skill == turn.recommended_expected / turn.expected);
+        assert!(turn.luck > 0.9);
+        assert_eq!(skill_and_luck(&[]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn compares_lines_from_their_first_difference() {
+        let goals: Vec<Word> = include_str!("../goals.txt").lines().take(100).map(Word::new).collect();
+        let solver = Solver::new(goals.clone(), goals.clone());
+        let strategy = Minimax { progress_every: usize::MAX, ..Minimax::default() };
+        let mut played = solver.clone();
+        played.play(goals[0], &strategy, 6).unwrap();
+        let same = compare_lines(&solver, played.history(), goals[0], &strategy, 6);
+        assert_eq!((same.line.as_slice(), same.divergence, same.cost), (played.history(), None, 0.0));
+
+        // The first guess leaving the most goals at worst costs guesses.
+        let worse = goals.iter().copied().max_by_key(|&g| worst_bucket(g, &goals)).unwrap();
+        let history = [(worse, Outcome::compare(goals[0], worse))];
+        let compared = compare_lines(&solver, &history, goals[0], &strategy, 6);
+        assert_eq!(compared.divergence, Some(0));
+        let cost = expected_guesses(&solver, worse, &strategy, 6) - expected_guesses(&solver, compared.line[0].0, &strategy, 6);
+        assert!(compared.cost > 0.0 && compared.cost == cost);
+        assert_eq!(expected_guesses(&Solver::new(goals[..1].to_vec(), goals.clone()), goals[0], &strategy, 6), 1.0);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::outcome::Outcome;
+use crate::solver::Solver;
+use crate::strategy::{Quick, Strategy};
+use crate::word::Word;
+use std::collections::HashMap;
+
+/// Games in progress for many players at once, such as the channels of a
+/// chat bot, each named by a key and started from the same [`Solver`]. A
+/// started game is a clone of that solver, sharing its word lists, so one
+/// hosted solver can serve many games.
+///
+/// [`handle`](SessionManager::handle) answers slash commands as a bot
+/// would; the rest is for driving games directly.
+pub struct SessionManager {
+    start: Solver,
+    strategy: Box<dyn Strategy>,
+    sessions: HashMap<String, Solver>,
+}
+
+impl SessionManager {
+    /// Games starting from `start`, with guesses recommended by `strategy`,
+    /// or by [`Quick`] while there are too many goals for it.
+    pub fn new(start: Solver, strategy: Box<dyn Strategy>) -> Self {
+        Self { start, strategy, sessions: HashMap::new() }
+    }
+
+    /// The game named `key`, started if there isn't one yet.
+    pub fn session(&mut self, key: &str) -> &mut Solver {
+        let start = &self.start;
+        self.sessions.entry(key.to_string()).or_insert_with(|| start.clone())
+    }
+
+    /// Ends the game named `key`, so the next command starts another.
+    /// Returns whether there was one.
+    pub fn end(&mut self, key: &str) -> bool {
+        self.sessions.remove(key).is_some()
+    }
+
+    /// How many games are in progress.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// The guess recommended in the game named `key`, or `None` if no goals
+    /// are left.
+    pub fn suggest(&mut self, key: &str) -> Option<Word> {
+        let start = &self.start;
+        let solver = self.sessions.entry(key.to_string()).or_insert_with(|| start.clone());
+        let strategy = if solver.candidates().len() > self.strategy.max_candidates() { &Quick } else { self.strategy.as_ref() };
+        solver.choose(strategy)
+    }
+
+    /// The reply to a slash command in the game named `key`:
+    ///
+    /// - `/suggest`, the recommended guess;
+    /// - `/guess WORD TILES`, recording a guess and its tiles, typed like
+    ///   "gybbg" or pasted as emoji;
+    /// - `/analyze WORD`, how well a guess would split the goals left;
+    /// - `/reset`, ending the game.
+    pub fn handle(&mut self, key: &str, command: &str) -> String {
+        let length = self.start.candidates().first().map_or(0, |goal| goal.len());
+        let fields: Vec<&str> = command.split_whitespace().collect();
+        let word = |text: &str| Word::parse(text).filter(|w| w.len() == length);
+        match fields[..] {
+            ["/suggest"] => match self.suggest(key) {
+                Some(guess) => format!("try {}, {} goals left", guess, self.session(key).candidates().len()),
+                None => "no goals are left".to_string(),
+            },
+            ["/guess", guess, tiles] => {
+                let Some(guess) = word(guess) else { return format!("{} isn't a word of {} letters", guess, length) };
+                let outcome = match tiles.parse::<Outcome>() {
+                    Ok(outcome) if outcome.tiles().len() == length => outcome,
+                    _ => return format!("{} isn't a row of {} tiles", tiles, length),
+                };
+                let solver = self.session(key);
+                solver.refine(guess, outcome);
+                if outcome.is_win() {
+                    let guesses = solver.history().len();
+                    self.end(key);
+                    return format!("solved in {} guesses", guesses);
+                }
+                match solver.candidates() {
+                    [] => "no goals fit those tiles".to_string(),
+                    [goal] => format!("the answer must be {}", goal),
+                    goals => format!("{} goals left", goals.len()),
+                }
+            },
+            ["/analyze", guess] => {
+                let Some(guess) = word(guess) else { return format!("{} isn't a word of {} letters", guess, length) };
+                let solver = self.session(key);
+                if solver.candidates().is_empty() { return "no goals are left".to_string() }
+                format!("{} leaves at most {} of {} goals, ranking {} of the guesses", guess, -solver.confidence(guess),
+                        solver.candidates().len(), solver.rank(guess))
+            },
+            ["/reset"] => {
+                self.end(key);
+                "started a new game".to_string()
+            },
+            _ => "try /suggest, /guess WORD TILES, /analyze WORD or /reset".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Minimax;
+
+    fn words(text: &str) -> Vec<Word> {
+        text.split(' ').map(Word::new).collect()
+    }
+
+    #[test]
+    fn keeps_a_game_per_key() {
+        let goals = words("cigar rebut sissy humph awake blush focal");
+        let strategy = Minimax { progress_every: usize::MAX, ..Minimax::default() };
+        let mut sessions = SessionManager::new(Solver::new(goals.clone(), goals), Box::new(strategy));
+        assert_eq!(sessions.handle("a", "/guess humph bybbb"), "the answer must be rebut");
+        assert_eq!(sessions.handle("b", "/analyze humph"), "humph leaves at most 4 of 7 goals, ranking 6 of the guesses");
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions.handle("a", "/suggest"), "try rebut, 1 goals left");
+        assert_eq!(sessions.handle("a", "/guess rebut ggggg"), "solved in 2 guesses");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions.handle("b", "/guess humph gybb"), "gybb isn't a row of 5 tiles");
+        assert_eq!(sessions.handle("b", "/reset"), "started a new game");
+        assert!(sessions.is_empty());
+    }
+}
@@ -0,0 +1,672 @@
+use crate::alphabet::MAX_SYMBOLS;
+use crate::analysis::{outcome_counts, positional_counts, worst_bucket};
+use crate::goalset::{GoalIndex, GoalSet};
+use crate::guarantee::safe_guess;
+use crate::matrix::OutcomeMatrix;
+use crate::metrics::{count, Counter};
+use crate::outcome::{LetterOutcome, Outcome};
+use crate::pattern::Pattern;
+use crate::strategy::{Quick, Strategy};
+use crate::transposition::TranspositionTable;
+use crate::verify::{verify_goals, Divergence};
+use crate::word::{to_letter_mask, Word};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: usize = 30;
+
+// A progress bar on stderr for a scoring pass, with the percent done, an
+// estimate of the time left and, when the items are guesses, the best so far.
+struct Progress {
+    total: usize,
+    every: usize,
+    done: AtomicUsize,
+    start: Instant,
+    best: Mutex<Option<(i32, Word)>>,
+}
+
+impl Progress {
+    fn new(total: usize, every: usize) -> Self {
+        Self { total, every, done: AtomicUsize::new(0), start: Instant::now(), best: Mutex::new(None) }
+    }
+
+    // Counts an item scored, redrawing every `every` items.
+    fn tick(&self, scored: Option<(i32, Word)>) {
+        if self.every == usize::MAX { return }
+        if let Some(scored) = scored {
+            let mut best = self.best.lock().unwrap();
+            if best.is_none_or(|b| scored.0 > b.0) { *best = Some(scored); }
+        }
+        let n = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if !n.is_multiple_of(self.every) { return }
+        let fraction = n as f64 / self.total as f64;
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let filled = ((fraction * BAR_WIDTH as f64) as usize).min(BAR_WIDTH);
+        let best = match *self.best.lock().unwrap() {
+            Some((confidence, guess)) => format!("  best so far {} (at most {} left)", guess, -confidence),
+            None => String::new(),
+        };
+        eprint!("\r[{}{}] {:>3.0}%  {:.1}s left{}\x1b[K", "#".repeat(filled), " ".repeat(BAR_WIDTH - filled),
+                100.0 * fraction, elapsed / fraction - elapsed, best);
+    }
+
+    // Clears the bar, if one was drawn, and says how far scoring got if it
+    // ran out of time.
+    fn finish(&self) {
+        if self.every == usize::MAX { return }
+        let done = self.done.load(Ordering::Relaxed);
+        if done >= self.every { eprint!("\r\x1b[K"); }
+        if done < self.total {
+            eprintln!("out of time after scoring {} of {} guesses ({:.0}%)", done, self.total, 100.0 * done as f64 / self.total as f64);
+        }
+    }
+}
+
+/// Scores every item across all available cores, keeping `items`' order.
+/// Redraws a progress bar on stderr every `progress_every` items scored.
+pub(crate) fn score_all<I, T>(items: &[I], progress_every: usize, score: impl Fn(I) -> T + Sync) -> Vec<(I, T)>
+where I: Copy + Send + Sync, T: Send {
+    score_with(items, progress_every, None, score, |_, _| None)
+}
+
+/// Like [`score_all`] for confidences of guesses, where `guess` names each
+/// item so the progress bar can show the best so far. Past `deadline`, no
+/// more are started, so only the first items, roughly in order, are scored.
+pub(crate) fn score_guesses<I>(items: &[I], progress_every: usize, deadline: Option<Instant>,
+                               guess: impl Fn(I) -> Word + Sync, score: impl Fn(I) -> i32 + Sync) -> Vec<(I, i32)>
+where I: Copy + Send + Sync {
+    let score = |item| {
+        count(Counter::GuessesScored, 1);
+        score(item)
+    };
+    score_with(items, progress_every, deadline, score, |item, &confidence| Some((confidence, guess(item))))
+}
+
+fn score_with<I, T>(items: &[I], progress_every: usize, deadline: Option<Instant>, score: impl Fn(I) -> T + Sync,
+                    label: impl Fn(I, &T) -> Option<(i32, Word)> + Sync) -> Vec<(I, T)>
+where I: Copy + Send + Sync, T: Send {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get()).min(items.len()).max(1);
+    let progress = Progress::new(items.len(), progress_every);
+    // Threads take the next item in turn, so they all work from the front.
+    let next = AtomicUsize::new(0);
+    let (progress, next, score, label) = (&progress, &next, &score, &label);
+    let mut scored: Vec<(usize, I, T)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads).map(|_| scope.spawn(move || {
+            let mut scored = Vec::new();
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                // Every thread scores one item, so something always is.
+                if i >= items.len() || (i >= threads && deadline.is_some_and(|d| Instant::now() >= d)) { break }
+                let s = score(items[i]);
+                progress.tick(if progress.every == usize::MAX { None } else { label(items[i], &s) });
+                scored.push((i, items[i], s));
+            }
+            scored
+        })).collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    });
+    progress.finish();
+    scored.sort_by_key(|s| s.0);
+    scored.into_iter().map(|(_, item, s)| (item, s)).collect()
+}
+
+/// Negated size of the largest set of goals `guess` could leave, so that
+/// higher is better.
+pub fn guess_confidence(pattern: &Pattern, goals: &[Word], guess: Word) -> i32 {
+    goals.iter().map(|&goal| -> i32 {
+        let outcome = Outcome::compare(goal, guess);
+        let mut hypothetical_pattern = pattern.clone();
+        hypothetical_pattern.refine(guess, outcome);
+        -(hypothetical_pattern.count_matching(goals) as i32)
+    }).min().unwrap()
+}
+
+/// The guess with the best worst case, with its [`guess_confidence`].
+///
+/// A goal word is preferred over a non-goal that is at most one word better,
+/// since it might win outright, and ties go to the alphabetically first, so
+/// the order of the lists doesn't matter. Redraws a progress bar on stderr every
+/// `progress_every` guesses evaluated.
+pub fn recommended_guess(pattern: &Pattern, goals: &[Word], dict: &[Word], progress_every: usize) -> (Word, i32) {
+    limited_guess(pattern, goals, dict, progress_every, SearchLimits::default())
+}
+
+/// Ways to cut a minimax search short, trading the best guess for time. The
+/// goals are always all scored, before the rest of the guesses, which are
+/// taken from the best by [`quick_guess`]'s heuristic down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Settle for the best guess found in this long. The progress bar's last
+    /// line says how many were scored.
+    pub budget: Option<Duration>,
+
+    /// Only score this many of the guesses that aren't goals.
+    pub prune: Option<usize>,
+}
+
+/// Like [`recommended_guess`], but within `limits`.
+pub fn limited_guess(pattern: &Pattern, goals: &[Word], dict: &[Word], progress_every: usize,
+                     limits: SearchLimits) -> (Word, i32) {
+    let deadline = limits.budget.map(|b| Instant::now() + b);
+    let counted: Vec<Word> = cutoff_order(goals).into_iter().map(|i| goals[i]).collect();
+    let (index, all) = (GoalIndex::new(&counted), GoalSet::full(goals.len()));
+    // Scoring a guess stops once its worst case is worse than `bound`, the
+    // best so far, so it can't win. Ties aren't cut off, so the result is the
+    // same as scoring everything.
+    let best_from = |dict: &[Word], bound: usize| -> (Word, i32) {
+        let bound = AtomicUsize::new(bound);
+        score_guesses(dict, progress_every, deadline, |guess| guess, |guess| {
+            let confidence = index.confidence_within(pattern, &all, guess, bound.load(Ordering::Relaxed));
+            bound.fetch_min(-confidence as usize, Ordering::Relaxed);
+            confidence
+        }).into_iter().max_by_key(|&(w, c)| (c, Reverse(w))).unwrap()
+    };
+    let (bg, bgc) = best_from(goals, usize::MAX);
+    // A guess that isn't a goal has to beat the best goal by two words.
+    let bound = (-bgc as usize).saturating_sub(2);
+    let (bd, bdc) = if limits == SearchLimits::default() { best_from(dict, bound) } else { best_from(&limits.order(pattern, goals, dict), bound) };
+    if bgc+1 >= bdc { (bg, bgc) } else { (bd, bdc) }
+}
+
+// Indices of the goals in the order to count them when worst cases may be
+// cut off: most like the rest first, by how common their letters are where
+// they are. Those share outcomes the most, so a guess's big sets, and the
+// cutoff, come soonest.
+pub(crate) fn cutoff_order(goals: &[Word]) -> Vec<usize> {
+    let counts = positional_counts(goals);
+    let common = |goal: &Word| -> u32 { goal.symbols().iter().enumerate().map(|(i, &s)| counts[i][s as usize]).sum() };
+    let mut order: Vec<usize> = (0..goals.len()).collect();
+    order.sort_by_key(|&i| (Reverse(common(&goals[i])), i));
+    order
+}
+
+impl SearchLimits {
+    // The guesses from `dict` to search, in the order to search them.
+    pub(crate) fn order(&self, pattern: &Pattern, goals: &[Word], dict: &[Word]) -> Vec<Word> {
+        let score = quick_scorer(pattern, goals);
+        let mut sorted: Vec<(u32, Word)> = dict.iter().map(|&w| (score(&w), w)).collect();
+        sorted.sort_by_key(|&(s, w)| (Reverse(s), w));
+        if let Some(prune) = self.prune { sorted.truncate(prune.max(1)); }
+        sorted.into_iter().map(|s| s.1).collect()
+    }
+}
+
+/// The worst case two guesses deep: over every outcome of `guess`, the most
+/// goals the best follow-up from `dict` could leave, negated like
+/// [`guess_confidence`].
+pub fn lookahead_confidence(goals: &[Word], dict: &[Word], guess: Word) -> i32 {
+    lookahead_confidence_in(goals, dict, guess, None)
+}
+
+// Like lookahead_confidence, remembering the best follow-up to each outcome
+// in `table`, under the hash of `dict`, if there is one.
+fn lookahead_confidence_in(goals: &[Word], dict: &[Word], guess: Word, table: Option<(&TranspositionTable, u64)>) -> i32 {
+    let mut buckets: HashMap<Outcome, Vec<Word>> = HashMap::new();
+    for &goal in goals {
+        if goal != guess { buckets.entry(Outcome::compare(goal, guess)).or_default().push(goal); }
+    }
+    let follow_up = |bucket: &[Word]| -> (Word, i32) {
+        dict.iter().chain(bucket).map(|&g| (g, -(worst_bucket(g, bucket) as i32))).max_by_key(|&(g, c)| (c, Reverse(g))).unwrap()
+    };
+    buckets.values().map(|bucket| match table {
+        Some((table, dict_hash)) => table.get_or_search(TranspositionTable::key_hashed("follow-up", bucket, dict_hash), || follow_up(bucket)).1,
+        None => follow_up(bucket).1,
+    }).min().unwrap_or(0)
+}
+
+/// Like [`recommended_guess`], but guesses within one word of the best worst
+/// case are ranked by [`lookahead_confidence`] first. Still returns the
+/// one-guess [`guess_confidence`].
+pub fn lookahead_guess(pattern: &Pattern, goals: &[Word], dict: &[Word], progress_every: usize) -> (Word, i32) {
+    lookahead_guess_in(pattern, goals, dict, progress_every, None)
+}
+
+// Like lookahead_guess, sharing the follow-ups found through `table`.
+pub(crate) fn lookahead_guess_in(pattern: &Pattern, goals: &[Word], dict: &[Word], progress_every: usize,
+                                 table: Option<&TranspositionTable>) -> (Word, i32) {
+    let table = table.map(|table| (table, TranspositionTable::hash(dict)));
+    let guesses: Vec<Word> = dict.iter().chain(goals).copied().collect();
+    let (index, all) = (GoalIndex::new(goals), GoalSet::full(goals.len()));
+    let scored = score_guesses(&guesses, progress_every, None, |guess| guess, |guess| index.confidence(pattern, &all, guess));
+    let best = scored.iter().map(|s| s.1).max().unwrap();
+    let close: Vec<(Word, i32)> = scored.into_iter().filter(|s| s.1 + 1 >= best).collect();
+    let close_words: Vec<Word> = close.iter().map(|s| s.0).collect();
+    let lookahead = score_all(&close_words, usize::MAX, |guess| lookahead_confidence_in(goals, dict, guess, table));
+    close.into_iter().zip(lookahead)
+        .max_by_key(|&((g, c), (_, l))| (l, goals.contains(&g), c, Reverse(g)))
+        .map(|(s, _)| s).unwrap()
+}
+
+/// A fast heuristic guess.
+///
+/// Scores guesses by how many remaining goals share each untested letter, plus
+/// how many share each letter in place. Linear in the word lists, so it's fast
+/// enough to run on every turn no matter how many goals remain.
+pub fn quick_guess(pattern: &Pattern, goals: &[Word], dict: &[Word]) -> Word {
+    if goals.len() <= 2 { return *goals.iter().min().unwrap() }
+    let score = quick_scorer(pattern, goals);
+    *dict.iter().max_by_key(|&&w| (score(&w), Reverse(w))).unwrap()
+}
+
+// The score quick_guess ranks guesses by.
+fn quick_scorer<'a>(pattern: &'a Pattern, goals: &[Word]) -> impl Fn(&Word) -> u32 + 'a {
+    let mut containing = [0u32; MAX_SYMBOLS];
+    for g in goals {
+        let mut letters = g.letters;
+        while letters != 0 {
+            containing[letters.trailing_zeros() as usize] += 1;
+            letters &= letters - 1;
+        }
+    }
+    let positional = positional_counts(goals);
+    let known = pattern.positive_letters | pattern.negative_letters;
+    move |w: &Word| -> u32 {
+        let untested = w.letters & !known;
+        let (mut untested, mut letters) = (untested, 0);
+        while untested != 0 {
+            letters += containing[untested.trailing_zeros() as usize];
+            untested &= untested - 1;
+        }
+        let placed: u32 = w.symbols().iter().enumerate()
+            .filter(|&(i, &s)| pattern.per_char[i] != to_letter_mask(s))
+            .map(|(i, &s)| positional[i][s as usize]).sum();
+        letters + placed
+    }
+}
+
+/// The goals a [`Solver`] has left, from [`Solver::remaining`], narrowed by
+/// any more constraints given with [`matching`](Candidates::matching), e.g. a
+/// player's query in a UI. Goals keep the solver's order.
+#[derive(Debug, Clone)]
+pub struct Candidates<'a> {
+    goals: &'a [Word],
+    constraints: Vec<Pattern>,
+}
+
+impl<'a> Candidates<'a> {
+    /// Only the goals also matching `pattern`.
+    pub fn matching(mut self, pattern: Pattern) -> Self {
+        self.constraints.push(pattern);
+        self
+    }
+
+    fn allows(&self, word: Word) -> bool {
+        self.constraints.iter().all(|c| c.matches(word))
+    }
+
+    /// The goals, in order.
+    pub fn iter(&self) -> impl Iterator<Item = Word> + '_ {
+        self.goals.iter().copied().filter(move |&w| self.allows(w))
+    }
+
+    /// Up to `n` goals, skipping the first `start`.
+    pub fn page(&self, start: usize, n: usize) -> impl Iterator<Item = Word> + '_ {
+        self.iter().skip(start).take(n)
+    }
+
+    /// Whether `word` is one of the goals.
+    pub fn contains(&self, word: Word) -> bool {
+        self.allows(word) && self.goals.contains(&word)
+    }
+
+    /// How many goals there are, counted unless there are no constraints.
+    pub fn count(&self) -> usize {
+        if self.constraints.is_empty() { return self.goals.len() }
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+/// A guess played in a [`Solver`]'s game; see [`Solver::turns`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Turn {
+    pub number: usize,
+    pub guess: Word,
+    pub outcome: Outcome,
+
+    /// How many goals were left after it.
+    pub remaining: usize,
+}
+
+/// A game in progress: the goals still consistent with the feedback so far,
+/// the words that may be guessed, and the guesses played.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    // The goals the game started with, or the matrix was built over, which
+    // clones share; the goals left are indices into them, and the same
+    // goals as words for strategies, shared until the next guess narrows
+    // them.
+    words: Arc<Vec<Word>>,
+    ids: Vec<u32>,
+    goals: Arc<Vec<Word>>,
+    // Shared between clones, such as the games of a benchmark, until the
+    // allowed guesses change.
+    dict: Arc<Vec<Word>>,
+    pattern: Pattern,
+    history: Vec<(Word, Outcome)>,
+    // How many goals were left after each guess of the history.
+    remaining: Vec<usize>,
+
+    // With a matrix, over `words`, the row of each allowed guess in it.
+    matrix: Option<Arc<OutcomeMatrix>>,
+    rows: Arc<Vec<u32>>,
+
+    // With verifying, the goals and how many guesses there had been then.
+    verified: Option<(Arc<Vec<Word>>, usize)>,
+    // With guaranteeing, the most guesses each game may take.
+    guaranteed: Option<usize>,
+}
+
+impl Solver {
+    /// Starts a game where the answer is one of `goals` and any of `dict` may
+    /// be guessed.
+    pub fn new(goals: Vec<Word>, dict: Vec<Word>) -> Self {
+        Self::with_pattern(goals, dict, Pattern::new())
+    }
+
+    /// Starts from constraints known up front, such as a parsed [`Pattern`].
+    pub fn with_pattern(mut goals: Vec<Word>, dict: Vec<Word>, pattern: Pattern) -> Self {
+        pattern.retain_matching(&mut goals);
+        let goals = Arc::new(goals);
+        let mut solver = Self {
+            words: goals.clone(), ids: (0..goals.len() as u32).collect(), goals, dict: Arc::new(dict), pattern, history: Vec::new(),
+            remaining: Vec::new(), matrix: None, rows: Arc::default(), verified: None, guaranteed: None,
+        };
+        solver.drop_useless_guesses();
+        solver
+    }
+
+    /// Precomputes every outcome between the allowed guesses and the goals
+    /// left, so strategies that can use an [`OutcomeMatrix`] work by lookup.
+    pub fn with_matrix(mut self) -> Self {
+        let matrix = OutcomeMatrix::new(&self.goals, &self.dict);
+        self.words = self.goals.clone();
+        self.ids = (0..self.goals.len() as u32).collect();
+        self.rows = Arc::new(self.dict.iter().map(|&w| matrix.row(w).unwrap() as u32).collect());
+        self.matrix = Some(Arc::new(matrix));
+        self
+    }
+
+    /// Records the outcome of a guess and drops goals inconsistent with it,
+    /// and allowed guesses that can no longer tell the goals left apart.
+    pub fn refine(&mut self, guess: Word, outcome: Outcome) {
+        if let Err(divergence) = self.try_refine(guess, outcome) { panic!("{}", divergence) }
+    }
+
+    // Like refine, returning what went wrong if verifying finds the goals
+    // left aren't the right ones.
+    pub(crate) fn try_refine(&mut self, guess: Word, outcome: Outcome) -> Result<(), Divergence> {
+        self.history.push((guess, outcome));
+        self.pattern.refine(guess, outcome);
+        let (mut ids, mut goals) = (Vec::new(), Vec::new());
+        for (batch, batch_ids) in self.goals.chunks(32).zip(self.ids.chunks(32)) {
+            let mut mask = self.pattern.batch_mask(batch);
+            while mask != 0 {
+                let id = batch_ids[mask.trailing_zeros() as usize];
+                ids.push(id);
+                goals.push(self.words[id as usize]);
+                mask &= mask - 1;
+            }
+        }
+        (self.ids, self.goals) = (ids, Arc::new(goals));
+        self.remaining.push(self.goals.len());
+        self.drop_useless_guesses();
+        let Some((goals, start)) = &self.verified else { return Ok(()) };
+        let history = &self.history[*start..];
+        verify_goals("solver", goals, history, &self.goals)?;
+        if let Some(matrix) = &self.matrix {
+            let ids: Vec<Word> = self.ids.iter().map(|&id| matrix.goals()[id as usize]).collect();
+            verify_goals("matrix", goals, history, &ids)?;
+        }
+        Ok(())
+    }
+
+    /// From now on, checks after each [`refine`](Solver::refine) that the
+    /// goals left are exactly those every guess since would have gotten its
+    /// outcome against, panicking with the [`Divergence`] if not. Slow, but
+    /// catches filtering bugs, like miscounted repeated letters.
+    pub fn verifying(mut self) -> Self {
+        self.verified = Some((self.goals.clone(), self.history.len()));
+        self
+    }
+
+    /// The game so far played again over other lists, such as every allowed
+    /// word as a goal once none of the goals fit. Keeps the matrix, which is
+    /// then over the goals left, verifying from here on and guaranteeing if
+    /// this solver has them.
+    pub fn replayed(&self, goals: Vec<Word>, dict: Vec<Word>) -> Self {
+        let mut solver = Self::new(goals, dict);
+        for &(guess, outcome) in &self.history { solver.refine(guess, outcome); }
+        if self.matrix.is_some() { solver = solver.with_matrix(); }
+        if self.verified.is_some() { solver = solver.verifying(); }
+        solver.guaranteed = self.guaranteed;
+        solver
+    }
+
+    /// From now on, [`choose`](Solver::choose) refuses guesses that could
+    /// leave an answer unfound within `limit` guesses in all, taking the
+    /// safe guess leaving the fewest goals at worst instead, or the
+    /// strategy's if no guess is safe. Only the last
+    /// [`GUARANTEE_DEPTH`](crate::GUARANTEE_DEPTH) guesses are checked.
+    pub fn guaranteeing(mut self, limit: usize) -> Self {
+        self.guaranteed = Some(limit);
+        self
+    }
+
+    // Drops guesses made only of letters no goal left has, which come up all
+    // gray whatever the answer. A solver's goals only narrow, so none of
+    // these is ever useful again; widening them takes a new solver, as
+    // replayed makes.
+    fn drop_useless_guesses(&mut self) {
+        if self.goals.is_empty() { return }
+        let letters = self.goals.iter().fold(0, |letters, g| letters | g.letters);
+        self.retain_guesses(|w| w.letters & letters != 0);
+    }
+
+    /// Drops allowed guesses that don't match the pattern, as strict hard
+    /// mode requires.
+    pub fn retain_matching_guesses(&mut self) {
+        let pattern = self.pattern.clone();
+        self.retain_guesses(|w| pattern.matches(w));
+    }
+
+    /// Whether `guess` uses every hint revealed so far, as Wordle's hard mode
+    /// requires: greens stay in place and yellows are played again, though
+    /// not necessarily elsewhere, and grays may be reused.
+    pub fn is_hard_mode_guess(&self, guess: Word) -> bool {
+        self.history.iter().all(|&(played, outcome)| {
+            let mut needed = [0u8; MAX_SYMBOLS];
+            let (played, guessed) = (played.symbols(), guess.symbols());
+            for (i, &tile) in outcome.tiles().iter().enumerate() {
+                if tile == LetterOutcome::Here && guessed[i] != played[i] { return false }
+                if tile != LetterOutcome::Nowhere { needed[played[i] as usize] += 1; }
+            }
+            for &s in guessed { needed[s as usize] = needed[s as usize].saturating_sub(1); }
+            needed.iter().all(|&n| n == 0)
+        })
+    }
+
+    /// Drops allowed guesses that Wordle's hard mode would reject; see
+    /// [`is_hard_mode_guess`](Solver::is_hard_mode_guess).
+    pub fn retain_hard_mode_guesses(&mut self) {
+        let keep: Vec<bool> = self.dict.iter().map(|&w| self.is_hard_mode_guess(w)).collect();
+        let mut keep = keep.into_iter();
+        self.retain_guesses(|_| keep.next().unwrap());
+    }
+
+    // Drops allowed guesses, keeping any matrix rows in step. The lists are
+    // only copied if some are dropped.
+    fn retain_guesses(&mut self, keep: impl FnMut(Word) -> bool) {
+        let keep: Vec<bool> = self.dict.iter().copied().map(keep).collect();
+        if keep.iter().all(|&k| k) { return }
+        let kept = |i: &usize| keep[*i];
+        self.dict = Arc::new((0..self.dict.len()).filter(kept).map(|i| self.dict[i]).collect());
+        if self.matrix.is_some() { self.rows = Arc::new((0..self.rows.len()).filter(kept).map(|i| self.rows[i]).collect()); }
+    }
+
+    /// Goals still consistent with every outcome so far.
+    pub fn candidates(&self) -> &[Word] {
+        &self.goals
+    }
+
+    /// The same goals as a view to query, page through and narrow further
+    /// without copying them.
+    pub fn remaining(&self) -> Candidates<'_> {
+        Candidates { goals: &self.goals, constraints: Vec::new() }
+    }
+
+    /// Words that may be guessed.
+    pub fn dict(&self) -> &[Word] {
+        &self.dict
+    }
+
+    /// What is known about the answer so far.
+    pub fn pattern(&self) -> &Pattern {
+        &self.pattern
+    }
+
+    /// Each guess played, with its outcome, in order.
+    pub fn history(&self) -> &[(Word, Outcome)] {
+        &self.history
+    }
+
+    /// The same guesses, numbered from 1, with how many goals each left.
+    pub fn turns(&self) -> Vec<Turn> {
+        self.history.iter().zip(&self.remaining).enumerate().map(|(i, (&(guess, outcome), &remaining))| {
+            Turn { number: i + 1, guess, outcome, remaining }
+        }).collect()
+    }
+
+    /// The guess `strategy` recommends, or `None` if no goals remain.
+    pub fn choose(&self, strategy: &dyn Strategy) -> Option<Word> {
+        if self.goals.is_empty() { return None }
+        let guess = match self.matrix.as_ref().and_then(|m| strategy.choose_indexed(m, &self.ids, &self.rows)) {
+            Some(guess) => guess,
+            None => strategy.choose(&self.goals, &self.dict, &self.pattern),
+        };
+        let Some(limit) = self.guaranteed else { return Some(guess) };
+        let left = limit.saturating_sub(self.history.len());
+        Some(safe_guess(guess, &self.goals, &self.dict, left).unwrap_or(guess))
+    }
+
+    /// Plays `strategy`'s recommendations against `goal`, falling back to
+    /// [`Quick`] while there are too many candidates for it, until `goal` is
+    /// guessed. Returns how many guesses that took, or `None` if `goal`
+    /// isn't one of the candidates or wasn't found within `limit` guesses.
+    /// The game is left in [`history`](Solver::history).
+    pub fn play(&mut self, goal: Word, strategy: &dyn Strategy, limit: usize) -> Option<usize> {
+        if !self.goals.contains(&goal) { return None }
+        self.play_against(|_, guess| Outcome::compare(goal, guess), strategy, limit)
+    }
+
+    /// Like [`play`](Solver::play), but `host` scores each guess given the
+    /// candidates left, so it needn't have settled on a goal; see
+    /// [`adversarial_outcome`](crate::adversarial_outcome). `None` if the
+    /// host's outcomes leave no candidates.
+    pub fn play_against(&mut self, mut host: impl FnMut(&[Word], Word) -> Outcome, strategy: &dyn Strategy,
+                        limit: usize) -> Option<usize> {
+        for n in 1..=limit {
+            let strategy = if self.goals.len() > strategy.max_candidates() { &Quick } else { strategy };
+            let guess = self.choose(strategy)?;
+            let outcome = host(&self.goals, guess);
+            self.refine(guess, outcome);
+            if outcome.is_win() { return Some(n) }
+        }
+        None
+    }
+
+    /// The `n` allowed guesses leaving the fewest goals in the worst case,
+    /// then the fewest expected, with candidates first among equals. Each
+    /// comes with those two numbers.
+    pub fn ranked_guesses(&self, n: usize, progress_every: usize) -> Vec<(Word, usize, f64)> {
+        let mut ranked = self.scored_guesses(progress_every);
+        let candidates: HashSet<Word> = self.goals.iter().copied().collect();
+        ranked.sort_by(|a, b| {
+            a.1.cmp(&b.1).then(a.2.total_cmp(&b.2))
+                .then(candidates.contains(&b.0).cmp(&candidates.contains(&a.0))).then(a.0.cmp(&b.0))
+        });
+        ranked.dedup_by_key(|r| r.0);
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// Where `guess` would come in [`ranked_guesses`](Solver::ranked_guesses),
+    /// from 1, whether or not it's allowed: one more than the allowed guesses
+    /// leaving fewer goals in the worst case, or as few and fewer expected.
+    pub fn rank(&self, guess: Word) -> usize {
+        let counts = outcome_counts(guess, &self.goals);
+        let worst = *counts.iter().max().unwrap_or(&0) as usize;
+        let expected = counts.iter().map(|&c| c as u64 * c as u64).sum::<u64>() as f64 / self.goals.len().max(1) as f64;
+        1 + self.scored_guesses(usize::MAX).iter().filter(|&&(w, other_worst, other_expected)| {
+            w != guess && (other_worst, other_expected) < (worst, expected)
+        }).count()
+    }
+
+    // Every allowed guess with the most goals it could leave and how many
+    // it's expected to, in the order of the allowed guesses.
+    fn scored_guesses(&self, progress_every: usize) -> Vec<(Word, usize, f64)> {
+        let total = self.goals.len().max(1) as f64;
+        let positions: Vec<usize> = (0..self.dict.len()).collect();
+        score_all(&positions, progress_every, |i| {
+            let counts = match &self.matrix {
+                Some(matrix) => matrix.outcome_counts(self.rows[i] as usize, &self.ids),
+                None => outcome_counts(self.dict[i], &self.goals),
+            };
+            let sum_of_squares: u64 = counts.iter().map(|&c| c as u64 * c as u64).sum();
+            (*counts.iter().max().unwrap_or(&0) as usize, sum_of_squares as f64 / total)
+        }).into_iter().map(|(i, (worst, expected))| (self.dict[i], worst, expected)).collect()
+    }
+
+    /// How well `guess` would split the remaining goals; see [`guess_confidence`].
+    pub fn confidence(&self, guess: Word) -> i32 {
+        match self.matrix.as_ref().and_then(|m| Some((m, m.row(guess)?))) {
+            Some((matrix, row)) => -(matrix.worst_bucket(row, &self.ids) as i32),
+            None => guess_confidence(&self.pattern, &self.goals, guess),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<Word> {
+        text.split(' ').map(Word::new).collect()
+    }
+
+    #[test]
+    fn replaying_keeps_the_settings() {
+        let goals = words("cigar rebut sissy humph awake blush focal");
+        let dict = words("crane toils acnes");
+        let mut solver = Solver::new(goals, dict.clone()).with_matrix().verifying().guaranteeing(6);
+        solver.refine(Word::new("crane"), "ybyyy".parse().unwrap());
+        assert!(solver.candidates().is_empty());
+        let widened = solver.replayed(dict.clone(), dict);
+        assert_eq!(widened.candidates(), &[Word::new("acnes")]);
+        assert_eq!(widened.history(), solver.history());
+        assert_eq!(widened.ids, vec![0]);
+        assert!(widened.matrix.is_some() && widened.verified.is_some());
+        assert_eq!(widened.guaranteed, Some(6));
+        let plain = Solver::new(words("cigar"), words("cigar")).replayed(words("rebut"), words("rebut"));
+        assert!(plain.matrix.is_none() && plain.verified.is_none() && plain.guaranteed.is_none());
+    }
+
+    #[test]
+    fn clones_share_the_words() {
+        let goals = words("cigar rebut sissy humph awake blush focal");
+        let solver = Solver::new(goals.clone(), goals);
+        let mut clone = solver.clone();
+        clone.refine(Word::new("humph"), Outcome::compare(Word::new("rebut"), Word::new("humph")));
+        assert!(Arc::ptr_eq(&solver.words, &clone.words));
+        assert_eq!(clone.ids, [1]);
+        assert_eq!(clone.candidates(), &[Word::new("rebut")]);
+        assert_eq!(solver.candidates().len(), 7);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::analysis::{expected_remaining, outcome_entropy, weighted_entropy, weighted_remaining};
+use crate::matrix::OutcomeMatrix;
+use crate::pattern::Pattern;
+use crate::priors::Priors;
+use crate::solver::{limited_guess, lookahead_guess_in, quick_guess, SearchLimits};
+use crate::transposition::TranspositionTable;
+use crate::word::Word;
+use std::cmp::Ordering;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A way of picking the next guess. Strategies are shared between threads,
+/// e.g. by [`bench`](crate::bench).
+pub trait Strategy: Sync {
+    /// Short name used to select the strategy, e.g. on the command line.
+    fn name(&self) -> &'static str;
+
+    /// Picks a guess from `dict` given the `candidates` still possible and
+    /// what is known about the answer. `candidates` is never empty.
+    fn choose(&self, candidates: &[Word], dict: &[Word], pattern: &Pattern) -> Word;
+
+    /// Like [`choose`](Strategy::choose), but with `candidates` given as goal
+    /// columns and the allowed guesses as `rows` of a precomputed matrix.
+    /// `None` if the strategy has no faster path this way.
+    fn choose_indexed(&self, _matrix: &OutcomeMatrix, _candidates: &[u32], _rows: &[u32]) -> Option<Word> {
+        None
+    }
+
+    /// Largest candidate set the strategy is practical for; callers may skip
+    /// or substitute a cheaper strategy above it.
+    fn max_candidates(&self) -> usize {
+        usize::MAX
+    }
+
+    /// Whether the strategy always picks the same guess in the same position,
+    /// so its choices can be cached or booked; true by default.
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+/// Minimizes the worst-case number of remaining candidates; see
+/// [`recommended_guess`](crate::recommended_guess).
+#[derive(Debug, Clone)]
+pub struct Minimax {
+    /// Redraw the progress bar on stderr after this many guesses are evaluated.
+    pub progress_every: usize,
+
+    /// 1 to look only at the next guess, 2 to break near-ties by the best
+    /// follow-up; see [`lookahead_guess`].
+    pub depth: usize,
+
+    /// At depth 1, settle for the best guess found in this long; see
+    /// [`SearchLimits`].
+    pub budget: Option<Duration>,
+
+    /// At depth 1, only score this many of the guesses that aren't
+    /// candidates, the most promising by a cheap heuristic.
+    pub prune: Option<usize>,
+
+    /// Where to remember the guesses found for positions, and the best
+    /// follow-ups a lookahead finds, so they're searched once.
+    pub table: Option<Arc<TranspositionTable>>,
+}
+
+impl Minimax {
+    fn limits(&self) -> SearchLimits {
+        SearchLimits { budget: self.budget, prune: self.prune }
+    }
+
+    // The guess `search` finds for the position, remembered in the table if
+    // there is one. Searches cut short by a budget aren't remembered, as
+    // another might find better.
+    fn remembered(&self, candidates: &[Word], guesses: &[Word], search: impl FnOnce() -> Word) -> Word {
+        match &self.table {
+            Some(table) if self.budget.is_none() => {
+                let kind = format!("minimax depth {} prune {:?}", self.depth.min(2), self.prune);
+                table.get_or_search(TranspositionTable::key(&kind, candidates, guesses), || (search(), 0)).0
+            },
+            _ => search(),
+        }
+    }
+}
+
+impl Default for Minimax {
+    fn default() -> Self {
+        Self { progress_every: 100, depth: 1, budget: None, prune: None, table: None }
+    }
+}
+
+impl Strategy for Minimax {
+    fn name(&self) -> &'static str {
+        "minimax"
+    }
+
+    fn choose(&self, candidates: &[Word], dict: &[Word], pattern: &Pattern) -> Word {
+        self.remembered(candidates, dict, || {
+            if self.depth >= 2 { return lookahead_guess_in(pattern, candidates, dict, self.progress_every, self.table.as_deref()).0 }
+            limited_guess(pattern, candidates, dict, self.progress_every, self.limits()).0
+        })
+    }
+
+    fn choose_indexed(&self, matrix: &OutcomeMatrix, candidates: &[u32], rows: &[u32]) -> Option<Word> {
+        if self.depth >= 2 { return None }
+        let search = || matrix.guesses()[matrix.minimax(candidates, rows, self.progress_every, self.limits()).0];
+        if self.table.is_none() { return Some(search()) }
+        let goals: Vec<Word> = candidates.iter().map(|&id| matrix.goals()[id as usize]).collect();
+        let guesses: Vec<Word> = rows.iter().map(|&row| matrix.guesses()[row as usize]).collect();
+        Some(self.remembered(&goals, &guesses, search))
+    }
+
+    // A budget keeps any number practical.
+    fn max_candidates(&self) -> usize {
+        if self.budget.is_some() && self.depth < 2 { usize::MAX } else { 1000 }
+    }
+}
+
+/// Letter-frequency heuristics; see [`quick_guess`].
+#[derive(Debug, Clone, Default)]
+pub struct Quick;
+
+impl Strategy for Quick {
+    fn name(&self) -> &'static str {
+        "quick"
+    }
+
+    fn choose(&self, candidates: &[Word], dict: &[Word], pattern: &Pattern) -> Word {
+        quick_guess(pattern, candidates, dict)
+    }
+}
+
+// The highest-scoring guess from either list. Among guesses within rounding
+// of each other, candidates win, since they might be the answer, and then
+// the alphabetically first.
+pub(crate) fn best_by(candidates: &[Word], dict: &[Word], score: impl Fn(Word) -> f64) -> Word {
+    let is_candidate: HashSet<Word> = candidates.iter().copied().collect();
+    dict.iter().chain(candidates).map(|&g| (g, score(g), is_candidate.contains(&g)))
+        .max_by(|a, b| match a.1 - b.1 {
+            d if d.abs() < 1e-9 => a.2.cmp(&b.2).then(b.0.cmp(&a.0)),
+            d => d.partial_cmp(&0.0).unwrap_or(Ordering::Equal),
+        })
+        .unwrap().0
+}
+
+/// Maximizes the expected information from the outcome: the Shannon entropy
+/// of its distribution over the candidates.
+#[derive(Debug, Clone, Default)]
+pub struct Entropy {
+    /// How likely each candidate is, if not uniformly.
+    pub priors: Option<Arc<Priors>>,
+}
+
+impl Strategy for Entropy {
+    fn name(&self) -> &'static str {
+        "entropy"
+    }
+
+    fn choose(&self, candidates: &[Word], dict: &[Word], _pattern: &Pattern) -> Word {
+        match &self.priors {
+            Some(priors) => best_by(candidates, dict, |g| weighted_entropy(g, candidates, priors)),
+            None => best_by(candidates, dict, |g| outcome_entropy(g, candidates)),
+        }
+    }
+}
+
+/// Guesses a candidate drawn at random, weighted by the priors if given: a
+/// strategy whose games vary, to be judged by [`simulate`](crate::simulate)
+/// rather than by a single [`bench`](crate::bench).
+#[derive(Debug)]
+pub struct Sampled {
+    /// How likely each candidate is, if not uniformly.
+    pub priors: Option<Arc<Priors>>,
+    rng: Mutex<StdRng>,
+}
+
+impl Sampled {
+    /// Draws from a generator seeded with `seed`, so the same seed plays the
+    /// same games.
+    pub fn new(seed: u64, priors: Option<Arc<Priors>>) -> Self {
+        Self { priors, rng: Mutex::new(StdRng::seed_from_u64(seed)) }
+    }
+}
+
+impl Strategy for Sampled {
+    fn name(&self) -> &'static str {
+        "sampled"
+    }
+
+    fn choose(&self, candidates: &[Word], _dict: &[Word], _pattern: &Pattern) -> Word {
+        let weights: Vec<f64> = candidates.iter().map(|&c| self.priors.as_ref().map_or(1.0, |p| p.weight(c))).collect();
+        let total: f64 = weights.iter().sum();
+        let mut rng = self.rng.lock().unwrap();
+        if total <= 0.0 { return candidates[rng.gen_range(0..candidates.len())] }
+        let mut point = rng.gen::<f64>() * total;
+        for (&candidate, &weight) in candidates.iter().zip(&weights) {
+            if point < weight { return candidate }
+            point -= weight;
+        }
+        *candidates.last().unwrap()
+    }
+
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+}
+
+/// Minimizes the average number of candidates left, rather than the worst case.
+#[derive(Debug, Clone, Default)]
+pub struct Expected {
+    /// How likely each candidate is, if not uniformly.
+    pub priors: Option<Arc<Priors>>,
+}
+
+impl Strategy for Expected {
+    fn name(&self) -> &'static str {
+        "expected"
+    }
+
+    fn choose(&self, candidates: &[Word], dict: &[Word], _pattern: &Pattern) -> Word {
+        match &self.priors {
+            Some(priors) => best_by(candidates, dict, |g| -weighted_remaining(g, candidates, priors)),
+            None => best_by(candidates, dict, |g| -expected_remaining(g, candidates)),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::str;
+use wordlesolve::{alphabet, LetterOutcome, Outcome};
+
+// A key read from a terminal in raw mode, or a click or a report of where
+// the cursor is, counting rows and columns from 1 at the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Left,
+    Right,
+    Up,
+    Down,
+    Enter,
+    Backspace,
+    Tab,
+    // A letter typed with control held.
+    Ctrl(char),
+    Click { row: usize, column: usize },
+    Position { row: usize, column: usize },
+    // An escape sequence that isn't one of the keys above.
+    Other,
+}
+
+// The terminal on standard input, reading a key at a time without echoing
+// them until dropped, control-C and the like included, so they can't stop
+// the program before it's put back. stty does the switching, so there's
+// nothing to link.
+pub struct RawMode {
+    saved: String,
+    mouse: bool,
+}
+
+impl RawMode {
+    pub fn new() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+        Ok(Self { saved, mouse: false })
+    }
+
+    // Raw mode with clicks reported as keys too, in the SGR encoding that
+    // works past column 223.
+    pub fn with_mouse() -> io::Result<Self> {
+        let mut raw = Self::new()?;
+        raw.mouse = true;
+        print!("\x1b[?1000h\x1b[?1006h");
+        io::stdout().flush()?;
+        Ok(raw)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if self.mouse {
+            print!("\x1b[?1006l\x1b[?1000l");
+            let _ = io::stdout().flush();
+        }
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+// Runs stty on the terminal with `args`, returning what it printed.
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty").args(args).stdin(File::open("/dev/tty")?).stderr(Stdio::null()).output()?;
+    if !output.status.success() { return Err(io::Error::other("stty failed")) }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// The next key typed on `input`, or None at its end.
+pub fn read_key(input: &mut impl Read) -> io::Result<Option<Key>> {
+    let Some(first) = read_byte(input)? else { return Ok(None) };
+    let key = match first {
+        b'\n' | b'\r' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        b'\t' => Key::Tab,
+        b @ 0x01..=0x1a => Key::Ctrl((b'a' + b - 1) as char),
+        0x1b => match read_byte(input)? {
+            Some(b'[') | Some(b'O') => {
+                // Parameters, then a final byte from @ to ~.
+                let mut params = Vec::new();
+                let mut last = b'~';
+                while let Some(b) = read_byte(input)? {
+                    if (0x40..=0x7e).contains(&b) { last = b; break }
+                    params.push(b);
+                }
+                let numbers: Vec<usize> = String::from_utf8_lossy(&params).trim_start_matches('<').split(';')
+                    .filter_map(|n| n.parse().ok()).collect();
+                match (last, params.first(), &numbers[..]) {
+                    // A press of the left button; releases end in m.
+                    (b'M', Some(b'<'), &[0, column, row]) => Key::Click { row, column },
+                    (b'R', _, &[row, column]) => Key::Position { row, column },
+                    (b'A', ..) => Key::Up,
+                    (b'B', ..) => Key::Down,
+                    (b'C', ..) => Key::Right,
+                    (b'D', ..) => Key::Left,
+                    _ => Key::Other,
+                }
+            },
+            _ => Key::Other,
+        },
+        b => {
+            // The rest of a UTF-8 character.
+            let mut bytes = vec![b];
+            let len = match b { 0xc0..=0xdf => 2, 0xe0..=0xef => 3, 0xf0..=0xf7 => 4, _ => 1 };
+            while bytes.len() < len {
+                let Some(b) = read_byte(input)? else { break };
+                bytes.push(b);
+            }
+            str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()).map_or(Key::Other, Key::Char)
+        },
+    };
+    Ok(Some(key))
+}
+
+// The row the cursor is on, as the terminal reports it, or None if it
+// doesn't; keys typed before the report are dropped.
+pub fn cursor_row(input: &mut impl Read) -> io::Result<Option<usize>> {
+    print!("\x1b[6n");
+    io::stdout().flush()?;
+    while let Some(key) = read_key(input)? {
+        if let Key::Position { row, .. } = key { return Ok(Some(row)) }
+    }
+    Ok(None)
+}
+
+// A key named like "tab", "ctrl-n" or a character, e.g. for a binding in
+// the config file.
+pub fn parse_key(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_control() && c != ' ' => return Some(Key::Char(c)),
+        _ => {},
+    }
+    if name == "tab" { return Some(Key::Tab) }
+    let letter = name.strip_prefix("ctrl-").filter(|l| l.len() == 1)?.chars().next()?;
+    // These are backspace, tab, and enter twice over.
+    Some(Key::Ctrl(letter)).filter(|_| letter.is_ascii_lowercase() && !"hijm".contains(letter))
+}
+
+// The character a key types on a line read the usual way, if it types one.
+pub fn key_char(key: Key) -> Option<char> {
+    match key {
+        Key::Char(c) => Some(c),
+        Key::Tab => Some('\t'),
+        Key::Ctrl(letter) => Some((letter as u8 - b'a' + 1) as char),
+        _ => None,
+    }
+}
+
+fn read_byte(input: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0];
+    Ok(if input.read(&mut byte)? == 0 { None } else { Some(byte[0]) })
+}
+
+// The tiles of a guess being picked key by key: left and right move between
+// them, space or up and down change the color of the one at the cursor, and
+// g, y or b set it and move on. For a right-to-left alphabet the tiles are
+// drawn that way, so left moves on to the next and right back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TilePicker {
+    pub tiles: Vec<LetterOutcome>,
+    pub cursor: usize,
+    pub rtl: bool,
+}
+
+// What a key did to a TilePicker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Picked {
+    Editing,
+    Done(Outcome),
+    Cancelled,
+}
+
+impl TilePicker {
+    // Gray tiles for a word of `length` letters, the cursor on the first.
+    pub fn new(length: usize) -> Self {
+        Self { tiles: vec![LetterOutcome::Nowhere; length], cursor: 0, rtl: alphabet().is_rtl() }
+    }
+
+    // Enter takes the tiles, and backspace gives up on them.
+    pub fn press(&mut self, key: Key) -> Picked {
+        let last = self.tiles.len() - 1;
+        let key = match key {
+            Key::Left if self.rtl => Key::Right,
+            Key::Right if self.rtl => Key::Left,
+            key => key,
+        };
+        match key {
+            Key::Left => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right => self.cursor = (self.cursor + 1).min(last),
+            Key::Char(' ') | Key::Up => self.cycle(1),
+            Key::Down => self.cycle(2),
+            Key::Char(c) => if let Some(tile) = LetterOutcome::from_letter(c) {
+                self.tiles[self.cursor] = tile;
+                self.cursor = (self.cursor + 1).min(last);
+            },
+            Key::Enter => return Picked::Done(Outcome::new(&self.tiles)),
+            Key::Backspace | Key::Ctrl('c') => return Picked::Cancelled,
+            Key::Tab | Key::Ctrl(_) | Key::Click { .. } | Key::Position { .. } | Key::Other => {},
+        }
+        Picked::Editing
+    }
+
+    // A click on a tile moves the cursor there and changes its color.
+    pub fn click(&mut self, tile: usize) {
+        self.cursor = tile;
+        self.cycle(1);
+    }
+
+    // Turns the cursor's tile from gray to yellow to green and round again,
+    // `steps` at a time.
+    pub fn cycle(&mut self, steps: usize) {
+        const ORDER: [LetterOutcome; 3] = [LetterOutcome::Nowhere, LetterOutcome::Elsewhere, LetterOutcome::Here];
+        let tile = &mut self.tiles[self.cursor];
+        *tile = ORDER[(ORDER.iter().position(|t| t == tile).unwrap() + steps) % ORDER.len()];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_keys_and_escape_sequences() {
+        let mut input: &[u8] = b"a\x1b[D\x1b[C\x1bOA\x1b[B \n\x7f\x1b[1;5H\xc3\xa9\x1b[<0;12;3M\x1b[<0;12;3m\x1b[7;1R";
+        let mut keys = Vec::new();
+        while let Some(key) = read_key(&mut input).unwrap() { keys.push(key); }
+        assert_eq!(keys, [Key::Char('a'), Key::Left, Key::Right, Key::Up, Key::Down, Key::Char(' '), Key::Enter, Key::Backspace,
+                          Key::Other, Key::Char('é'), Key::Click { row: 3, column: 12 }, Key::Other, Key::Position { row: 7, column: 1 }]);
+    }
+
+    #[test]
+    fn names_keys() {
+        assert_eq!(parse_key("tab"), Some(Key::Tab));
+        assert_eq!(parse_key("ctrl-n"), Some(Key::Ctrl('n')));
+        assert_eq!(parse_key("u"), Some(Key::Char('u')));
+        assert_eq!(parse_key("ctrl-m"), None);
+        assert_eq!(parse_key("undo"), None);
+        assert_eq!(key_char(Key::Ctrl('n')), Some('\x0e'));
+        let mut input: &[u8] = b"\t\x0e\x03";
+        let keys: Vec<Key> = std::iter::from_fn(|| read_key(&mut input).unwrap()).collect();
+        assert_eq!(keys, [Key::Tab, Key::Ctrl('n'), Key::Ctrl('c')]);
+    }
+
+    #[test]
+    fn picks_tiles() {
+        let mut picker = TilePicker::new(5);
+        for key in [Key::Char(' '), Key::Right, Key::Up, Key::Up, Key::Right, Key::Right, Key::Char('y'), Key::Char('g'), Key::Char('g'),
+                    Key::Left, Key::Down] {
+            assert_eq!(picker.press(key), Picked::Editing);
+        }
+        assert_eq!(picker.cursor, 3);
+        assert_eq!(picker.press(Key::Enter), Picked::Done("ygbbg".parse().unwrap()));
+        assert_eq!(picker.press(Key::Backspace), Picked::Cancelled);
+        picker.click(1);
+        picker.click(1);
+        assert_eq!(picker.cursor, 1);
+        assert_eq!(picker.press(Key::Enter), Picked::Done("yybbg".parse().unwrap()));
+        let mut picker = TilePicker { rtl: true, ..TilePicker::new(5) };
+        for key in [Key::Left, Key::Left, Key::Char('g'), Key::Right, Key::Right, Key::Char('y')] { picker.press(key); }
+        assert_eq!(picker.press(Key::Enter), Picked::Done("bygbb".parse().unwrap()));
+    }
+}
@@ -0,0 +1,89 @@
+use std::str::FromStr;
+use wordlesolve::LetterOutcome;
+
+// The colors tiles, keys and the --tui panels' headings are drawn in, as
+// 256-color numbers: each tile's background, the letters on the tiles and
+// the headings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub here: u8,
+    pub elsewhere: u8,
+    pub nowhere: u8,
+    pub letters: u8,
+    pub heading: u8,
+}
+
+impl Default for Theme {
+    // The game's own colors.
+    fn default() -> Self {
+        Self { here: 28, elsewhere: 178, nowhere: 240, letters: 15, heading: 15 }
+    }
+}
+
+impl Theme {
+    // The game's high contrast colors, orange for right and blue for
+    // elsewhere, which tell apart without seeing green from yellow.
+    pub const HIGH_CONTRAST: Self = Self { here: 209, elsewhere: 111, nowhere: 238, letters: 15, heading: 209 };
+
+    // Ethan Schoonover's Solarized accents on its dark base.
+    pub const SOLARIZED: Self = Self { here: 64, elsewhere: 136, nowhere: 240, letters: 230, heading: 33 };
+
+    // `text` on a tile of `outcome`'s color.
+    pub fn tile(&self, outcome: LetterOutcome, text: &str) -> String {
+        let background = match outcome {
+            LetterOutcome::Here => self.here,
+            LetterOutcome::Elsewhere => self.elsewhere,
+            LetterOutcome::Nowhere => self.nowhere,
+        };
+        format!("\x1b[1;38;5;{};48;5;{}m{}\x1b[0m", self.letters, background, text)
+    }
+
+    pub fn heading(&self, text: &str) -> String {
+        format!("\x1b[1;38;5;{}m{}\x1b[0m", self.heading, text)
+    }
+}
+
+// A theme by name, or colors for the tiles right, elsewhere and nowhere,
+// then optionally for their letters and the headings, e.g. "28,178,240".
+impl FromStr for Theme {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, ()> {
+        match text {
+            "default" => return Ok(Self::default()),
+            "high-contrast" => return Ok(Self::HIGH_CONTRAST),
+            "solarized" => return Ok(Self::SOLARIZED),
+            _ => {},
+        }
+        let colors: Vec<u8> = text.split(',').map(|c| c.trim().parse().map_err(drop)).collect::<Result<_, _>>()?;
+        let default = Self::default();
+        match colors[..] {
+            [here, elsewhere, nowhere] => Ok(Self { here, elsewhere, nowhere, ..default }),
+            [here, elsewhere, nowhere, letters] => Ok(Self { here, elsewhere, nowhere, letters, ..default }),
+            [here, elsewhere, nowhere, letters, heading] => Ok(Self { here, elsewhere, nowhere, letters, heading }),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_themes() {
+        assert_eq!("default".parse(), Ok(Theme::default()));
+        assert_eq!("solarized".parse(), Ok(Theme::SOLARIZED));
+        assert_eq!("22, 94,236".parse(), Ok(Theme { here: 22, elsewhere: 94, nowhere: 236, ..Theme::default() }));
+        assert_eq!("22,94,236,0,21".parse(), Ok(Theme { here: 22, elsewhere: 94, nowhere: 236, letters: 0, heading: 21 }));
+        assert_eq!("22,94".parse::<Theme>(), Err(()));
+        assert_eq!("22,94,256".parse::<Theme>(), Err(()));
+        assert_eq!("dark".parse::<Theme>(), Err(()));
+    }
+
+    #[test]
+    fn draws_tiles() {
+        assert_eq!(Theme::HIGH_CONTRAST.tile(LetterOutcome::Here, " A "), "\x1b[1;38;5;15;48;5;209m A \x1b[0m");
+        assert_eq!(Theme::default().heading("board"), "\x1b[1;38;5;15mboard\x1b[0m");
+    }
+}
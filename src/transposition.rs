@@ -0,0 +1,146 @@
+use crate::cache::{fnv, list_hash};
+use crate::metrics::{count, Counter};
+use crate::word::Word;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Search results keyed on the position searched, so a position reached
+/// again, by guesses in another order, in a lookahead or in another game, is
+/// looked up instead of searched. Shared between threads, and between
+/// strategies and searches through an `Arc`.
+///
+/// Each result is a guess and its score, whose meaning depends on the kind
+/// of search, which is part of the key along with the candidates and the
+/// allowed guesses. Keys are 64-bit hashes, so two positions colliding is
+/// vanishingly unlikely, but not impossible.
+#[derive(Debug, Default)]
+pub struct TranspositionTable {
+    entries: Mutex<HashMap<u64, (Word, i32)>>,
+    hits: AtomicUsize,
+}
+
+impl TranspositionTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The key for a search of `kind`, e.g. "minimax", among `candidates`
+    /// with `guesses` allowed, whatever order either is in.
+    pub fn key(kind: &str, candidates: &[Word], guesses: &[Word]) -> u64 {
+        Self::key_hashed(kind, candidates, Self::hash(guesses))
+    }
+
+    // Like key, with the guesses already hashed, since searches tend to look
+    // up many candidate sets with the same guesses.
+    pub(crate) fn key_hashed(kind: &str, candidates: &[Word], guesses: u64) -> u64 {
+        fnv(kind.bytes().chain(list_hash(candidates.iter()).to_le_bytes()).chain(guesses.to_le_bytes()))
+    }
+
+    pub(crate) fn hash(words: &[Word]) -> u64 {
+        list_hash(words.iter())
+    }
+
+    /// The result stored under `key`, or the one `search` finds, stored. The
+    /// table isn't locked during the search, so threads searching the same
+    /// position at once may both search it.
+    pub fn get_or_search(&self, key: u64, search: impl FnOnce() -> (Word, i32)) -> (Word, i32) {
+        if let Some(found) = self.get(key) { return found }
+        let found = search();
+        self.insert(key, found);
+        found
+    }
+
+    /// The result stored under `key`, if any.
+    pub fn get(&self, key: u64) -> Option<(Word, i32)> {
+        let found = self.entries.lock().unwrap().get(&key).copied();
+        count(Counter::TableLookups, 1);
+        if found.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            count(Counter::TableHits, 1);
+        }
+        found
+    }
+
+    /// Stores a result under `key`.
+    pub fn insert(&self, key: u64, result: (Word, i32)) {
+        self.entries.lock().unwrap().insert(key, result);
+    }
+
+    /// How many positions are stored.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many lookups found a stored result.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Writes every result to the file at `path`, a line each, so a long
+    /// search can be resumed with [`load`](TranspositionTable::load).
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let text: String = self.entries.lock().unwrap().iter()
+            .map(|(key, (guess, score))| format!("{:016x} {} {}\n", key, guess, score)).collect();
+        if let Some(dir) = std::path::Path::new(path).parent() { fs::create_dir_all(dir)?; }
+        // Written aside first, so a search stopped while saving keeps the
+        // last whole file.
+        let partial = format!("{}.partial", path);
+        fs::write(&partial, text)?;
+        fs::rename(partial, path)
+    }
+
+    /// Adds the results [`save`](TranspositionTable::save) wrote to `path`,
+    /// returning how many there were. Lines that can't be read are skipped.
+    pub fn load(&self, path: &str) -> io::Result<usize> {
+        let text = fs::read_to_string(path)?;
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.extend(text.lines().filter_map(|line| {
+            let mut fields = line.split(' ');
+            let key = u64::from_str_radix(fields.next()?, 16).ok()?;
+            Some((key, (Word::parse(fields.next()?)?, fields.next()?.parse().ok()?)))
+        }));
+        Ok(entries.len() - before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_ignore_order() {
+        let (a, b, c) = (Word::new("cigar"), Word::new("rebut"), Word::new("sissy"));
+        assert_eq!(TranspositionTable::key("minimax", &[a, b], &[c, a]), TranspositionTable::key("minimax", &[b, a], &[a, c]));
+        assert_ne!(TranspositionTable::key("minimax", &[a, b], &[c]), TranspositionTable::key("optimal", &[a, b], &[c]));
+        assert_ne!(TranspositionTable::key("minimax", &[a, b], &[c]), TranspositionTable::key("minimax", &[a, c], &[c]));
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let table = TranspositionTable::new();
+        let results = [(1, (Word::new("cigar"), -3)), (u64::MAX, (Word::new("rebut"), 12)), (0xabc, (Word::new("sissy"), 0))];
+        for &(key, result) in &results { table.insert(key, result); }
+        let path = std::env::temp_dir().join(format!("wordlesolve-table-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        table.save(path).unwrap();
+        let mut text = fs::read_to_string(path).unwrap();
+        text.push_str("not a line\nzz cigar 1\n");
+        fs::write(path, text).unwrap();
+        let loaded = TranspositionTable::new();
+        assert_eq!(loaded.load(path).unwrap(), results.len());
+        fs::remove_file(path).unwrap();
+        for &(key, result) in &results { assert_eq!(loaded.get(key), Some(result)); }
+        assert_eq!(loaded.get(2), None);
+        assert_eq!(loaded.hits(), results.len());
+        assert_eq!(loaded.get_or_search(2, || (Word::new("cigar"), 7)), (Word::new("cigar"), 7));
+        assert_eq!(loaded.get_or_search(2, || unreachable!()), (Word::new("cigar"), 7));
+    }
+}
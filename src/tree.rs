@@ -0,0 +1,300 @@
+use crate::matrix::OutcomeMatrix;
+use crate::outcome::Outcome;
+use crate::pattern::Pattern;
+use crate::solver::{score_all, Solver};
+use crate::strategy::{Quick, Strategy};
+use crate::word::Word;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// A strategy's whole plan for a game: the guess to play, then for each
+/// outcome it could get besides a win, the plan for the goals that leaves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionTree {
+    /// The guess played here.
+    pub guess: Word,
+
+    /// How many goals are still possible before the guess.
+    pub candidates: usize,
+
+    /// Whether the guess is one of them, so wins for that goal.
+    pub wins: bool,
+
+    /// The plan after each outcome other than a win, in order of
+    /// [`Outcome::code`].
+    pub branches: Vec<(Outcome, DecisionTree)>,
+}
+
+impl DecisionTree {
+    /// Expands `strategy` from `solver`'s position over every candidate,
+    /// falling back to [`Quick`] while there are too many candidates for it,
+    /// as [`Solver::play`] does. Branches still unsolved after `limit`
+    /// guesses are left out. `None` if there are no candidates.
+    ///
+    /// The branches after the first guess are expanded on all available cores.
+    pub fn build(solver: &Solver, strategy: &dyn Strategy, limit: usize) -> Option<Self> {
+        let (guess, outcomes) = Self::expand(solver, strategy, limit)?;
+        let branches = score_all(&outcomes, usize::MAX, |outcome| Self::after(solver, guess, outcome, strategy, limit - 1));
+        Some(Self::node(solver, guess, branches))
+    }
+
+    // The guess to play and the outcomes it leads on from, if any.
+    fn expand(solver: &Solver, strategy: &dyn Strategy, limit: usize) -> Option<(Word, Vec<Outcome>)> {
+        if limit == 0 { return None }
+        let strategy = if solver.candidates().len() > strategy.max_candidates() { &Quick } else { strategy };
+        let guess = solver.choose(strategy)?;
+        let mut outcomes: Vec<Outcome> = solver.candidates().iter().map(|&goal| Outcome::compare(goal, guess))
+            .filter(|o| !o.is_win()).collect();
+        outcomes.sort_by_key(Outcome::code);
+        outcomes.dedup();
+        Some((guess, outcomes))
+    }
+
+    // The tree for the position after `guess` gets `outcome`.
+    fn after(solver: &Solver, guess: Word, outcome: Outcome, strategy: &dyn Strategy, limit: usize) -> Option<Self> {
+        let mut solver = solver.clone();
+        solver.refine(guess, outcome);
+        let (guess, outcomes) = Self::expand(&solver, strategy, limit)?;
+        let branches = outcomes.into_iter().map(|o| (o, Self::after(&solver, guess, o, strategy, limit - 1))).collect();
+        Some(Self::node(&solver, guess, branches))
+    }
+
+    fn node(solver: &Solver, guess: Word, branches: Vec<(Outcome, Option<Self>)>) -> Self {
+        let branches = branches.into_iter().filter_map(|(o, tree)| Some((o, tree?))).collect();
+        Self { guess, candidates: solver.candidates().len(), wins: solver.candidates().contains(&guess), branches }
+    }
+
+    /// The most guesses any goal in the tree takes.
+    pub fn depth(&self) -> usize {
+        1 + self.branches.iter().map(|(_, tree)| tree.depth()).max().unwrap_or(0)
+    }
+
+    /// How many goals the tree solves, one per win.
+    pub fn goals(&self) -> usize {
+        self.wins as usize + self.branches.iter().map(|(_, tree)| tree.goals()).sum::<usize>()
+    }
+
+    /// The guesses needed to solve every goal in the tree, added up.
+    pub fn total_guesses(&self) -> usize {
+        self.wins as usize + self.branches.iter().map(|(_, tree)| tree.goals() + tree.total_guesses()).sum::<usize>()
+    }
+
+    /// The tree as a JSON object, with the guess, the candidates and an
+    /// object of the branches keyed on their tiles as letters, e.g.
+    /// `{"guess":"crane","candidates":2,"next":{"bbbbg":{...}}}`. Leaves
+    /// have no `next`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, json: &mut String) {
+        write!(json, "{{\"guess\":{},\"candidates\":{}", quoted(&self.guess.to_string()), self.candidates).unwrap();
+        if !self.branches.is_empty() {
+            json.push_str(",\"next\":{");
+            for (i, (outcome, tree)) in self.branches.iter().enumerate() {
+                if i > 0 { json.push(','); }
+                write!(json, "\"{}\":", outcome.letters()).unwrap();
+                tree.write_json(json);
+            }
+            json.push('}');
+        }
+        json.push('}');
+    }
+
+    /// The tree as a Graphviz DOT digraph: a node for each guess, labelled
+    /// with the candidates before it, and an edge for each outcome.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph wordlesolve {\n  node [shape=box fontname=monospace];\n");
+        self.write_dot(&mut dot, &mut 0);
+        dot.push_str("}\n");
+        dot
+    }
+
+    // Writes this node as number `next`, then its branches, leaving `next`
+    // past the last number used.
+    fn write_dot(&self, dot: &mut String, next: &mut usize) {
+        let id = *next;
+        *next += 1;
+        writeln!(dot, "  n{} [label={}];", id, quoted(&format!("{}\n{} left", self.guess, self.candidates))).unwrap();
+        for (outcome, tree) in &self.branches {
+            writeln!(dot, "  n{} -> n{} [label=\"{}\"];", id, *next, outcome.letters()).unwrap();
+            tree.write_dot(dot, next);
+        }
+    }
+}
+
+impl FromStr for DecisionTree {
+    type Err = String;
+
+    /// Reads the JSON written by [`to_json`](DecisionTree::to_json).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut reader = Reader { rest: s };
+        let tree = reader.tree()?;
+        if !reader.rest.trim().is_empty() { return Err(reader.error("the end")) }
+        Ok(tree)
+    }
+}
+
+// Reads the JSON DecisionTree::to_json writes: objects, strings and whole
+// numbers.
+struct Reader<'a> {
+    rest: &'a str,
+}
+
+impl Reader<'_> {
+    fn error(&self, expected: &str) -> String {
+        format!("expected {} at {:?}", expected, self.rest.chars().take(20).collect::<String>())
+    }
+
+    // Consumes `c`, after any whitespace, if it's next.
+    fn eat(&mut self, c: char) -> bool {
+        self.rest = self.rest.trim_start();
+        self.rest.strip_prefix(c).map(|rest| self.rest = rest).is_some()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) { Ok(()) } else { Err(self.error(&format!("{:?}", c))) }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        let mut chars = self.rest.chars();
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => string.push('\n'),
+                    Some(c) => string.push(c),
+                    None => return Err(self.error("an escape")),
+                },
+                Some(c) => string.push(c),
+                None => return Err(self.error("a closing quote")),
+            }
+        }
+        self.rest = chars.as_str();
+        Ok(string)
+    }
+
+    fn number(&mut self) -> Result<usize, String> {
+        self.rest = self.rest.trim_start();
+        let digits = self.rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(self.rest.len());
+        let number = self.rest[..digits].parse().map_err(|_| self.error("a number"))?;
+        self.rest = &self.rest[digits..];
+        Ok(number)
+    }
+
+    // Reads an object, passing each key to `field` to read its value.
+    fn object(&mut self, mut field: impl FnMut(&mut Self, String) -> Result<(), String>) -> Result<(), String> {
+        self.expect('{')?;
+        if self.eat('}') { return Ok(()) }
+        loop {
+            let key = self.string()?;
+            self.expect(':')?;
+            field(self, key)?;
+            if !self.eat(',') { return self.expect('}') }
+        }
+    }
+
+    fn tree(&mut self) -> Result<DecisionTree, String> {
+        let (mut guess, mut candidates, mut branches) = (None, None, Vec::new());
+        self.object(|reader, key| {
+            match key.as_str() {
+                "guess" => {
+                    let word = reader.string()?;
+                    guess = Some(Word::parse(&word).ok_or_else(|| format!("{:?} isn't a word", word))?);
+                },
+                "candidates" => candidates = Some(reader.number()?),
+                "next" => reader.object(|reader, key| {
+                    branches.push((key.parse::<Outcome>()?, reader.tree()?));
+                    Ok(())
+                })?,
+                _ => return Err(format!("unexpected field {:?}", key)),
+            }
+            Ok(())
+        })?;
+        let guess = guess.ok_or("a tree node has no guess")?;
+        let candidates = candidates.ok_or("a tree node has no candidates")?;
+        branches.sort_by_key(|b: &(Outcome, DecisionTree)| b.0.code());
+        // Whatever the branches don't account for, the guess wins.
+        let wins = candidates > branches.iter().map(|b| b.1.candidates).sum();
+        Ok(DecisionTree { guess, candidates, wins, branches })
+    }
+}
+
+/// A [`Strategy`] following a [`DecisionTree`] and falling back to another
+/// off it.
+///
+/// Positions are recognized by their candidates, whatever guesses led to
+/// them. Off the tree with more candidates than the fallback is practical
+/// for, [`Quick`] stands in for it.
+pub struct Planned {
+    strategy: Box<dyn Strategy>,
+    guesses: HashMap<Vec<Word>, Word>,
+}
+
+impl Planned {
+    /// Follows `tree` for games whose answer is one of `goals`, from which
+    /// the candidates at each of its positions are worked out.
+    pub fn new(tree: &DecisionTree, goals: &[Word], strategy: Box<dyn Strategy>) -> Self {
+        let mut goals = goals.to_vec();
+        goals.sort();
+        goals.dedup();
+        let mut guesses = HashMap::new();
+        Self::plan(tree, goals, &mut guesses);
+        Self { strategy, guesses }
+    }
+
+    fn plan(tree: &DecisionTree, candidates: Vec<Word>, guesses: &mut HashMap<Vec<Word>, Word>) {
+        for (outcome, branch) in &tree.branches {
+            let left: Vec<Word> = candidates.iter().copied().filter(|&g| Outcome::compare(g, tree.guess) == *outcome).collect();
+            if !left.is_empty() { Self::plan(branch, left, guesses); }
+        }
+        guesses.insert(candidates, tree.guess);
+    }
+
+    // The tree's guess when these are the candidates, if it's allowed.
+    fn planned<'a>(&self, candidates: impl Iterator<Item = &'a Word>, allowed: impl Fn(Word) -> bool) -> Option<Word> {
+        let mut candidates: Vec<Word> = candidates.copied().collect();
+        candidates.sort();
+        self.guesses.get(&candidates).copied().filter(|&g| allowed(g) || candidates.contains(&g))
+    }
+
+    fn fallback(&self, candidates: usize) -> &dyn Strategy {
+        if candidates > self.strategy.max_candidates() { &Quick } else { self.strategy.as_ref() }
+    }
+}
+
+impl Strategy for Planned {
+    fn name(&self) -> &'static str {
+        self.strategy.name()
+    }
+
+    fn choose(&self, candidates: &[Word], dict: &[Word], pattern: &Pattern) -> Word {
+        self.planned(candidates.iter(), |g| dict.contains(&g))
+            .unwrap_or_else(|| self.fallback(candidates.len()).choose(candidates, dict, pattern))
+    }
+
+    fn choose_indexed(&self, matrix: &OutcomeMatrix, candidates: &[u32], rows: &[u32]) -> Option<Word> {
+        let planned = self.planned(candidates.iter().map(|&id| &matrix.goals()[id as usize]),
+                                   |g| matrix.row(g).is_some_and(|row| rows.contains(&(row as u32))));
+        planned.or_else(|| self.fallback(candidates.len()).choose_indexed(matrix, candidates, rows))
+    }
+}
+
+// A JSON string, which DOT's quoting is close enough to for words.
+fn quoted(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => { quoted.push('\\'); quoted.push(c); },
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,73 @@
+use crate::outcome::Outcome;
+use crate::pattern::Pattern;
+use crate::solver::Solver;
+use crate::word::Word;
+use std::fmt;
+
+/// Goals that filtering by [`Pattern`] and replaying [`Outcome::compare`]
+/// disagree on, after the guesses of `history`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// Which filter disagreed, e.g. "pattern".
+    pub filter: &'static str,
+    pub history: Vec<(Word, Outcome)>,
+
+    /// Goals the filter kept though some guess would have scored otherwise
+    /// against them, and goals it dropped though every guess would have
+    /// scored the same.
+    pub kept: Vec<Word>,
+    pub dropped: Vec<Word>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let words = |words: &[Word]| words.iter().map(Word::to_string).collect::<Vec<_>>().join(" ");
+        let history: Vec<String> = self.history.iter().map(|(guess, outcome)| format!("{} {}", guess, outcome.letters())).collect();
+        write!(formatter, "{} filtering after {} ", self.filter, history.join(", "))?;
+        if !self.kept.is_empty() { write!(formatter, "kept {}", words(&self.kept))?; }
+        if !self.kept.is_empty() && !self.dropped.is_empty() { formatter.write_str(" and ")?; }
+        if !self.dropped.is_empty() { write!(formatter, "dropped {}", words(&self.dropped))?; }
+        Ok(())
+    }
+}
+
+/// The goals each guess of `history` gets its outcome against, by comparing
+/// them directly, in order.
+pub fn consistent_goals(goals: &[Word], history: &[(Word, Outcome)]) -> Vec<Word> {
+    goals.iter().copied().filter(|&goal| history.iter().all(|&(guess, outcome)| Outcome::compare(goal, guess) == outcome)).collect()
+}
+
+/// Checks that the `remaining` goals `filter` left out of `goals` after
+/// `history` are the [`consistent_goals`], in any order.
+pub fn verify_goals(filter: &'static str, goals: &[Word], history: &[(Word, Outcome)], remaining: &[Word]) -> Result<(), Divergence> {
+    let mut expected = consistent_goals(goals, history);
+    let mut remaining = remaining.to_vec();
+    expected.sort();
+    remaining.sort();
+    if expected == remaining { return Ok(()) }
+    Err(Divergence {
+        filter,
+        history: history.to_vec(),
+        kept: remaining.iter().copied().filter(|w| expected.binary_search(w).is_err()).collect(),
+        dropped: expected.iter().copied().filter(|w| remaining.binary_search(w).is_err()).collect(),
+    })
+}
+
+/// Plays `guesses` against `goal` from `solver`'s position, checking after
+/// each that [`Pattern`] filtering and the solver, through its matrix too if
+/// it has one, leave exactly the [`consistent_goals`].
+pub fn verify_game(solver: &Solver, goal: Word, guesses: &[Word]) -> Result<(), Divergence> {
+    let goals = solver.candidates().to_vec();
+    let mut solver = solver.clone().verifying();
+    let mut pattern = Pattern::new();
+    let mut history = Vec::new();
+    for &guess in guesses {
+        let outcome = Outcome::compare(goal, guess);
+        history.push((guess, outcome));
+        pattern.refine(guess, outcome);
+        let matched: Vec<Word> = goals.iter().copied().filter(|&w| pattern.matches(w)).collect();
+        verify_goals("pattern", &goals, &history, &matched)?;
+        solver.try_refine(guess, outcome)?;
+    }
+    Ok(())
+}
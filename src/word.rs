@@ -0,0 +1,101 @@
+use crate::alphabet::{alphabet, is_direction_mark, LetterMask};
+use std::collections::HashMap;
+use std::fmt;
+use std::str;
+use std::sync::{Mutex, OnceLock};
+
+/// The longest word supported.
+pub const MAX_LEN: usize = 8;
+
+pub(crate) fn to_letter_mask(symbol: u8) -> LetterMask {
+    1 << symbol
+}
+
+/// A word of up to [`MAX_LEN`] letters from the installed
+/// [`alphabet`](crate::alphabet), with a bitmask of the letters it uses.
+///
+/// With [accents folded](crate::Alphabet::folding_accents), words differing
+/// only in accents are the same word, shown as it was first parsed with
+/// them, e.g. "été" however it's typed after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Word {
+    // Each letter's index in the alphabet, padded with zeros.
+    symbols: [u8; MAX_LEN],
+    len: u8,
+    pub(crate) letters: LetterMask,
+}
+
+impl Word {
+    /// Builds a word from text already known to be letters of the alphabet.
+    ///
+    /// Panics if `text` is longer than [`MAX_LEN`] or has other characters;
+    /// use [`Word::parse`] for untrusted input.
+    pub fn new(text: &str) -> Self {
+        Self::parse(text).unwrap_or_else(|| panic!("{:?} isn't a word", text))
+    }
+
+    /// Parses a word, or returns `None` unless `text` is 1 to [`MAX_LEN`]
+    /// letters of the alphabet. Marks of the text's direction are skipped;
+    /// see [`is_direction_mark`](crate::is_direction_mark).
+    pub fn parse(text: &str) -> Option<Self> {
+        let alphabet = alphabet();
+        let mut symbols = [0; MAX_LEN];
+        let mut len = 0;
+        let mut letters = 0;
+        let mut folded = false;
+        for c in text.chars().filter(|&c| !is_direction_mark(c)) {
+            if len == MAX_LEN { return None }
+            symbols[len] = alphabet.lookup(c)?;
+            folded |= alphabet.symbol(symbols[len]) != c;
+            letters |= to_letter_mask(symbols[len]);
+            len += 1;
+        }
+        if len == 0 { return None }
+        let word = Self { symbols, len: len as u8, letters };
+        if folded {
+            let spelled = text.chars().filter(|&c| !is_direction_mark(c)).collect();
+            spellings().lock().unwrap().entry(word).or_insert(spelled);
+        }
+        Some(word)
+    }
+
+    /// Each letter's index in the alphabet.
+    pub fn symbols(&self) -> &[u8] {
+        &self.symbols[..self.len as usize]
+    }
+
+    /// How many letters the word has.
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Always false; words have at least one letter.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl str::FromStr for Word {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, ()> {
+        Self::parse(text).ok_or(())
+    }
+}
+
+// How words parsed with folded accents were first spelled.
+fn spellings() -> &'static Mutex<HashMap<Word, String>> {
+    static SPELLINGS: OnceLock<Mutex<HashMap<Word, String>>> = OnceLock::new();
+    SPELLINGS.get_or_init(Mutex::default)
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let alphabet = alphabet();
+        if alphabet.folds_accents() {
+            if let Some(spelled) = spellings().lock().unwrap().get(self) { return formatter.write_str(spelled) }
+        }
+        for &s in self.symbols() { fmt::Write::write_char(formatter, alphabet.symbol(s))?; }
+        Ok(())
+    }
+}
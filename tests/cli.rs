@@ -0,0 +1,537 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use wordlesolve::{DecisionTree, Outcome, Word};
+
+// Runs the binary with `args`, returning its standard output.
+fn run(args: &[&str]) -> String {
+    run_with(args, "")
+}
+
+// Like run, typing `input`.
+fn run_with(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wordlesolve")).args(args).env("HOME", scratch("home")).env_remove("XDG_CACHE_HOME")
+        .env_remove("XDG_DATA_HOME").env_remove("XDG_CONFIG_HOME").stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped())
+        .spawn().unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// A path of its own for each test, emptied.
+fn scratch(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("wordlesolve-test-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&path);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn budgeted_choices_are_not_cached() {
+    let cache = scratch("budget-cache");
+    let cache = cache.to_str().unwrap();
+    let best = |extra: &[&str]| -> String {
+        let mut args = vec!["--constraints", "?a,-esoir"];
+        args.extend(extra);
+        args.push("best");
+        run(&args).lines().last().unwrap().to_string()
+    };
+    let uncached = best(&["--no-cache"]);
+    best(&["--cache", cache, "--budget", "0s"]);
+    assert_eq!(best(&["--cache", cache]), uncached);
+}
+
+#[test]
+fn pruned_choices_are_cached_apart() {
+    let cache = scratch("prune-cache");
+    let cache = cache.to_str().unwrap();
+    let best = |extra: &[&str]| -> String {
+        let mut args = vec!["--constraints", "?r,-aise", "--cache", cache];
+        args.extend(extra);
+        args.push("best");
+        run(&args).lines().last().unwrap().to_string()
+    };
+    let pruned = best(&["--no-cache", "--prune", "1"]);
+    let full = best(&["--no-cache"]);
+    assert_ne!(pruned, full);
+    best(&["--prune", "1"]);
+    assert_eq!(best(&[]), full);
+    assert_eq!(best(&["--prune", "1"]), pruned);
+}
+
+#[test]
+fn sampled_choices_are_not_cached() {
+    let cache = scratch("sampled-cache");
+    let cache = cache.to_str().unwrap();
+    let best = |seed: &str| -> String {
+        run(&["--strategy", "sampled", "--seed", seed, "--cache", cache, "--constraints", "?a,-esoir", "best"]).lines().last().unwrap().to_string()
+    };
+    let first = best("1");
+    assert!((2..10).map(|seed| best(&seed.to_string())).any(|guess| guess != first));
+}
+
+#[test]
+fn big_positions_are_looked_up_before_falling_back() {
+    // Too many goals for minimax to search while playing, but a book it
+    // wrote has the opener, and a cache never keeps the quick strategy's.
+    let words = scratch("big-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(1100).collect::<Vec<_>>().join("\n")).unwrap();
+    let (book, cache) = (scratch("big-book"), scratch("big-cache"));
+    let lists = ["--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap()];
+    let best = |extra: &[&str]| -> String {
+        let mut args = lists.to_vec();
+        args.extend(extra);
+        args.push("best");
+        run(&args).lines().last().unwrap().to_string()
+    };
+    run(&[&lists[..], &["--no-cache", "book", book.to_str().unwrap()]].concat());
+    assert!(best(&["--no-cache", "--book", book.to_str().unwrap()]).ends_with("(minimax strategy)"));
+    for _ in 0..2 { assert!(best(&["--cache", cache.to_str().unwrap()]).ends_with("(quick strategy)")); }
+    // The built-in book has the opener over the full lists.
+    let metrics = scratch("big-metrics");
+    assert!(run(&["--no-cache", "--metrics-out", metrics.to_str().unwrap(), "best"]).ends_with("(minimax strategy)\n"));
+    assert!(fs::read_to_string(&metrics).unwrap().contains("\"cache_hits\":1,\"quick_fallbacks\":0"));
+}
+
+#[test]
+fn low_memory_recommends_the_same() {
+    for constraints in ["?a,-esoir", "+r@2,-aise"] {
+        let best = |extra: &[&str]| run(&[&["--no-cache", "--constraints", constraints][..], extra, &["best"]].concat());
+        assert_eq!(best(&["--low-memory"]), best(&[]));
+    }
+}
+
+#[test]
+fn reports_are_written_as_web_pages() {
+    let words = scratch("report-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let (bench, simulated) = (scratch("bench.html"), scratch("simulate.html"));
+    let lists = ["--no-cache", "--guesses", "2", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap()];
+    run(&[&lists[..], &["--compare", "minimax,quick", "--report", bench.to_str().unwrap(), "bench"]].concat());
+    run(&[&lists[..], &["--strategy", "sampled", "--report", simulated.to_str().unwrap(), "simulate", "2"]].concat());
+    let bench = fs::read_to_string(bench).unwrap();
+    assert!(bench.starts_with("<!DOCTYPE html>") && bench.ends_with("</html>\n"));
+    assert!(bench.contains("<h2>minimax</h2>") && bench.contains("<h2>quick</h2>") && bench.contains("<li><b>"));
+    assert!(fs::read_to_string(simulated).unwrap().contains("<h1>2 trials over 60 goals</h1>"));
+}
+
+#[test]
+fn games_past_the_guess_limit_fail() {
+    let words = scratch("failure-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let bench = |guesses: &str| -> String {
+        let output = run(&["--no-cache", "--guesses", guesses, "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "bench"]);
+        output.lines().find(|line| line.contains("not solved within")).unwrap().to_string()
+    };
+    assert_eq!(bench("2"), "26 not solved within 2 guesses, 43.33% failed");
+    assert_eq!(bench("3"), "1 not solved within 3 guesses, 1.67% failed");
+}
+
+#[test]
+fn failures_are_shown_turn_by_turn() {
+    let words = scratch("shown-failure-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let output = run(&["--no-cache", "--guesses", "3", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--show-failures", "bench"]);
+    let shown: Vec<&str> = output.lines().skip_while(|line| !line.starts_with("model,")).collect();
+    assert_eq!(shown, [
+        "model, solved in 4 guesses",
+        "  1 react ⬜🟨⬜⬜⬜ 6 left",
+        "  2 flesh ⬜🟨🟨⬜⬜ 2 left",
+        "  3 linen 🟨⬜⬜🟩⬜ 1 left",
+        "  4 model 🟩🟩🟩🟩🟩 1 left",
+        "  too many goals after turn 2: 2 left to find in 1 guesses",
+    ]);
+}
+
+#[test]
+fn reviews_weigh_skill_and_luck() {
+    let output = run_with(&["--no-cache", "review"], "crane ⬜⬜⬜🟨⬜\n");
+    assert!(output.contains("   skill 81% (78.7 left expected; arise leaves 63.7)\n"));
+    assert!(output.ends_with("skill 81%, luck 46%\n"));
+}
+
+#[test]
+fn reviews_compare_the_strategy_line() {
+    let words = scratch("line-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let output = run_with(&["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--quiet", "--review", "model"],
+                          "cigar\nrebut\nmodel\n");
+    let compared: Vec<&str> = output.lines().skip_while(|line| !line.starts_with("    played")).collect();
+    assert_eq!(compared, [
+        "    played       minimax",
+        "1.  cigar ⬜⬜⬜⬜⬜  react ⬜🟨⬜⬜⬜  <- first difference, 0.22 more guesses expected",
+        "2.  rebut ⬜🟨⬜⬜⬜  flesh ⬜🟨🟨⬜⬜",
+        "3.  model 🟩🟩🟩🟩🟩  linen 🟨⬜⬜🟩⬜",
+        "4.               model 🟩🟩🟩🟩🟩",
+    ]);
+}
+
+#[test]
+fn games_are_summed_up_in_markdown() {
+    let words = scratch("summary-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let board = "cigar ⬜⬜⬜⬜⬜\nrebut ⬜🟨⬜⬜⬜\nmodel 🟩🟩🟩🟩🟩\n";
+    let output = run_with(&["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--summary", "markdown", "review"], board);
+    assert_eq!(output, "
+**Wordle 3/6**
+
+| | guess | tiles | goals left | skill | luck |
+|--:|---|---|--:|--:|--:|
+| 1 | cigar | ⬜⬜⬜⬜⬜ | 60 → 13 | 46% | 11% |
+| 2 | rebut | ⬜🟨⬜⬜⬜ | 13 → 3 | 90% | 12% |
+| 3 | model | 🟩🟩🟩🟩🟩 | 3 → 1 | 60% | 83% |
+
+Skill 65%, luck 35%.
+
+The minimax strategy would have played **react** instead of cigar on turn 1, expecting 0.22 fewer guesses: react, flesh, linen, model.
+");
+}
+
+#[test]
+fn shared_words_can_hide_behind_spoilers() {
+    let words = scratch("spoiler-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let lists = ["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--quiet", "--spoiler-markup"];
+    let output = run_with(&[&lists[..], &["--summary", "markdown", "model"]].concat(), "cigar\nrebut\nmodel\n");
+    assert!(output.contains("\n⬜⬜⬜⬜⬜ ||cigar||\n⬜🟨⬜⬜⬜ ||rebut||\n🟩🟩🟩🟩🟩 ||model||\n"));
+    assert!(output.contains("\n1. ⬜⬜⬜⬜⬜ ||cigar||: 60 → 13 goals, skill 46%, luck 11%\n"));
+    assert!(output.contains("**||react||** instead of ||cigar||"));
+    assert!(!output.contains(" model") && !output.contains("| guess"));
+}
+
+#[test]
+fn bound_keys_play_suggestions_and_take_guesses_back() {
+    let words = scratch("key-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let lists = ["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--quiet"];
+    // ctrl-n, then tab with tiles, ctrl-b and ctrl-t.
+    let output = run_with(&[&lists[..], &["assist"]].concat(), "\x0e\n\t bbbbb\n\x02\n\x14\n");
+    assert!(output.contains("next suggestion is crate (2 of 5)\n"));
+    assert!(output.contains("recommended guess is blush"));
+    assert_eq!(output.matches("recommended guess is react").count(), 2);
+    assert!(output.contains("try a word with e"));
+    // Rebinding a key from the config file unbinds the default.
+    let config = scratch("key-config");
+    fs::write(&config, "key_undo = \"u\"\n").unwrap();
+    let output = run_with(&[&lists[..], &["--config", config.to_str().unwrap(), "assist"]].concat(), "crate bbbbb\n\x02\nu\nundo\n");
+    assert_eq!(output.matches("recommended guess is react").count(), 2);
+    assert_eq!(output.matches("expected a guess and its tiles").count(), 2);
+}
+
+#[test]
+fn config_files_are_read_as_toml() {
+    let words = scratch("toml-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let config = scratch("toml-config");
+    fs::write(&config, format!("# every day's flags\nno_cache = true\nexclude_suffix = [ ]\ngoals = \"{0}\"\ndict = '{0}'\nquiet = true\n\
+                                 hard = true  # keep to the clues\nallow_any = true\n\n[key]\nundo = \"\\u0075\" # u\n", words.display())).unwrap();
+    let output = run_with(&["--config", config.to_str().unwrap(), "assist"], "crate bbbbb\nu\ncrane bbbbg\ntonic bbbbb\n");
+    assert_eq!(output.matches("recommended guess is react").count(), 2);
+    assert!(output.contains("tonic breaks hard mode rules"));
+}
+
+#[test]
+fn updated_word_lists_replace_the_built_in_ones() {
+    let source = scratch("list-source");
+    fs::create_dir(&source).unwrap();
+    let goals: Vec<&str> = include_str!("../goals.txt").lines().take(60).collect();
+    fs::write(source.join("goals.txt"), goals.join("\n")).unwrap();
+    fs::write(source.join("extra.txt"), "aahed\naalii\n").unwrap();
+    let lists = scratch("installed-lists");
+    let update = ["--lists-dir", lists.to_str().unwrap(), "--lists-source", source.to_str().unwrap(), "update-wordlists"];
+    assert!(run(&update).contains("/wordle/goals.txt: 60 words from "));
+    // The old lists are kept once there are some.
+    fs::write(source.join("goals.txt"), goals[1..].join("\n")).unwrap();
+    assert!(run(&update).contains("goals.txt: 59 words from ") && lists.join("wordle/goals.txt.bak").exists());
+    assert!(run(&["--lists-dir", lists.to_str().unwrap(), "--no-cache", "--quiet", "bench"]).contains("minimax strategy over 59 goals\n"));
+}
+
+#[test]
+fn turns_are_described_in_the_chosen_language() {
+    let words = scratch("spanish-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let lists = ["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--compact"];
+    let output = run_with(&[&lists[..], &["--ui-lang", "es", "cigar"]].concat(), "react\ncigar\n");
+    assert!(output.contains("quedan 60 palabras\n") && output.contains("prueba "));
+    assert!(output.contains("\nresuelto en 2 intentos\n"));
+    assert!(run_with(&[&lists[..], &["cigar"]].concat(), "cigar\n").contains("\nsolved in 1 guesses\n"));
+}
+
+#[test]
+fn profiles_play_by_each_games_rules() {
+    // The original's answers go in the list's order, one a day.
+    let output = run_with(&["--no-cache", "--quiet", "--no-record", "--profile", "original", "host", "2021-06-20"], "rebut\n");
+    assert!(output.contains("🟩🟩🟩🟩🟩 rebut"));
+    let output = run_with(&["--no-cache", "--no-record", "--profile", "original", "daily", "assist", "2021-06-21"], "");
+    assert!(output.contains("  2313 matching goal words"));
+    // The New York Times' game doesn't repeat them, but hello wordl does.
+    let past = scratch("past-answers");
+    fs::write(&past, "cigar\nrebut\n").unwrap();
+    let output = run_with(&["--no-cache", "--profile", "nyt", "--past-answers", past.to_str().unwrap(), "assist"], "");
+    assert!(output.contains("  2313 matching goal words"));
+    let output = run_with(&["--no-cache", "--profile", "hello-wordl", "--past-answers", past.to_str().unwrap(), "assist"], "");
+    assert!(!output.contains("  2313 matching") && !output.contains("  2315 matching"));
+}
+
+#[test]
+fn right_to_left_words_are_read_without_direction_marks() {
+    let words = scratch("hebrew-words");
+    fs::write(&words, "אבגדה\nהדגבא\nבגדהו\nגדהוז\n").unwrap();
+    let lists = ["--no-cache", "--alphabet", "אבגדהוזחטיכךלמםנןסעפףצץקרשת", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--quiet"];
+    // Pasted with a right-to-left mark, and the board kept first letter first.
+    let output = run_with(&[&lists[..], &["אבגדה"]].concat(), "\u{200f}בגדהו\nאבגדה\n");
+    assert!(output.contains("\n🟨🟨🟨🟨⬜ בגדהו\n🟩🟩🟩🟩🟩 אבגדה\n"));
+}
+
+#[test]
+fn kana_games_use_symbols_past_the_sixty_fourth() {
+    let words = scratch("kana-words");
+    fs::write(&words, "らりるれ\nろわをん\nらわりを\nんれるろ\nゆよらん\n").unwrap();
+    let hiragana: String = ('\u{3041}'..='\u{3096}').collect();
+    let lists = ["--no-cache", "--alphabet", &hiragana, "--length", "4", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--quiet"];
+    let output = run_with(&[&lists[..], &["んれるろ"]].concat(), "らりるれ\nんれるろ\n");
+    assert!(output.contains("\n⬜⬜🟩🟨 らりるれ\n🟩🟩🟩🟩 んれるろ\n"));
+}
+
+#[test]
+fn folded_accents_match_while_words_show_as_spelled() {
+    let words = scratch("french-words");
+    fs::write(&words, "été\ncafé\nmère\nlivre\npâtes\n").unwrap();
+    let lists = ["--no-cache", "--fold-accents", "--length", "4", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--quiet"];
+    // Typed without accents, and shown with them.
+    let output = run_with(&[&lists[..], &["mere"]].concat(), "cafe\nmère\n");
+    assert!(output.contains("\n⬜⬜⬜🟩 café\n🟩🟩🟩🟩 mère\n"));
+}
+
+#[test]
+fn crowds_votes_only_count_listed_words() {
+    // xyzzx would win on votes, but isn't a word.
+    let output = run_with(&["--no-cache", "crowd", "cigar"], "a xyzzx\nb xyzzx\nc crane\n\n");
+    assert!(output.contains("round 1: 1 votes\n  crane 1\nplaying crane"));
+}
+
+#[test]
+fn assist_narrows_the_goals_by_typed_tiles() {
+    let words = scratch("assist-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let lists = ["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap()];
+    // The answer is never given: the tiles alone lead to it.
+    let output = run_with(&[&lists[..], &["assist"]].concat(), "react bbbbb\nblush ⬜🟩⬜🟩⬜\nfloss ggggg\n");
+    assert!(output.contains("recommended guess is react (at most 6 possible words)\n"));
+    assert!(output.contains("pattern is -acert\n  6 matching goal words\n"));
+    assert!(output.contains("recommended guess is blush"));
+    assert!(output.contains("the answer must be floss\n"));
+    assert!(output.contains("solved in 3 guesses\n"));
+}
+
+#[test]
+fn json_turns_are_a_line_of_fields_each() {
+    let words = scratch("json-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let lists = ["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--no-record"];
+    let output = run_with(&[&lists[..], &["--json", "assist"]].concat(), "react bbbbb\nblush bgbgb\nfloss ggggg\n");
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines.iter().all(|line| line.starts_with('{') && line.ends_with('}')), "{}", output);
+    assert_eq!(lines[0], "{\"turn\":1,\"pattern\":\"\",\"remaining\":60,\"recommended\":\"react\",\"strategy\":\"minimax\",\"worst_case\":6}");
+    assert!(lines[1].starts_with("{\"guess\":\"react\",\"guess_worst_case\":6,"));
+    // Few enough goals are listed, with their odds.
+    assert!(lines[2].starts_with("{\"turn\":2,\"pattern\":\"-acert\",\"remaining\":6,\"candidates\":[\""));
+    assert!(lines[2].contains("\"probabilities\":[0.1667,") && lines[2].ends_with("\"recommended\":\"blush\",\"strategy\":\"minimax\",\"worst_case\":1}"));
+    assert!(lines[4].contains("\"remaining\":1,\"candidates\":[\"floss\"],\"probabilities\":[1.0000]"));
+    assert_eq!(lines.last(), Some(&"{\"solved\":3}"));
+}
+
+#[test]
+fn saved_games_resume_where_they_left_off() {
+    let words = scratch("save-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let lists = ["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--quiet", "--no-record"];
+    let (saved, resaved) = (scratch("saved-game"), scratch("resaved-game"));
+    let output = run_with(&[&lists[..], &["assist"]].concat(), &format!("react bbbbb\nblush bgbgb\nsave {}\nhistory\n", saved.display()));
+    assert!(output.contains("saved 2 guesses to "));
+    let history = output.split("saved 2 guesses to ").nth(1).unwrap().split_once('\n').unwrap().1;
+    // The resumed game has the same guesses, and is saved the same again.
+    let resumed = run_with(&[&lists[..], &["--resume", saved.to_str().unwrap(), "assist"]].concat(),
+                           &format!("history\nsave {}\n", resaved.display()));
+    assert_eq!(history, "1. react ⬜⬜⬜⬜⬜ 6 left\n2. blush ⬜🟩⬜🟩⬜ 1 left\n");
+    assert!(resumed.contains(history), "{:?} doesn't include {:?}", resumed, history);
+    assert!(resumed.contains("the answer must be floss\n"));
+    assert_eq!(fs::read_to_string(&resaved).unwrap(), fs::read_to_string(&saved).unwrap());
+    assert_eq!(fs::read_to_string(&saved).unwrap(), "react ⬜⬜⬜⬜⬜\nblush ⬜🟩⬜🟩⬜\n");
+}
+
+#[test]
+fn tui_screens_show_every_panel() {
+    let words = scratch("tui-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let lists = ["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--no-record", "--no-color"];
+    let output = run_with(&[&lists[..], &["--tui", "assist"]].concat(), "react bbbbb\n");
+    let screens: Vec<&str> = output.split("\x1b[2J\x1b[H").skip(1).collect();
+    assert_eq!(screens.len(), 2);
+    assert!(screens[0].starts_with("board                  60 goals left\n"));
+    assert!(screens[0].contains("best     worst expected\n") && screens[0].contains("minimax recommends react\n"));
+    // The board, the goals left, the best guesses and the keyboard, side by side.
+    let second = screens[1];
+    assert!(second.starts_with("board                  6 goals left\nreact ⬜⬜⬜⬜⬜       "));
+    assert!(["floss", "humph", "booby", "blush", "pound", "sissy"].iter().all(|goal| second.contains(goal)));
+    assert!(second.contains("                  blush        1     1.00\n"));
+    assert!(second.contains("q w . . . y u i o p ") && second.contains("  z x . v b n m\n"));
+    assert!(second.contains("minimax recommends blush\n"));
+}
+
+#[test]
+fn exported_trees_play_every_goal() {
+    let words = scratch("tree-words");
+    let goals: Vec<&str> = include_str!("../goals.txt").lines().take(60).collect();
+    fs::write(&words, goals.join("\n")).unwrap();
+    let out = scratch("tree.json");
+    let output = run(&["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "tree", "--out", out.to_str().unwrap()]);
+    assert!(output.contains("60 goals in 146 guesses (2.4333 on average), at most 4\n"));
+    let tree: DecisionTree = fs::read_to_string(&out).unwrap().parse().unwrap();
+    // Following the plan outcome by outcome finds each goal, in as many
+    // guesses in all as it says.
+    let mut total = 0;
+    for goal in goals.iter().map(|g| Word::new(g)) {
+        let mut node = &tree;
+        for guesses in 1.. {
+            let outcome = Outcome::compare(goal, node.guess);
+            if outcome.is_win() { total += guesses; break }
+            node = &node.branches.iter().find(|(o, _)| *o == outcome).unwrap_or_else(|| panic!("no branch for {} after {}", goal, node.guess)).1;
+        }
+    }
+    assert_eq!(total, 146);
+    // The DOT beside it has a box per guess and an arrow per branch.
+    let dot = fs::read_to_string(out.with_extension("dot")).unwrap();
+    assert!(dot.starts_with("digraph wordlesolve {\n") && dot.contains("  n0 [label=\"react\\n60 left\"];\n  n0 -> n1 [label=\"bbbbb\"];\n"));
+    let nodes = dot.lines().filter(|l| l.contains(" [label=\"") && !l.contains("->")).count();
+    assert_eq!(dot.lines().filter(|l| l.contains("->")).count(), nodes - 1);
+}
+
+#[test]
+fn loaded_trees_are_followed_then_searched_past() {
+    let words = scratch("planned-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let lists = ["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--no-record", "--quiet"];
+    // A written tree plays as the search would have.
+    let out = scratch("planned.json");
+    run(&[&lists[..], &["tree", "--out", out.to_str().unwrap()]].concat());
+    let planned = run(&[&lists[..], &["--tree", out.to_str().unwrap(), "play", "floss"]].concat());
+    assert!(planned.starts_with("1 react ⬜⬜⬜⬜⬜\n2 blush ⬜🟩⬜🟩⬜\n3 floss 🟩🟩🟩🟩🟩\n"));
+    // A tree only as deep as its opener, which searching wouldn't pick, is
+    // followed as far as it goes and then left to the search.
+    let opener = scratch("opener.json");
+    fs::write(&opener, "{\"guess\":\"cigar\",\"candidates\":60}\n").unwrap();
+    let output = run_with(&[&lists[..], &["--tree", opener.to_str().unwrap(), "assist"]].concat(), "cigar bbbbb\n");
+    assert_eq!(output, "recommended guess is cigar (at most 13 possible words)\nrecommended guess is floss (at most 2 possible words)\n");
+}
+
+#[test]
+fn stats_count_the_recorded_games() {
+    let words = scratch("stats-words");
+    fs::write(&words, include_str!("../goals.txt").lines().take(60).collect::<Vec<_>>().join("\n")).unwrap();
+    let stats = scratch("stats");
+    let lists = ["--no-cache", "--goals", words.to_str().unwrap(), "--dict", words.to_str().unwrap(), "--quiet", "--stats", stats.to_str().unwrap()];
+    let recorded = [&lists[..], &["--record"]].concat();
+    run_with(&[&recorded[..], &["assist"]].concat(), "react bbbbb\nblush bgbgb\nfloss ggggg\n");
+    run_with(&[&recorded[..], &["cigar"]].concat(), "cigar\n");
+    run_with(&[&recorded[..], &["--guesses", "2", "assist"]].concat(), "react bbbbb\nblush bgbgb\n");
+    run_with(&[&recorded[..], &["rebut"]].concat(), "react\nrebut\n");
+    // Games the player didn't host aren't recorded unless asked.
+    run_with(&[&lists[..], &["rebut"]].concat(), "rebut\n");
+    let modes: Vec<String> = fs::read_to_string(&stats).unwrap().lines().map(|l| l.split(' ').skip(1).collect::<Vec<_>>().join(" ")).collect();
+    assert_eq!(modes, ["wordle assist 3", "wordle goal 1", "wordle assist X", "wordle goal 2"]);
+    let output = run(&[&lists[..], &["stats"]].concat());
+    assert!(output.starts_with("played 4, won 75%, current streak 1, max streak 2\naverage 2.00 guesses a win\n"));
+    assert!(output.contains("  1     1 ####") && output.contains("  3     1 ####") && output.contains("  6     0"));
+}
+
+#[test]
+fn the_first_two_turns_are_played_from_the_built_in_book() {
+    let metrics = scratch("book-metrics");
+    let output = run_with(&["--no-cache", "--quiet", "--metrics-out", metrics.to_str().unwrap(), "assist"], "arise bbbbb\n");
+    assert!(output.starts_with("recommended guess is arise (minimax strategy)\n"), "{}", output);
+    let turns = fs::read_to_string(&metrics).unwrap();
+    let turns: Vec<&str> = turns.lines().filter(|line| line.starts_with('{')).collect();
+    assert_eq!(turns.len(), 2);
+    for turn in turns {
+        assert!(turn.contains("\"cache_lookups\":1,\"cache_hits\":1,\"quick_fallbacks\":0"), "{}", turn);
+    }
+}
+
+#[test]
+fn popular_openers_are_answered_from_the_built_in_book() {
+    let metrics = scratch("opener-metrics");
+    for opener in ["adieu", "crane", "slate"] {
+        run_with(&["--no-cache", "--quiet", "--metrics-out", metrics.to_str().unwrap(), "assist"], &format!("{} bbybb\n", opener));
+        let turns = fs::read_to_string(&metrics).unwrap();
+        let reply = turns.lines().find(|line| line.starts_with("{\"turn\":2,")).unwrap();
+        assert!(reply.contains("\"cache_lookups\":1,\"cache_hits\":1,\"quick_fallbacks\":0"), "{}: {}", opener, reply);
+    }
+}
+
+#[test]
+fn typed_guesses_keep_to_hard_mode() {
+    // After crane's final e, hard mode needs it there, and ultra-hard rules
+    // out guessing anything with c, r, a or n too.
+    let played = "crane bbbbg\ntonic bbbbb\n";
+    assert!(run_with(&["--quiet", "--hard", "assist"], played).contains("tonic breaks hard mode rules"));
+    assert!(run_with(&["--quiet", "--ultra-hard", "assist"], played).contains("tonic breaks ultra-hard rules"));
+    let json = "{\"guess\":\"crane\",\"outcome\":\"bbbbg\"}\n{\"guess\":\"tonic\",\"outcome\":\"bbbbb\"}\n";
+    assert!(run_with(&["--hard", "--protocol", "assist"], json).contains("{\"error\":\"tonic breaks hard mode rules\"}"));
+    assert!(run_with(&["--quiet", "--ultra-hard", "cigar"], "crane\nfight\n").contains("fight breaks ultra-hard rules"));
+    assert!(run_with(&["--quiet", "--ultra-hard", "absurdle"], "crane\ncrane\n").contains("crane breaks ultra-hard rules"));
+    assert!(!run_with(&["--quiet", "assist"], played).contains("breaks"));
+}
+
+#[test]
+fn hosted_guesses_keep_to_ultra_hard_mode() {
+    // Repeating a guess that didn't win can't be right, but hard mode allows it.
+    assert!(run_with(&["--seed", "1", "--ultra-hard", "--no-record", "host"], "crane\ncrane\n").contains("crane breaks ultra-hard rules"));
+    assert!(!run_with(&["--seed", "1", "--hard", "--no-record", "host"], "crane\ncrane\n").contains("breaks"));
+}
+
+#[test]
+fn protocol_strings_decode_escapes() {
+    let lines = [
+        r#"{"guess":"crane","outcome":"bbbbg"}"#,
+        r#"{"command":"history"}"#,
+        r#"{"guess":"cra\rne","outcome":"bbbbg"}"#,
+        r#"{"guess":"\u0063r\u0061ne","outcome":"bbbbg"}"#,
+        r#"{"guess":"\ud83d\ude00","outcome":"bbbbg"}"#,
+        r#"{"guess":"cra\qne","outcome":"bbbbg"}"#,
+        r#"{"guess":"\ud83d","outcome":"bbbbg"}"#,
+    ];
+    let output = run_with(&["--protocol", "assist"], &(lines.join("\n") + "\n"));
+    let replies: Vec<&str> = output.lines().skip(2).collect();
+    assert!(replies[0].starts_with(r#"{"history":[{"turn":1,"guess":"crane","outcome":"bbbbg""#), "{}", replies[0]);
+    assert!(replies[1].contains(r#"\"cra\\rne\" is more than one word"#), "{}", replies[1]);
+    assert!(replies[2].starts_with(r#"{"turn":3,"#), "{}", replies[2]);
+    assert!(replies[3].contains("'😀'"), "{}", replies[3]);
+    assert!(replies[4].contains("isn't a JSON object of strings"), "{}", replies[4]);
+    assert!(replies[5].contains("isn't a JSON object of strings"), "{}", replies[5]);
+}
+
+#[test]
+fn daily_goals_stay_put() {
+    // Everyone playing a date's puzzle gets the same answer, with any build.
+    for (date, goal) in [("2026-01-31", "sloop"), ("2026-02-01", "filer")] {
+        let output = run_with(&["--json", "--no-record", "--seed", "7", "host", date], "quit\n");
+        assert_eq!(output.lines().last().unwrap(), format!(r#"{{"answer":"{}"}}"#, goal));
+    }
+}
+
+#[test]
+fn assist_widens_to_allowed_words() {
+    // Only acnes, which isn't a goal, fits.
+    let played = "crane ybyyy\ntoils bbbbg\nacnes ggggg\n";
+    for flags in [&["--quiet"][..], &["--quiet", "--verify", "--guarantee"]] {
+        let output = run_with(&[flags, &["assist"]].concat(), played);
+        assert!(output.contains("any allowed word might be the answer; 1 does"), "{}", output);
+        assert!(output.contains("solved in 3 guesses"), "{}", output);
+    }
+    let output = run_with(&["--quiet", "assist"], "crane bbbbb\ncrane ggggb\n");
+    assert!(output.contains("no words fit; taking back any of guesses 1, 2 would fix it"), "{}", output);
+}